@@ -0,0 +1,45 @@
+//! Parsing time of reports with growing event tables
+//!
+//! Run with `cargo bench`. The time per row should stay about the same for
+//! every table size.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const HEADER: &str = "Destruction of ground vehicles and fleets";
+
+/// A report whose ground vehicle table has `rows` rows
+fn report(rows: u32) -> String {
+    let template = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+    let start = template.find(HEADER).unwrap();
+    let end = start + template[start..].find("\n\n").unwrap() + 1;
+
+    let mut table = format!(
+        "{HEADER}     {rows}    {} SL     {} RP    \n",
+        rows * 1010,
+        rows * 77
+    );
+    for row in 0..rows {
+        table.push_str(&format!(
+            "    {}:{:02}     Concept 3          M6A1            1010 SL    77 RP\n",
+            row / 60,
+            row % 60
+        ));
+    }
+
+    format!("{}{table}{}", &template[..start], &template[end..])
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for rows in [100, 300, 600] {
+        let input = report(rows);
+        group.throughput(Throughput::Elements(rows.into()));
+        group.bench_with_input(BenchmarkId::from_parameter(rows), &input, |b, input| {
+            b.iter(|| wt_battle_report::from_str(input).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -0,0 +1,143 @@
+#ifndef WT_BATTLE_REPORT_H
+#define WT_BATTLE_REPORT_H
+
+/* Generated by cbindgen, rerun the tests with UPDATE_FFI_HEADER=1 to update. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The report was written as JSON
+ */
+#define WTBR_OK 0
+
+/**
+ * A path is null or not valid UTF-8
+ */
+#define WTBR_INVALID_PATH 1
+
+/**
+ * The report could not be read or parsed
+ */
+#define WTBR_PARSE_ERROR 2
+
+/**
+ * The JSON could not be written
+ */
+#define WTBR_WRITE_ERROR 3
+
+/**
+ * [`wt_report_result`] of a won battle
+ */
+#define WT_WIN 0
+
+/**
+ * [`wt_report_result`] of a lost battle
+ */
+#define WT_LOSS 1
+
+/**
+ * [`wt_report_result`] of a draw
+ */
+#define WT_DRAW 2
+
+/**
+ * [`wt_report_result`] of a report that does not state the result
+ */
+#define WT_UNKNOWN 3
+
+/**
+ * A parsed report, opaque to C
+ *
+ * Created by [`wt_parse_report`] and released with [`wt_report_free`].
+ */
+typedef struct WtBattleReport WtBattleReport;
+
+
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parse the `len` bytes at `input` as a report
+ *
+ * The input is decoded like in [`from_slice`], so it does not need to be
+ * NUL-terminated or UTF-8. Returns null if the report cannot be parsed, the
+ * reason is then available from [`wt_parse_error_message`]. Release the
+ * report with [`wt_report_free`].
+ *
+ * # Safety
+ *
+ * `input` must be null or point to `len` readable bytes.
+ */
+struct WtBattleReport *wt_parse_report(const char *input, uintptr_t len);
+
+/**
+ * The reason the last [`wt_parse_report`] on this thread failed
+ *
+ * Returns null if it succeeded or was never called. The message stays
+ * valid until the next call to [`wt_parse_report`] on the same thread.
+ */
+const char *wt_parse_error_message(void);
+
+/**
+ * The session ID of `report` as a NUL-terminated string
+ *
+ * Returns null if `report` is null. The string is owned by the report and
+ * freed with it.
+ *
+ * # Safety
+ *
+ * `report` must be null or returned by [`wt_parse_report`] and not freed.
+ */
+const char *wt_report_session_id(const struct WtBattleReport *report);
+
+/**
+ * The result of the battle, one of the `WT_*` result constants
+ *
+ * Returns -1 if `report` is null.
+ *
+ * # Safety
+ *
+ * `report` must be null or returned by [`wt_parse_report`] and not freed.
+ */
+int wt_report_result(const struct WtBattleReport *report);
+
+/**
+ * Release a report returned by [`wt_parse_report`]
+ *
+ * Does nothing if `report` is null.
+ *
+ * # Safety
+ *
+ * `report` must be null or returned by [`wt_parse_report`] and not freed
+ * before.
+ */
+void wt_report_free(struct WtBattleReport *report);
+
+/**
+ * Parse the report at `in_path` and write it as JSON to `out_path`
+ *
+ * Both paths are NUL-terminated UTF-8, also on Windows, where they are
+ * handed to the wide (UTF-16) file APIs. Encode them as UTF-8 instead of in
+ * the ANSI code page to reach any file name.
+ *
+ * Returns [`WTBR_OK`] and removes a stale error file on success. Otherwise
+ * the error message is written to `out_path` with `.err` appended and one
+ * of the other `WTBR_*` codes is returned. Nothing is written if `out_path`
+ * itself is invalid.
+ *
+ * # Safety
+ *
+ * Both pointers must be null or point to NUL-terminated strings.
+ */
+int wtbr_parse_file_to_json(const char *in_path, const char *out_path);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* WT_BATTLE_REPORT_H */
@@ -1,35 +1,455 @@
 //! Battle Report Value
 
-use std::str::FromStr;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::Sum,
+    ops::{Add, AddAssign},
+    str::FromStr,
+};
 
-use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+use crate::{builder::BattleReportBuilder, nation::Nation};
+
+/// A parsed battle report
+///
+/// Reports hash by their session ID alone, since two reports of the same
+/// session describe the same battle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BattleReport {
     pub session_id: String,
+    /// When the battle started, if the report states it next to the session
+    #[cfg(feature = "timestamps")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::datetime::option_naive")
+    )]
+    pub session_time: Option<crate::NaiveDateTime>,
     pub result: BattleResult,
     pub mission_name: String,
+    pub player_name: Option<String>,
+    pub squadron: Option<String>,
 
     pub events: Vec<Event>,
+    /// Headers of the tables `events` were listed in, in order
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub event_tables: Vec<EventTable>,
 
     pub awards: Vec<Award>,
+    /// Number of awards stated in the header of the awards table
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub awards_header_count: usize,
+    /// Total stated in the header of the awards table, without "Other
+    /// awards" unlike [`Self::awards_total`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::awards_header_total")
+    )]
+    pub awards_header_total: Reward,
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::reward_for_winning::option")
+    )]
     pub reward_for_winning: Option<Reward>,
+    /// Bonus for the first victory of the day, `None` if the report has none
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::first_victory_bonus::option")
+    )]
+    pub first_victory_bonus: Option<Reward>,
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::other_awards")
+    )]
     pub other_awards: Reward,
+    /// Battle tasks and challenges completed in the battle, empty when the
+    /// report lists none
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub completed_tasks: Vec<CompletedTask>,
+    /// Stages of active wagers completed in the battle, empty when the
+    /// report lists none
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub wagers: Vec<WagerProgress>,
 
     pub vehicles: Vec<Vehicle>,
+    /// Crew experience per vehicle, empty when the report lists none
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub crew_experience: Vec<CrewExperience>,
 
+    /// Activity over the whole battle in percent, from the `Activity:` line
+    ///
+    /// Rounded to whole percent, see [`Self::activity_basis_points`]. The
+    /// activity in each vehicle is [`Vehicle::activity`].
     pub activity: u8,
+    /// Activity over the whole battle in hundredths of a percent, like 9750
+    /// for `97,5 %`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub activity_basis_points: u16,
 
     pub damaged_vehicles: Vec<String>,
     pub automatic_repair: u32,
     pub automatic_purchases: u32,
     pub vehicle_research: Vec<VehicleResearch>,
     pub modification_research: Vec<ModificationResearch>,
+    /// Points the battle added to the squadron's research of a squadron
+    /// vehicle, which reports only state when playing in a squadron
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub squadron_research: Option<u32>,
+    /// Consumables used in the battle, empty when the report lists none
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub used_items: Vec<UsedItem>,
 
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::earned_rewards")
+    )]
     pub earned_rewards: Reward,
+    /// Research converted to CRP automatically, which reports state for
+    /// premium vehicles
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub converted_research: Option<u32>,
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::balance")
+    )]
     pub balance: Reward,
 }
 
+impl BattleReport {
+    /// Start building a report, see [`BattleReportBuilder`]
+    pub fn builder() -> BattleReportBuilder {
+        BattleReportBuilder::new()
+    }
+
+    /// The session ID, or an ID derived from the report if it has none
+    ///
+    /// Reports parsed leniently from a paste without the session line have
+    /// an empty session ID. They get `derived-` followed by the
+    /// [`fingerprint`](Self::fingerprint) in hex instead, so they still have
+    /// a stable key.
+    pub fn effective_session_id(&self) -> Cow<'_, str> {
+        if self.session_id.is_empty() {
+            Cow::Owned(format!("derived-{:016x}", self.fingerprint()))
+        } else {
+            Cow::Borrowed(&self.session_id)
+        }
+    }
+
+    /// A hash of the rendered report
+    ///
+    /// Unlike [`Hash`] it covers every field, and it is the same for the
+    /// same report across runs and builds.
+    pub fn fingerprint(&self) -> u64 {
+        // 64-bit FNV-1a
+        self.to_string()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+            })
+    }
+
+    /// Duration of the battle in seconds
+    ///
+    /// Reports do not state how long a battle lasted, so this is the time of
+    /// the last event or award. Returns `None` if the report has neither.
+    pub fn battle_duration(&self) -> Option<u32> {
+        self.events
+            .iter()
+            .map(|event| event.time)
+            .chain(self.awards.iter().map(|award| award.time))
+            .max()
+    }
+
+    /// Same as [`Self::battle_duration`]
+    ///
+    /// Timestamps in reports are `m:ss`, or `h:mm:ss` for long battles, and
+    /// are parsed into seconds.
+    pub fn battle_duration_seconds(&self) -> Option<u32> {
+        self.battle_duration()
+    }
+
+    /// [`Self::battle_duration`] in whole minutes, rounded down
+    pub fn battle_duration_minutes(&self) -> Option<u32> {
+        self.battle_duration().map(|seconds| seconds / 60)
+    }
+
+    /// Every event table with its rows
+    ///
+    /// Reports that were not parsed, like ones loaded from JSON written
+    /// before tables were kept, have no tables and yield nothing.
+    pub fn events_by_table(&self) -> impl Iterator<Item = (&EventTable, &[Event])> {
+        let mut rest = self.events.as_slice();
+        self.event_tables.iter().map(move |table| {
+            let (rows, tail) = rest.split_at(table.count.min(rest.len()));
+            rest = tail;
+            (table, rows)
+        })
+    }
+
+    /// Number of enemies the player destroyed
+    pub fn total_kills(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|event| event.kind.is_destruction())
+            .count()
+    }
+
+    /// Number of times the player destroyed `enemy`
+    ///
+    /// Names are compared after [`normalize_vehicle_name`].
+    pub fn kills_against(&self, enemy: &str) -> usize {
+        let enemy = normalize_vehicle_name(enemy);
+        self.events
+            .iter()
+            .filter(|event| event.kind.is_destruction())
+            .filter(|event| {
+                event
+                    .enemy
+                    .as_deref()
+                    .is_some_and(|name| normalize_vehicle_name(name) == enemy)
+            })
+            .count()
+    }
+
+    /// Events of the vehicle named exactly `vehicle`
+    pub fn events_for_vehicle<'a>(&'a self, vehicle: &'a str) -> impl Iterator<Item = &'a Event> {
+        self.events
+            .iter()
+            .filter(move |event| event.vehicle == vehicle)
+    }
+
+    /// Events in which `name` is either the player's vehicle or the enemy
+    ///
+    /// Names are compared after [`normalize_vehicle_name`].
+    pub fn events_involving<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Event> {
+        let name = normalize_vehicle_name(name);
+        self.events.iter().filter(move |event| {
+            normalize_vehicle_name(&event.vehicle) == name
+                || event
+                    .enemy
+                    .as_deref()
+                    .is_some_and(|enemy| normalize_vehicle_name(enemy) == name)
+        })
+    }
+
+    /// Events listed in the table of `kind`
+    pub fn events_of_kind(&self, kind: EventKind) -> impl Iterator<Item = &Event> {
+        self.events.iter().filter(move |event| event.kind == kind)
+    }
+
+    /// Query the events, see [`EventFilter`]
+    pub fn filter_events(&self) -> EventFilter<'_> {
+        EventFilter::new(&self.events)
+    }
+
+    /// Cost of the automatic repair as a negative amount of silver lions
+    pub fn repair_cost(&self) -> i64 {
+        -i64::from(self.automatic_repair)
+    }
+
+    /// Cost of the automatic ammo and crew purchases as a negative amount of
+    /// silver lions
+    pub fn purchase_cost(&self) -> i64 {
+        -i64::from(self.automatic_purchases)
+    }
+
+    /// Silver lions earned plus the (negative) repair and purchase costs
+    ///
+    /// This is the silver lions amount of the total line.
+    pub fn net_silverlions(&self) -> i64 {
+        i64::from(self.earned_rewards.silverlions()) + self.repair_cost() + self.purchase_cost()
+    }
+
+    /// Research points on the total line per minute of [`Self::battle_duration`]
+    ///
+    /// Returns zero if the duration is unknown.
+    pub fn rp_per_minute(&self) -> f64 {
+        match self.battle_duration() {
+            Some(seconds) if seconds > 0 => {
+                f64::from(self.balance.research()) * 60.0 / f64::from(seconds)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// The vehicle named `name`, if the player used it
+    pub fn vehicle_by_name(&self, name: &str) -> Option<&Vehicle> {
+        self.vehicles.iter().find(|vehicle| vehicle.name == name)
+    }
+
+    /// Research points the unit named `name` received
+    pub fn research_for_vehicle(&self, name: &str) -> Option<&VehicleResearch> {
+        self.vehicle_research
+            .iter()
+            .find(|research| research.name == name)
+    }
+
+    /// Every modification of the vehicle named `name` that received research
+    pub fn modification_research_for_vehicle(&self, name: &str) -> Vec<&ModificationResearch> {
+        self.modification_research
+            .iter()
+            .filter(|research| research.vehicle == name)
+            .collect()
+    }
+
+    /// Reward earned from awards, including "Other awards"
+    pub fn awards_total(&self) -> Reward {
+        self.awards
+            .iter()
+            .map(|award| award.reward.clone())
+            .sum::<Reward>()
+            + self.other_awards.clone()
+    }
+
+    /// Reward of events and vehicles summed by the nation of the vehicle
+    ///
+    /// Awards are not tied to a vehicle and are left out.
+    pub fn reward_by_nation(&self) -> HashMap<Nation, Reward> {
+        let mut rewards = HashMap::<Nation, Reward>::new();
+        let earned = self
+            .events
+            .iter()
+            .map(|event| (&event.vehicle, &event.reward))
+            .chain(
+                self.vehicles
+                    .iter()
+                    .map(|vehicle| (&vehicle.name, &vehicle.reward)),
+            );
+        for (vehicle, reward) in earned {
+            *rewards.entry(Nation::of_vehicle(vehicle)).or_default() += reward.clone();
+        }
+        rewards
+    }
+}
+
+/// The sections of a report that parsed before an error
+///
+/// A section is `None` if the parser did not get to it. Sections that may be
+/// missing from a report, like the vehicle research, are `Some` and empty if
+/// the parser got past them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PartialBattleReport {
+    pub session_id: Option<String>,
+    #[cfg(feature = "timestamps")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::datetime::option_naive")
+    )]
+    pub session_time: Option<crate::NaiveDateTime>,
+    pub result: Option<BattleResult>,
+    pub mission_name: Option<String>,
+    pub player_name: Option<String>,
+    pub squadron: Option<String>,
+
+    pub events: Option<Vec<Event>>,
+    pub event_tables: Option<Vec<EventTable>>,
+
+    pub awards: Option<Vec<Award>>,
+    pub awards_header_count: Option<usize>,
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::awards_header_total::option")
+    )]
+    pub awards_header_total: Option<Reward>,
+    /// `None` if the report has no reward for winning, too
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::reward_for_winning::option")
+    )]
+    pub reward_for_winning: Option<Reward>,
+    /// `None` if the report has no first victory bonus, too
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::first_victory_bonus::option")
+    )]
+    pub first_victory_bonus: Option<Reward>,
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::other_awards::option")
+    )]
+    pub other_awards: Option<Reward>,
+    pub completed_tasks: Option<Vec<CompletedTask>>,
+    pub wagers: Option<Vec<WagerProgress>>,
+
+    pub vehicles: Option<Vec<Vehicle>>,
+    pub crew_experience: Option<Vec<CrewExperience>>,
+
+    pub activity: Option<u8>,
+    pub activity_basis_points: Option<u16>,
+
+    pub damaged_vehicles: Option<Vec<String>>,
+    pub automatic_repair: Option<u32>,
+    pub automatic_purchases: Option<u32>,
+    pub vehicle_research: Option<Vec<VehicleResearch>>,
+    pub modification_research: Option<Vec<ModificationResearch>>,
+    pub squadron_research: Option<u32>,
+    pub used_items: Option<Vec<UsedItem>>,
+
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::earned_rewards::option")
+    )]
+    pub earned_rewards: Option<Reward>,
+    pub converted_research: Option<u32>,
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::balance::option")
+    )]
+    pub balance: Option<Reward>,
+}
+
+impl PartialBattleReport {
+    /// The complete report, if every section parsed
+    pub fn into_report(self) -> Option<BattleReport> {
+        Some(BattleReport {
+            session_id: self.session_id?,
+            #[cfg(feature = "timestamps")]
+            session_time: self.session_time,
+            result: self.result?,
+            mission_name: self.mission_name?,
+            player_name: self.player_name,
+            squadron: self.squadron,
+            events: self.events?,
+            event_tables: self.event_tables?,
+            awards: self.awards?,
+            awards_header_count: self.awards_header_count?,
+            awards_header_total: self.awards_header_total?,
+            reward_for_winning: self.reward_for_winning,
+            first_victory_bonus: self.first_victory_bonus,
+            other_awards: self.other_awards?,
+            completed_tasks: self.completed_tasks?,
+            wagers: self.wagers?,
+            vehicles: self.vehicles?,
+            crew_experience: self.crew_experience?,
+            activity: self.activity?,
+            activity_basis_points: self.activity_basis_points?,
+            damaged_vehicles: self.damaged_vehicles?,
+            automatic_repair: self.automatic_repair?,
+            automatic_purchases: self.automatic_purchases?,
+            vehicle_research: self.vehicle_research?,
+            modification_research: self.modification_research?,
+            squadron_research: self.squadron_research,
+            used_items: self.used_items?,
+            earned_rewards: self.earned_rewards?,
+            converted_research: self.converted_research,
+            balance: self.balance?,
+        })
+    }
+}
+
+impl Hash for BattleReport {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.session_id.hash(state);
+    }
+}
+
 impl FromStr for BattleReport {
     type Err = crate::parser::Error;
 
@@ -38,52 +458,1094 @@ impl FromStr for BattleReport {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum BattleResult {
     Win,
     Loss,
+    Draw,
+    /// The report did not state the result, see [`ParseOptions::lenient`](crate::ParseOptions::lenient)
+    Unknown,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Event {
+    /// Seconds since the start of the battle
     pub time: u32,
-    pub kind: String,
+    pub kind: EventKind,
     pub vehicle: String,
+    /// `None` for [`EventKind::AllyAssistance`], whose row names the ally
     pub enemy: Option<String>,
+    /// The allied vehicle the player helped in an
+    /// [`EventKind::AllyAssistance`] event
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ally: Option<String>,
+    /// The row is marked with `×`, an enemy destroyed by allies after the
+    /// player damaged it
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub assisted: bool,
+    #[cfg_attr(feature = "flat-rewards", serde(flatten, with = "crate::flat::reward"))]
     pub reward: Reward,
 }
 
-#[derive(Debug, Clone, Serialize, Default)]
+impl Event {
+    /// An event without an enemy or reward
+    pub fn new(time: u32, kind: EventKind, vehicle: impl Into<String>) -> Self {
+        Self {
+            time,
+            kind,
+            vehicle: vehicle.into(),
+            enemy: None,
+            ally: None,
+            assisted: false,
+            reward: Reward::default(),
+        }
+    }
+
+    pub fn with_enemy(self, enemy: impl Into<String>) -> Self {
+        Self {
+            enemy: Some(enemy.into()),
+            ..self
+        }
+    }
+
+    pub fn with_ally(self, ally: impl Into<String>) -> Self {
+        Self {
+            ally: Some(ally.into()),
+            ..self
+        }
+    }
+
+    pub fn with_assisted(self, assisted: bool) -> Self {
+        Self { assisted, ..self }
+    }
+
+    pub fn with_reward(self, reward: Reward) -> Self {
+        Self { reward, ..self }
+    }
+}
+
+/// The header of an event table
+///
+/// The rows of the table are the next `count` entries of
+/// [`BattleReport::events`], see [`BattleReport::events_by_table`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EventTable {
+    /// Name of the table as an English report states it
+    ///
+    /// Several names share a kind, like `Resupply of allies` and `Help with
+    /// repairs`, so this keeps the one the report used.
+    pub name: String,
+    pub kind: EventKind,
+    /// Rows listed in the table, without rows repeated by the game
+    pub count: usize,
+    /// The total stated in the header, which may differ from the sum of
+    /// the rows by rounding
+    #[cfg_attr(feature = "flat-rewards", serde(flatten, with = "crate::flat::total"))]
+    pub total: Reward,
+}
+
+/// A lazy query over the events of a report
+///
+/// Every condition narrows the query further, setting one again replaces
+/// it. Iterate the filter to get the matching events in report order.
+///
+/// ```
+/// use wt_battle_report::EventKind;
+///
+/// let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+/// let report = wt_battle_report::from_str(&input).unwrap();
+/// let scouted = report
+///     .filter_events()
+///     .by_kind(EventKind::Scouting)
+///     .after_time(5 * 60)
+///     .collect::<Vec<_>>();
+/// assert_eq!(scouted.len(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventFilter<'a> {
+    events: std::slice::Iter<'a, Event>,
+    kind: Option<EventKind>,
+    vehicle: Option<&'a str>,
+    before: Option<u32>,
+    after: Option<u32>,
+    min_sl: Option<u32>,
+}
+
+impl<'a> EventFilter<'a> {
+    /// A query matching every event in `events`
+    pub fn new(events: &'a [Event]) -> Self {
+        Self {
+            events: events.iter(),
+            kind: None,
+            vehicle: None,
+            before: None,
+            after: None,
+            min_sl: None,
+        }
+    }
+
+    /// Events listed in the table of `kind`
+    pub fn by_kind(self, kind: EventKind) -> Self {
+        Self {
+            kind: Some(kind),
+            ..self
+        }
+    }
+
+    /// Events of the vehicle named exactly `vehicle`
+    pub fn by_vehicle(self, vehicle: &'a str) -> Self {
+        Self {
+            vehicle: Some(vehicle),
+            ..self
+        }
+    }
+
+    /// Events earlier than `time` seconds into the battle
+    pub fn before_time(self, time: u32) -> Self {
+        Self {
+            before: Some(time),
+            ..self
+        }
+    }
+
+    /// Events later than `time` seconds into the battle
+    pub fn after_time(self, time: u32) -> Self {
+        Self {
+            after: Some(time),
+            ..self
+        }
+    }
+
+    /// Events that paid at least `silverlions`, including bonuses
+    pub fn with_min_sl(self, silverlions: u32) -> Self {
+        Self {
+            min_sl: Some(silverlions),
+            ..self
+        }
+    }
+
+    fn matches(&self, event: &Event) -> bool {
+        self.kind.as_ref().is_none_or(|kind| event.kind == *kind)
+            && self.vehicle.is_none_or(|vehicle| event.vehicle == vehicle)
+            && self.before.is_none_or(|time| event.time < time)
+            && self.after.is_none_or(|time| event.time > time)
+            && self
+                .min_sl
+                .is_none_or(|min| event.reward.silverlions() >= min)
+    }
+}
+
+impl<'a> Iterator for EventFilter<'a> {
+    type Item = &'a Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let event = self.events.next()?;
+            if self.matches(event) {
+                return Some(event);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.events.size_hint().1)
+    }
+}
+
+impl FromStr for BattleResult {
+    type Err = ParseBattleResultError;
+
+    /// Parse a result as written in a report ("Victory") or as serialized ("win")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "victory" | "win" => Ok(Self::Win),
+            "defeat" | "loss" => Ok(Self::Loss),
+            "draw" => Ok(Self::Draw),
+            "unknown" => Ok(Self::Unknown),
+            _ => Err(ParseBattleResultError {
+                input: s.to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown battle result: {input:?}")]
+pub struct ParseBattleResultError {
+    input: String,
+}
+
+/// The table an [`Event`] was listed in
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EventKind {
+    DestructionOfAircraft,
+    DestructionOfGroundVehicles,
+    DestructionOfFleets,
+    Assistance,
+    CriticalDamage,
+    SevereDamage,
+    Damage,
+    Scouting,
+    DamageToScoutedEnemy,
+    DestructionOfScoutedEnemy,
+    CaptureOfZones,
+    DamageToBase,
+    AttackOfBase,
+    DestructionOfBase,
+    DestructionOfStrategicObjects,
+    /// Repairing, resupplying or extinguishing an allied vehicle, see
+    /// [`Event::ally`]
+    AllyAssistance,
+    /// A table this crate does not know about yet
+    Other(String),
+}
+
+impl EventKind {
+    /// Look up the kind for a table name as it appears in the report
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "Destruction of aircraft" => Self::DestructionOfAircraft,
+            "Destruction of ground vehicles and fleets" => Self::DestructionOfGroundVehicles,
+            "Destruction of enemy fleets" => Self::DestructionOfFleets,
+            "Assistance in destroying the enemy" => Self::Assistance,
+            "Critical damage to the enemy" | "Critical damage" => Self::CriticalDamage,
+            "Severe damage to the enemy" | "Severe damage to aircraft" => Self::SevereDamage,
+            "Damage to the enemy" => Self::Damage,
+            "Scouting of the enemy" => Self::Scouting,
+            "Damage taken by scouted enemies" => Self::DamageToScoutedEnemy,
+            "Destruction by allies of scouted enemies" => Self::DestructionOfScoutedEnemy,
+            "Capture of zones" | "Capture of the zone" => Self::CaptureOfZones,
+            "Damage to the base" => Self::DamageToBase,
+            "Attack of the base" => Self::AttackOfBase,
+            "Destruction of the base" => Self::DestructionOfBase,
+            "Destruction of strategic objects" => Self::DestructionOfStrategicObjects,
+            "Help with repairs" | "Resupply of allies" | "Extinguishing allies" => {
+                Self::AllyAssistance
+            }
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    /// The usual table name of the kind in English reports
+    ///
+    /// Kinds with several table names return one of them, the name of a
+    /// parsed table is [`EventTable::name`].
+    pub fn name(&self) -> &str {
+        match self {
+            Self::DestructionOfAircraft => "Destruction of aircraft",
+            Self::DestructionOfGroundVehicles => "Destruction of ground vehicles and fleets",
+            Self::DestructionOfFleets => "Destruction of enemy fleets",
+            Self::Assistance => "Assistance in destroying the enemy",
+            Self::CriticalDamage => "Critical damage to the enemy",
+            Self::SevereDamage => "Severe damage to aircraft",
+            Self::Damage => "Damage to the enemy",
+            Self::Scouting => "Scouting of the enemy",
+            Self::DamageToScoutedEnemy => "Damage taken by scouted enemies",
+            Self::DestructionOfScoutedEnemy => "Destruction by allies of scouted enemies",
+            Self::CaptureOfZones => "Capture of zones",
+            Self::DamageToBase => "Damage to the base",
+            Self::AttackOfBase => "Attack of the base",
+            Self::DestructionOfBase => "Destruction of the base",
+            Self::DestructionOfStrategicObjects => "Destruction of strategic objects",
+            Self::AllyAssistance => "Help with repairs",
+            Self::Other(name) => name,
+        }
+    }
+
+    /// The player destroyed the enemy in this event
+    pub fn is_destruction(&self) -> bool {
+        matches!(
+            self,
+            Self::DestructionOfAircraft
+                | Self::DestructionOfGroundVehicles
+                | Self::DestructionOfFleets
+        )
+    }
+
+    /// The player damaged the enemy in this event without destroying it
+    pub fn is_damage(&self) -> bool {
+        matches!(
+            self,
+            Self::CriticalDamage | Self::SevereDamage | Self::Damage
+        )
+    }
+
+    /// The player spotted the enemy in this event
+    pub fn is_scouting(&self) -> bool {
+        matches!(self, Self::Scouting)
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Normalize a vehicle name for comparisons
+///
+/// Reports sometimes append an empty `()` to enemy vehicle names, so
+/// `T-34 (1942)()` and `T-34 (1942)` name the same vehicle.
+pub fn normalize_vehicle_name(name: &str) -> &str {
+    let name = name.trim();
+    name.strip_suffix("()").unwrap_or(name).trim_end()
+}
+
+/// Silver lions and research points earned
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Reward {
-    pub silverlions: u32,
-    pub research: u32,
+    pub sl: RewardBreakdown,
+    pub rp: RewardBreakdown,
+    /// Squadron research points (SRP), zero unless the player played in a
+    /// squadron
+    pub squadron_research: u32,
+    /// Amounts in currencies without a field of their own, like war bonds
+    /// (`WB`) or event currencies, by their unit or name
+    pub other_currencies: Vec<(String, u32)>,
+}
+
+impl Reward {
+    /// A reward without bonuses
+    pub fn new(silverlions: u32, research: u32) -> Self {
+        Self {
+            sl: silverlions.into(),
+            rp: research.into(),
+            squadron_research: 0,
+            other_currencies: Vec::new(),
+        }
+    }
+
+    pub fn with_squadron_research(mut self, squadron_research: u32) -> Self {
+        self.squadron_research = squadron_research;
+        self
+    }
+
+    pub fn with_other_currency(mut self, currency: impl Into<String>, amount: u32) -> Self {
+        self.other_currencies.push((currency.into(), amount));
+        self
+    }
+
+    /// Amount in the currency with the unit or name `currency`, zero if the
+    /// reward has none
+    pub fn other_currency(&self, currency: &str) -> u32 {
+        self.other_currencies
+            .iter()
+            .filter(|(name, _)| name == currency)
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    /// Total silver lions, including bonuses
+    pub fn silverlions(&self) -> u32 {
+        self.sl.total
+    }
+
+    /// Total research points, including bonuses
+    pub fn research(&self) -> u32 {
+        self.rp.total
+    }
+
+    /// `true` if nothing was earned in any currency
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.silverlions() == 0
+            && self.research() == 0
+            && self.squadron_research == 0
+            && self.other_currencies.iter().all(|(_, amount)| *amount == 0)
+    }
+
+    #[inline]
+    pub fn is_nonzero(&self) -> bool {
+        !self.is_zero()
+    }
+
+    /// `true` if no research points were earned, like for scouting
+    #[inline]
+    pub fn only_sl(&self) -> bool {
+        self.research() == 0
+    }
+
+    /// `true` if no silver lions were earned
+    #[inline]
+    pub fn only_rp(&self) -> bool {
+        self.silverlions() == 0
+    }
+}
+
+impl Add for Reward {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for Reward {
+    fn add_assign(&mut self, rhs: Self) {
+        self.sl += rhs.sl;
+        self.rp += rhs.rp;
+        self.squadron_research += rhs.squadron_research;
+        for (currency, amount) in rhs.other_currencies {
+            match self
+                .other_currencies
+                .iter_mut()
+                .find(|(name, _)| *name == currency)
+            {
+                Some((_, sum)) => *sum += amount,
+                None => self.other_currencies.push((currency, amount)),
+            }
+        }
+    }
+}
+
+impl Sum for Reward {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// An amount split into its base value and the bonuses added to it
+///
+/// Reports list bonuses like `10 + (PA)10 + (Booster)10 = 30 RP`. Amounts
+/// without bonuses have an empty `bonuses` list and `base == total`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct RewardBreakdown {
+    pub base: u32,
+    pub bonuses: Vec<(BonusKind, u32)>,
+    pub total: u32,
+}
+
+impl RewardBreakdown {
+    /// Sum of the bonuses of `kind`
+    pub fn bonus(&self, kind: &BonusKind) -> u32 {
+        self.bonuses
+            .iter()
+            .filter(|(bonus, _)| bonus == kind)
+            .map(|(_, value)| value)
+            .sum()
+    }
+}
+
+impl From<u32> for RewardBreakdown {
+    fn from(total: u32) -> Self {
+        Self {
+            base: total,
+            bonuses: Vec::new(),
+            total,
+        }
+    }
+}
+
+impl AddAssign for RewardBreakdown {
+    /// Adds the bases and totals, merging bonuses of the same kind
+    fn add_assign(&mut self, rhs: Self) {
+        self.base += rhs.base;
+        self.total += rhs.total;
+        for (kind, value) in rhs.bonuses {
+            match self.bonuses.iter_mut().find(|(bonus, _)| *bonus == kind) {
+                Some((_, sum)) => *sum += value,
+                None => self.bonuses.push((kind, value)),
+            }
+        }
+    }
+}
+
+/// The source of a bonus in a [`RewardBreakdown`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BonusKind {
+    PremiumAccount,
+    Booster,
+    Talisman,
+    /// A bonus label this crate does not know about yet
+    Unknown(String),
+}
+
+impl BonusKind {
+    /// Look up the kind for a bonus label as it appears in the report
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "PA" => Self::PremiumAccount,
+            "Booster" => Self::Booster,
+            "Talismans" | "Talisman" => Self::Talisman,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+
+    /// The bonus label as it appears in the report
+    pub fn name(&self) -> &str {
+        match self {
+            Self::PremiumAccount => "PA",
+            Self::Booster => "Booster",
+            Self::Talisman => "Talismans",
+            Self::Unknown(name) => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vehicle {
     pub name: String,
+    /// Activity in this vehicle in percent, from the time played table
+    ///
+    /// Zero for a vehicle without a time played row, which only lenient
+    /// parsing accepts.
     pub activity: u8,
+    /// Seconds spent in this vehicle, zero without a time played row
     pub time_played: u32,
+    #[cfg_attr(feature = "flat-rewards", serde(flatten, with = "crate::flat::reward"))]
     pub reward: Reward,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl Vehicle {
+    /// A vehicle that was not played and earned nothing
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            activity: 0,
+            time_played: 0,
+            reward: Reward::default(),
+        }
+    }
+
+    pub fn with_activity(self, activity: u8) -> Self {
+        Self { activity, ..self }
+    }
+
+    pub fn with_time_played(self, time_played: u32) -> Self {
+        Self {
+            time_played,
+            ..self
+        }
+    }
+
+    pub fn with_reward(self, reward: Reward) -> Self {
+        Self { reward, ..self }
+    }
+
+    /// Number of enemies this vehicle destroyed in `events`, usually
+    /// [`BattleReport::events`]
+    pub fn kills(&self, events: &[Event]) -> usize {
+        self.events(events)
+            .filter(|event| event.kind.is_destruction())
+            .count()
+    }
+
+    /// Silver lions this vehicle earned in `events`
+    ///
+    /// Unlike [`Self::reward`], this leaves out the activity and awards.
+    pub fn sl_earned_from_events(&self, events: &[Event]) -> u32 {
+        self.events(events)
+            .map(|event| event.reward.silverlions())
+            .sum()
+    }
+
+    /// Research points this vehicle earned in `events`
+    ///
+    /// Unlike [`Self::reward`], this leaves out the time played and awards.
+    pub fn rp_earned_from_events(&self, events: &[Event]) -> u32 {
+        self.events(events)
+            .map(|event| event.reward.research())
+            .sum()
+    }
+
+    /// the events of this vehicle, compared by exact name like
+    /// [`BattleReport::events_for_vehicle`]
+    fn events<'a>(&'a self, events: &'a [Event]) -> impl Iterator<Item = &'a Event> {
+        events.iter().filter(|event| event.vehicle == self.name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct VehicleResearch {
     pub name: String,
     pub research: u32,
+    /// Research progress of the unit, if the report shows it
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub progress_percent: Option<u8>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ModificationResearch {
     pub vehicle: String,
     pub name: String,
     pub research: u32,
+    /// Convertible research points, zero when the line does not list any
+    pub convertible_research: u32,
+}
+
+/// Experience the crew of a vehicle gained, listed under "Crew experience"
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CrewExperience {
+    pub vehicle: String,
+    pub experience: u32,
+}
+
+/// A consumable listed under "Used items"
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UsedItem {
+    pub name: String,
+    pub count: u32,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Award {
+    /// Seconds since the start of the battle
     pub time: u32,
     pub name: String,
+    #[cfg_attr(feature = "flat-rewards", serde(flatten, with = "crate::flat::reward"))]
+    pub reward: Reward,
+    /// Golden eagles in the cell after the reward, zero when the row has none
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub golden_eagles: u32,
+}
+
+/// A battle task or challenge completed in the battle
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CompletedTask {
+    /// Name of the task without the quotes around it
+    pub name: String,
+    #[cfg_attr(feature = "flat-rewards", serde(flatten, with = "crate::flat::reward"))]
     pub reward: Reward,
+    /// Convertible research points awarded, zero when the line lists none
+    ///
+    /// They are not part of the research points of [`Self::reward`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub convertible_research: u32,
+    /// Golden eagles awarded, zero when the line lists none
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub golden_eagles: u32,
+}
+
+/// A completed stage or the final payout of a wager
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WagerProgress {
+    /// Name of the wager without the quotes around it
+    pub name: String,
+    /// The completed stage, `None` for the final payout
+    pub stage: Option<u32>,
+    /// Number of stages of the wager, `None` for the final payout
+    pub stages_total: Option<u32>,
+    #[cfg_attr(feature = "flat-rewards", serde(flatten, with = "crate::flat::reward"))]
+    pub reward: Reward,
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use crate::*;
+
+    fn report(path: &str) -> BattleReport {
+        let input = std::fs::read_to_string(path).unwrap();
+        from_str(&input).unwrap()
+    }
+
+    #[test]
+    fn effective_session_id() {
+        let report = report("./data/1603c1c00028a36.report");
+        assert_eq!(report.effective_session_id(), "1603c1c00028a36");
+
+        let input = std::fs::read_to_string("./data/lenient/missing-session.report").unwrap();
+        let parse = |input: &str| {
+            from_str_with_options(input, ParseOptions::lenient())
+                .unwrap()
+                .0
+        };
+        let derived = parse(&input).effective_session_id().into_owned();
+        assert!(derived.starts_with("derived-"), "{derived}");
+        assert_eq!(derived.len(), "derived-".len() + 16);
+        assert_eq!(parse(&input).effective_session_id(), derived);
+
+        let other = input.replace("Activity: 95%", "Activity: 94%");
+        assert_ne!(parse(&other).effective_session_id(), derived);
+    }
+
+    #[test]
+    fn battle_duration_of_real_report() {
+        let report = report("./data/1603c1c00028a36.report");
+        assert_eq!(report.battle_duration(), Some(13 * 60 + 55));
+        assert_eq!(report.battle_duration_seconds(), Some(13 * 60 + 55));
+        assert_eq!(report.battle_duration_minutes(), Some(13));
+    }
+
+    #[test]
+    fn kill_and_earning_helpers() {
+        let report = report("./data/1712a4f0003b2c5.report");
+        assert_eq!(report.total_kills(), 2);
+        assert_eq!(report.kills_against("Fletcher"), 1);
+        assert_eq!(report.kills_against("Fletcher()"), 1);
+        assert_eq!(report.kills_against("Porter"), 1);
+        assert_eq!(report.kills_against("Base"), 0);
+        assert_eq!(report.repair_cost(), -2650);
+        assert_eq!(report.purchase_cost(), -400);
+        assert_eq!(report.net_silverlions(), 5765);
+        assert_eq!(
+            report.net_silverlions(),
+            i64::from(report.balance.silverlions())
+        );
+        // 1194 RP in 12:30
+        assert!((report.rp_per_minute() - 95.52).abs() < 1e-9);
+    }
+
+    #[test]
+    fn vehicle_event_helpers() {
+        let report = report("./data/1603c1c00028a36.report");
+        let vehicle = |name| report.vehicle_by_name(name).unwrap();
+        let concept = vehicle("Concept 3");
+        assert_eq!(concept.kills(&report.events), 3);
+        assert_eq!(vehicle("Sherman Firefly").kills(&report.events), 3);
+        assert_eq!(vehicle("Wyvern S4").kills(&report.events), 1);
+        assert_eq!(concept.kills(&[]), 0);
+
+        // the activity and time played tables credit it with 730 SL and 748 RP
+        assert_eq!(concept.reward, Reward::new(730, 68 + 680));
+        assert_eq!(concept.sl_earned_from_events(&report.events), 4750);
+        assert_eq!(concept.rp_earned_from_events(&report.events), 337);
+    }
+
+    #[test]
+    fn filter_events() {
+        let report = report("./data/1603c1c00028a36.report");
+        assert_eq!(report.events_for_vehicle("Concept 3").count(), 15);
+        assert_eq!(report.events_for_vehicle("Concept").count(), 0);
+
+        let kills = report
+            .events_for_vehicle("Concept 3")
+            .filter(|event| event.kind.is_destruction())
+            .filter_map(|event| event.enemy.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(kills, ["M6A1", "ISU-122()", "Chi-To Late"]);
+
+        let scouted = report
+            .events_of_kind(EventKind::Scouting)
+            .map(|event| (event.time, event.enemy.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scouted,
+            [
+                (2 * 60 + 5, Some("M36 GMC()")),
+                (3 * 60 + 4, Some("M36 GMC()")),
+                (5 * 60 + 56, Some("Chi-To Late")),
+                (6 * 60 + 25, Some("M6A1")),
+                (6 * 60 + 51, Some("ISU-122()")),
+            ]
+        );
+        assert_eq!(report.events_of_kind(EventKind::CaptureOfZones).count(), 0);
+    }
+
+    #[test]
+    fn events_involving_either_side() {
+        let report = report("./data/1603c1c00028a36.report");
+        let times = |name| {
+            report
+                .events_involving(name)
+                .map(|event| event.time)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(times("KV-85"), [13 * 60 + 43, 13 * 60 + 35]);
+        assert_eq!(times("ISU-122"), [8 * 60 + 17, 7 * 60 + 29, 6 * 60 + 51]);
+        assert_eq!(times("ISU-122()"), times("ISU-122"));
+        assert_eq!(
+            report.events_involving("Sherman Firefly").count(),
+            report.events_for_vehicle("Sherman Firefly").count()
+        );
+        assert_eq!(report.events_involving("KV-8").count(), 0);
+    }
+
+    #[test]
+    fn filter_events_by_conditions() {
+        let report = report("./data/1603c1c00028a36.report");
+        assert_eq!(report.filter_events().count(), report.events.len());
+
+        let times = |filter: EventFilter<'_>| filter.map(|event| event.time).collect::<Vec<_>>();
+        assert_eq!(
+            times(
+                report
+                    .filter_events()
+                    .by_vehicle("Sherman Firefly")
+                    .by_kind(EventKind::DestructionOfGroundVehicles)
+                    .after_time(11 * 60 + 47)
+            ),
+            [13 * 60 + 14, 13 * 60 + 43]
+        );
+        assert_eq!(
+            times(
+                report
+                    .filter_events()
+                    .by_kind(EventKind::CriticalDamage)
+                    .before_time(8 * 60)
+                    .with_min_sl(100)
+            ),
+            [2 * 60 + 27, 6 * 60 + 29, 7 * 60 + 29]
+        );
+        assert_eq!(
+            report
+                .filter_events()
+                .with_min_sl(1000)
+                .map(|event| event.vehicle.as_str())
+                .collect::<Vec<_>>(),
+            ["Wyvern S4", "Concept 3", "Concept 3", "Concept 3"]
+        );
+        assert_eq!(report.filter_events().by_vehicle("Concept").count(), 0);
+    }
+
+    #[test]
+    fn lookup_by_vehicle_name() {
+        let mut report = report("./data/160409b0002a1af.report");
+        assert_eq!(
+            report
+                .vehicle_by_name("T-34 (1940)")
+                .map(|vehicle| vehicle.time_played),
+            Some(6 * 60 + 43)
+        );
+        assert_eq!(report.vehicle_by_name("T-34 (1941)"), None);
+        assert_eq!(
+            report
+                .research_for_vehicle("T-34 (1941)")
+                .map(|research| research.research),
+            Some(1191)
+        );
+        assert_eq!(report.research_for_vehicle("T-34 (1940)"), None);
+
+        report.modification_research.push(ModificationResearch {
+            vehicle: "YaG-10 (29-K)".to_string(),
+            name: "Tracks".to_string(),
+            research: 80,
+            convertible_research: 0,
+        });
+        let names = report
+            .modification_research_for_vehicle("YaG-10 (29-K)")
+            .into_iter()
+            .map(|research| research.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Improved Parts", "Tracks"]);
+        assert!(report
+            .modification_research_for_vehicle("KV-1 (L-11)")
+            .is_empty());
+    }
+
+    #[test]
+    fn awards_total_includes_other_awards() {
+        let report = report("./data/1603c1c00028a36.report");
+        let total = report.awards_total();
+        assert_eq!(total.silverlions(), 3450 + 5295);
+        assert_eq!(total.research(), 100 + 115);
+    }
+
+    #[rstest]
+    #[case("Victory", BattleResult::Win)]
+    #[case("defeat", BattleResult::Loss)]
+    #[case("DRAW", BattleResult::Draw)]
+    #[case("win", BattleResult::Win)]
+    #[case("loss", BattleResult::Loss)]
+    fn parse_battle_result(#[case] input: &str, #[case] expected: BattleResult) {
+        assert_eq!(input.parse::<BattleResult>(), Ok(expected));
+    }
+
+    #[test]
+    fn parse_unknown_battle_result() {
+        assert!("Stalemate".parse::<BattleResult>().is_err());
+    }
+
+    #[rstest]
+    #[case(Reward::new(0, 0), true, true, true)]
+    #[case(Reward::new(51, 0), false, true, false)]
+    #[case(Reward::new(0, 10), false, false, true)]
+    #[case(Reward::new(1010, 77), false, false, false)]
+    #[case(Reward::new(0, 0).with_squadron_research(50), false, true, true)]
+    fn classify_reward(
+        #[case] reward: Reward,
+        #[case] zero: bool,
+        #[case] only_sl: bool,
+        #[case] only_rp: bool,
+    ) {
+        assert_eq!(reward.is_zero(), zero);
+        assert_eq!(reward.is_nonzero(), !zero);
+        assert_eq!(reward.only_sl(), only_sl);
+        assert_eq!(reward.only_rp(), only_rp);
+    }
+
+    #[cfg(all(feature = "serde", not(feature = "flat-rewards")))]
+    #[test]
+    fn event_reward_is_nested() {
+        use serde_json::json;
+
+        let event = Event::new(252, EventKind::DestructionOfFleets, "Z-25")
+            .with_enemy("Fletcher")
+            .with_reward(Reward::new(1630, 98));
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "time": 252,
+                "kind": "DestructionOfFleets",
+                "vehicle": "Z-25",
+                "enemy": "Fletcher",
+                "ally": null,
+                "assisted": false,
+                "reward": {
+                    "sl": { "base": 1630, "bonuses": [], "total": 1630 },
+                    "rp": { "base": 98, "bonuses": [], "total": 98 },
+                    "squadron_research": 0,
+                    "other_currencies": [],
+                },
+            })
+        );
+        assert_eq!(serde_json::from_value::<Event>(value).unwrap(), event);
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case(BattleResult::Win)]
+    #[case(BattleResult::Loss)]
+    #[case(BattleResult::Draw)]
+    #[case(BattleResult::Unknown)]
+    fn battle_result_json_round_trip(#[case] result: BattleResult) {
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(serde_json::from_str::<BattleResult>(&json).unwrap(), result);
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case("./data/1603c1c00028a36.report")]
+    #[case("./data/1703c87000d01c0-items.report")]
+    #[case("./data/1703c87000d01c0-squadron.report")]
+    #[case("./data/de/1603c1c00028a36.report")]
+    fn battle_report_json_round_trip(#[case] path: &str) {
+        let report = report(path);
+        let json = serde_json::to_string(&report).unwrap();
+        let deserialized = serde_json::from_str::<BattleReport>(&json).unwrap();
+        assert_eq!(deserialized, report);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn missing_reward_fields_default_to_zero() {
+        let reward = serde_json::from_str::<Reward>(r#"{"sl": {"total": 10}}"#).unwrap();
+        assert_eq!(reward.silverlions(), 10);
+        assert_eq!(reward.research(), 0);
+    }
+
+    #[test]
+    fn event_kind_round_trips_names() {
+        let report = report("./data/1603c1c00028a36.report");
+        for event in &report.events {
+            assert!(!matches!(event.kind, EventKind::Other(_)));
+            assert_eq!(EventKind::from_name(event.kind.name()), event.kind);
+        }
+    }
+
+    #[test]
+    fn naval_event_kinds() {
+        let report = report("./data/1712a4f0003b2c5.report");
+        let kinds = report
+            .events
+            .iter()
+            .map(|event| event.kind.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                EventKind::DestructionOfFleets,
+                EventKind::DestructionOfFleets,
+                EventKind::Damage,
+                EventKind::Damage,
+                EventKind::Damage,
+                EventKind::DamageToBase,
+                EventKind::AttackOfBase,
+                EventKind::CaptureOfZones,
+            ]
+        );
+    }
+
+    #[test]
+    fn air_event_kinds() {
+        let report = report("./data/1714b3e0001c7a2.report");
+        let kinds = report
+            .events
+            .iter()
+            .map(|event| event.kind.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                EventKind::DestructionOfAircraft,
+                EventKind::DestructionOfAircraft,
+                EventKind::SevereDamage,
+                EventKind::CriticalDamage,
+                EventKind::DestructionOfBase,
+                EventKind::DestructionOfStrategicObjects,
+            ]
+        );
+        assert_eq!(report.events[0].enemy.as_deref(), Some("Spitfire F Mk IX"));
+        assert_eq!(report.events[4].enemy, None);
+        assert_eq!(report.events[5].enemy.as_deref(), Some("Pillbox"));
+    }
+
+    #[test]
+    fn reports_hash_by_session_id() {
+        use std::{
+            collections::HashSet,
+            hash::{BuildHasher, RandomState},
+        };
+
+        let original = report("./data/1603c1c00028a36.report");
+        let mut edited = original.clone();
+        edited.mission_name.clear();
+
+        let hasher = RandomState::new();
+        assert_ne!(original, edited);
+        assert_eq!(hasher.hash_one(&original), hasher.hash_one(&edited));
+
+        let reports = [original.clone(), original, edited]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(reports.len(), 2);
+    }
+
+    #[test]
+    fn reward_by_nation_of_british_lineup() {
+        let report = report("./data/1603c1c00028a36.report");
+        let rewards = report.reward_by_nation();
+        assert_eq!(rewards.len(), 1);
+        // events 12655 SL 786 RP, activity 3152 SL 160 RP, time played 1057 RP
+        let britain = &rewards[&Nation::Britain];
+        assert_eq!((britain.silverlions(), britain.research()), (15807, 2003));
+    }
+
+    #[test]
+    fn normalize_vehicle_names() {
+        assert_eq!(normalize_vehicle_name("ISU-122()"), "ISU-122");
+        assert_eq!(normalize_vehicle_name("T-34 (1942)()"), "T-34 (1942)");
+        assert_eq!(normalize_vehicle_name("T-34 (1942)"), "T-34 (1942)");
+        assert_eq!(normalize_vehicle_name("M36 GMC() "), "M36 GMC");
+    }
+
+    #[test]
+    fn battle_duration_without_events() {
+        let mut report = report("./data/1603c1c00028a36.report");
+        report.events.clear();
+        report.awards.clear();
+        assert_eq!(report.battle_duration(), None);
+        assert_eq!(report.battle_duration_minutes(), None);
+    }
 }
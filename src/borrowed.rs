@@ -0,0 +1,357 @@
+//! Borrowed Battle Report Value
+//!
+//! These types mirror the ones in [`crate::battle_report`], but their names
+//! are slices of the parsed input instead of owned strings. Parsing into them
+//! with [`crate::from_str_borrowed`] saves an allocation for every name in a
+//! report, which adds up when processing thousands of reports.
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{
+    Award, BattleReport, BattleResult, CompletedTask, CrewExperience, Event, EventKind, EventTable,
+    ModificationResearch, Reward, UsedItem, Vehicle, VehicleResearch, WagerProgress,
+};
+
+/// A parsed battle report borrowing from its input
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct BattleReportRef<'a> {
+    pub session_id: &'a str,
+    #[cfg(feature = "timestamps")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::datetime::option_naive::serialize")
+    )]
+    pub session_time: Option<crate::NaiveDateTime>,
+    pub result: BattleResult,
+    pub mission_name: &'a str,
+    pub player_name: Option<&'a str>,
+    pub squadron: Option<&'a str>,
+
+    pub events: Vec<EventRef<'a>>,
+    pub event_tables: Vec<EventTable>,
+
+    pub awards: Vec<AwardRef<'a>>,
+    pub awards_header_count: usize,
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::awards_header_total")
+    )]
+    pub awards_header_total: Reward,
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::reward_for_winning::option")
+    )]
+    pub reward_for_winning: Option<Reward>,
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::first_victory_bonus::option")
+    )]
+    pub first_victory_bonus: Option<Reward>,
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::other_awards")
+    )]
+    pub other_awards: Reward,
+    pub completed_tasks: Vec<CompletedTaskRef<'a>>,
+    pub wagers: Vec<WagerProgressRef<'a>>,
+
+    pub vehicles: Vec<VehicleRef<'a>>,
+    pub crew_experience: Vec<CrewExperienceRef<'a>>,
+
+    pub activity: u8,
+    pub activity_basis_points: u16,
+
+    pub damaged_vehicles: Vec<&'a str>,
+    pub automatic_repair: u32,
+    pub automatic_purchases: u32,
+    pub vehicle_research: Vec<VehicleResearchRef<'a>>,
+    pub modification_research: Vec<ModificationResearchRef<'a>>,
+    pub squadron_research: Option<u32>,
+    pub used_items: Vec<UsedItemRef<'a>>,
+
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::earned_rewards")
+    )]
+    pub earned_rewards: Reward,
+    pub converted_research: Option<u32>,
+    #[cfg_attr(
+        feature = "flat-rewards",
+        serde(flatten, with = "crate::flat::balance")
+    )]
+    pub balance: Reward,
+}
+
+impl BattleReportRef<'_> {
+    /// Copy the report into a [`BattleReport`]
+    pub fn to_owned(&self) -> BattleReport {
+        BattleReport {
+            session_id: self.session_id.to_string(),
+            #[cfg(feature = "timestamps")]
+            session_time: self.session_time,
+            result: self.result,
+            mission_name: self.mission_name.to_string(),
+            player_name: self.player_name.map(str::to_string),
+            squadron: self.squadron.map(str::to_string),
+            events: self.events.iter().map(EventRef::to_owned).collect(),
+            event_tables: self.event_tables.clone(),
+            awards: self.awards.iter().map(AwardRef::to_owned).collect(),
+            awards_header_count: self.awards_header_count,
+            awards_header_total: self.awards_header_total.clone(),
+            reward_for_winning: self.reward_for_winning.clone(),
+            first_victory_bonus: self.first_victory_bonus.clone(),
+            other_awards: self.other_awards.clone(),
+            completed_tasks: self
+                .completed_tasks
+                .iter()
+                .map(CompletedTaskRef::to_owned)
+                .collect(),
+            wagers: self.wagers.iter().map(WagerProgressRef::to_owned).collect(),
+            vehicles: self.vehicles.iter().map(VehicleRef::to_owned).collect(),
+            crew_experience: self
+                .crew_experience
+                .iter()
+                .map(CrewExperienceRef::to_owned)
+                .collect(),
+            activity: self.activity,
+            activity_basis_points: self.activity_basis_points,
+            damaged_vehicles: self
+                .damaged_vehicles
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            automatic_repair: self.automatic_repair,
+            automatic_purchases: self.automatic_purchases,
+            vehicle_research: self
+                .vehicle_research
+                .iter()
+                .map(VehicleResearchRef::to_owned)
+                .collect(),
+            modification_research: self
+                .modification_research
+                .iter()
+                .map(ModificationResearchRef::to_owned)
+                .collect(),
+            squadron_research: self.squadron_research,
+            used_items: self.used_items.iter().map(UsedItemRef::to_owned).collect(),
+            earned_rewards: self.earned_rewards.clone(),
+            converted_research: self.converted_research,
+            balance: self.balance.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct EventRef<'a> {
+    /// Seconds since the start of the battle
+    pub time: u32,
+    pub kind: EventKind,
+    pub vehicle: &'a str,
+    pub enemy: Option<&'a str>,
+    pub ally: Option<&'a str>,
+    pub assisted: bool,
+    #[cfg_attr(feature = "flat-rewards", serde(flatten, with = "crate::flat::reward"))]
+    pub reward: Reward,
+}
+
+impl EventRef<'_> {
+    pub fn to_owned(&self) -> Event {
+        Event {
+            time: self.time,
+            kind: self.kind.clone(),
+            vehicle: self.vehicle.to_string(),
+            enemy: self.enemy.map(str::to_string),
+            ally: self.ally.map(str::to_string),
+            assisted: self.assisted,
+            reward: self.reward.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct AwardRef<'a> {
+    /// Seconds since the start of the battle
+    pub time: u32,
+    pub name: &'a str,
+    #[cfg_attr(feature = "flat-rewards", serde(flatten, with = "crate::flat::reward"))]
+    pub reward: Reward,
+    pub golden_eagles: u32,
+}
+
+impl AwardRef<'_> {
+    pub fn to_owned(&self) -> Award {
+        Award {
+            time: self.time,
+            name: self.name.to_string(),
+            reward: self.reward.clone(),
+            golden_eagles: self.golden_eagles,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct CompletedTaskRef<'a> {
+    pub name: &'a str,
+    #[cfg_attr(feature = "flat-rewards", serde(flatten, with = "crate::flat::reward"))]
+    pub reward: Reward,
+    pub convertible_research: u32,
+    pub golden_eagles: u32,
+}
+
+impl CompletedTaskRef<'_> {
+    pub fn to_owned(&self) -> CompletedTask {
+        CompletedTask {
+            name: self.name.to_string(),
+            reward: self.reward.clone(),
+            convertible_research: self.convertible_research,
+            golden_eagles: self.golden_eagles,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct WagerProgressRef<'a> {
+    pub name: &'a str,
+    pub stage: Option<u32>,
+    pub stages_total: Option<u32>,
+    #[cfg_attr(feature = "flat-rewards", serde(flatten, with = "crate::flat::reward"))]
+    pub reward: Reward,
+}
+
+impl WagerProgressRef<'_> {
+    pub fn to_owned(&self) -> WagerProgress {
+        WagerProgress {
+            name: self.name.to_string(),
+            stage: self.stage,
+            stages_total: self.stages_total,
+            reward: self.reward.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct VehicleRef<'a> {
+    pub name: &'a str,
+    /// Activity in this vehicle in percent, from the time played table
+    ///
+    /// Zero for a vehicle without a time played row, which only lenient
+    /// parsing accepts.
+    pub activity: u8,
+    /// Seconds spent in this vehicle, zero without a time played row
+    pub time_played: u32,
+    #[cfg_attr(feature = "flat-rewards", serde(flatten, with = "crate::flat::reward"))]
+    pub reward: Reward,
+}
+
+impl VehicleRef<'_> {
+    pub fn to_owned(&self) -> Vehicle {
+        Vehicle {
+            name: self.name.to_string(),
+            activity: self.activity,
+            time_played: self.time_played,
+            reward: self.reward.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct VehicleResearchRef<'a> {
+    pub name: &'a str,
+    pub research: u32,
+    /// Research progress of the unit, if the report shows it
+    pub progress_percent: Option<u8>,
+}
+
+impl VehicleResearchRef<'_> {
+    pub fn to_owned(&self) -> VehicleResearch {
+        VehicleResearch {
+            name: self.name.to_string(),
+            research: self.research,
+            progress_percent: self.progress_percent,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ModificationResearchRef<'a> {
+    pub vehicle: &'a str,
+    pub name: &'a str,
+    pub research: u32,
+    /// Convertible research points, zero when the line does not list any
+    pub convertible_research: u32,
+}
+
+impl ModificationResearchRef<'_> {
+    pub fn to_owned(&self) -> ModificationResearch {
+        ModificationResearch {
+            vehicle: self.vehicle.to_string(),
+            name: self.name.to_string(),
+            research: self.research,
+            convertible_research: self.convertible_research,
+        }
+    }
+}
+
+/// Experience the crew of a vehicle gained, listed under "Crew experience"
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct CrewExperienceRef<'a> {
+    pub vehicle: &'a str,
+    pub experience: u32,
+}
+
+impl CrewExperienceRef<'_> {
+    pub fn to_owned(&self) -> CrewExperience {
+        CrewExperience {
+            vehicle: self.vehicle.to_string(),
+            experience: self.experience,
+        }
+    }
+}
+
+/// A consumable listed under "Used items"
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct UsedItemRef<'a> {
+    pub name: &'a str,
+    pub count: u32,
+}
+
+impl UsedItemRef<'_> {
+    pub fn to_owned(&self) -> UsedItem {
+        UsedItem {
+            name: self.name.to_string(),
+            count: self.count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn borrowed_reports_match_owned_reports() {
+        for entry in std::fs::read_dir("./data").unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                continue;
+            }
+            let input = std::fs::read_to_string(&path).unwrap();
+            let borrowed = crate::from_str_borrowed(&input).unwrap();
+            assert_eq!(
+                borrowed.to_owned(),
+                crate::from_str(&input).unwrap(),
+                "{}",
+                path.display()
+            );
+        }
+    }
+}
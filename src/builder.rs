@@ -0,0 +1,389 @@
+//! Battle Report Builder
+//!
+//! Assembles a [`BattleReport`] section by section, for tests and for tools
+//! that make up reports instead of parsing them.
+
+use crate::{
+    Award, BattleReport, BattleResult, CompletedTask, CrewExperience, Event, EventTable,
+    ModificationResearch, PartialBattleReport, Reward, UsedItem, Vehicle, VehicleResearch,
+    WagerProgress,
+};
+
+/// A [`BattleReport`] under construction
+///
+/// Every section but the session ID and the battle result defaults to
+/// empty or zero. Event tables default to one table per run of events of the
+/// same kind, totalling its rows, and likewise for the awards table header.
+///
+/// ```
+/// use wt_battle_report::{BattleReport, BattleResult, Event, EventKind, Reward};
+///
+/// let report = BattleReport::builder()
+///     .with_session_id("1712a4f0003b2c5")
+///     .with_result(BattleResult::Win)
+///     .add_event(
+///         Event::new(252, EventKind::DestructionOfFleets, "Z-25")
+///             .with_enemy("Fletcher")
+///             .with_reward(Reward::new(1630, 98)),
+///     )
+///     .build()
+///     .unwrap();
+/// assert_eq!(report.total_kills(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BattleReportBuilder {
+    report: PartialBattleReport,
+}
+
+/// A required section was not set on a [`BattleReportBuilder`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Battle report is missing its {field}")]
+pub struct MissingFieldError {
+    pub field: &'static str,
+}
+
+impl BattleReportBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.report.session_id = Some(session_id.into());
+        self
+    }
+
+    #[cfg(feature = "timestamps")]
+    pub fn with_session_time(mut self, session_time: crate::NaiveDateTime) -> Self {
+        self.report.session_time = Some(session_time);
+        self
+    }
+
+    pub fn with_result(mut self, result: BattleResult) -> Self {
+        self.report.result = Some(result);
+        self
+    }
+
+    pub fn with_mission_name(mut self, mission_name: impl Into<String>) -> Self {
+        self.report.mission_name = Some(mission_name.into());
+        self
+    }
+
+    pub fn with_player_name(mut self, player_name: impl Into<String>) -> Self {
+        self.report.player_name = Some(player_name.into());
+        self
+    }
+
+    pub fn with_squadron(mut self, squadron: impl Into<String>) -> Self {
+        self.report.squadron = Some(squadron.into());
+        self
+    }
+
+    pub fn with_events(mut self, events: Vec<Event>) -> Self {
+        self.report.events = Some(events);
+        self
+    }
+
+    pub fn add_event(mut self, event: Event) -> Self {
+        self.report.events.get_or_insert_with(Vec::new).push(event);
+        self
+    }
+
+    pub fn with_event_tables(mut self, event_tables: Vec<EventTable>) -> Self {
+        self.report.event_tables = Some(event_tables);
+        self
+    }
+
+    pub fn with_awards(mut self, awards: Vec<Award>) -> Self {
+        self.report.awards = Some(awards);
+        self
+    }
+
+    pub fn add_award(mut self, award: Award) -> Self {
+        self.report.awards.get_or_insert_with(Vec::new).push(award);
+        self
+    }
+
+    /// Set the row count and total of the awards table header
+    pub fn with_awards_header(mut self, count: usize, total: Reward) -> Self {
+        self.report.awards_header_count = Some(count);
+        self.report.awards_header_total = Some(total);
+        self
+    }
+
+    pub fn with_reward_for_winning(mut self, reward: Reward) -> Self {
+        self.report.reward_for_winning = Some(reward);
+        self
+    }
+
+    pub fn with_first_victory_bonus(mut self, reward: Reward) -> Self {
+        self.report.first_victory_bonus = Some(reward);
+        self
+    }
+
+    pub fn with_other_awards(mut self, reward: Reward) -> Self {
+        self.report.other_awards = Some(reward);
+        self
+    }
+
+    pub fn with_completed_tasks(mut self, completed_tasks: Vec<CompletedTask>) -> Self {
+        self.report.completed_tasks = Some(completed_tasks);
+        self
+    }
+
+    pub fn with_wagers(mut self, wagers: Vec<WagerProgress>) -> Self {
+        self.report.wagers = Some(wagers);
+        self
+    }
+
+    pub fn with_vehicles(mut self, vehicles: Vec<Vehicle>) -> Self {
+        self.report.vehicles = Some(vehicles);
+        self
+    }
+
+    pub fn add_vehicle(mut self, vehicle: Vehicle) -> Self {
+        self.report
+            .vehicles
+            .get_or_insert_with(Vec::new)
+            .push(vehicle);
+        self
+    }
+
+    pub fn with_crew_experience(mut self, crew_experience: Vec<CrewExperience>) -> Self {
+        self.report.crew_experience = Some(crew_experience);
+        self
+    }
+
+    pub fn with_activity(mut self, activity: u8) -> Self {
+        self.report.activity = Some(activity);
+        self.report.activity_basis_points = Some(u16::from(activity) * 100);
+        self
+    }
+
+    /// Set the activity in hundredths of a percent, and the rounded
+    /// [`BattleReport::activity`] with it
+    pub fn with_activity_basis_points(mut self, basis_points: u16) -> Self {
+        self.report.activity = Some(crate::parser::whole_percent(basis_points));
+        self.report.activity_basis_points = Some(basis_points);
+        self
+    }
+
+    pub fn with_damaged_vehicles(mut self, damaged_vehicles: Vec<String>) -> Self {
+        self.report.damaged_vehicles = Some(damaged_vehicles);
+        self
+    }
+
+    pub fn with_automatic_repair(mut self, automatic_repair: u32) -> Self {
+        self.report.automatic_repair = Some(automatic_repair);
+        self
+    }
+
+    pub fn with_automatic_purchases(mut self, automatic_purchases: u32) -> Self {
+        self.report.automatic_purchases = Some(automatic_purchases);
+        self
+    }
+
+    pub fn with_vehicle_research(mut self, vehicle_research: Vec<VehicleResearch>) -> Self {
+        self.report.vehicle_research = Some(vehicle_research);
+        self
+    }
+
+    pub fn with_modification_research(
+        mut self,
+        modification_research: Vec<ModificationResearch>,
+    ) -> Self {
+        self.report.modification_research = Some(modification_research);
+        self
+    }
+
+    pub fn with_squadron_research(mut self, squadron_research: u32) -> Self {
+        self.report.squadron_research = Some(squadron_research);
+        self
+    }
+
+    pub fn with_used_items(mut self, used_items: Vec<UsedItem>) -> Self {
+        self.report.used_items = Some(used_items);
+        self
+    }
+
+    pub fn with_earned_rewards(mut self, earned_rewards: Reward) -> Self {
+        self.report.earned_rewards = Some(earned_rewards);
+        self
+    }
+
+    pub fn with_converted_research(mut self, converted_research: u32) -> Self {
+        self.report.converted_research = Some(converted_research);
+        self
+    }
+
+    pub fn with_balance(mut self, balance: Reward) -> Self {
+        self.report.balance = Some(balance);
+        self
+    }
+
+    /// Finish the report, failing if the session ID or result is missing
+    pub fn build(self) -> Result<BattleReport, MissingFieldError> {
+        let report = self.report;
+        let events = report.events.unwrap_or_default();
+        let awards = report.awards.unwrap_or_default();
+        let event_tables = report
+            .event_tables
+            .unwrap_or_else(|| default_tables(&events));
+        Ok(BattleReport {
+            session_id: report.session_id.ok_or(MissingFieldError {
+                field: "session ID",
+            })?,
+            #[cfg(feature = "timestamps")]
+            session_time: report.session_time,
+            result: report.result.ok_or(MissingFieldError { field: "result" })?,
+            mission_name: report.mission_name.unwrap_or_default(),
+            player_name: report.player_name,
+            squadron: report.squadron,
+            events,
+            event_tables,
+            awards_header_count: report.awards_header_count.unwrap_or(awards.len()),
+            awards_header_total: report
+                .awards_header_total
+                .unwrap_or_else(|| plain_total(awards.iter().map(|award| &award.reward))),
+            awards,
+            reward_for_winning: report.reward_for_winning,
+            first_victory_bonus: report.first_victory_bonus,
+            other_awards: report.other_awards.unwrap_or_default(),
+            completed_tasks: report.completed_tasks.unwrap_or_default(),
+            wagers: report.wagers.unwrap_or_default(),
+            vehicles: report.vehicles.unwrap_or_default(),
+            crew_experience: report.crew_experience.unwrap_or_default(),
+            activity: report.activity.unwrap_or_default(),
+            activity_basis_points: report.activity_basis_points.unwrap_or_default(),
+            damaged_vehicles: report.damaged_vehicles.unwrap_or_default(),
+            automatic_repair: report.automatic_repair.unwrap_or_default(),
+            automatic_purchases: report.automatic_purchases.unwrap_or_default(),
+            vehicle_research: report.vehicle_research.unwrap_or_default(),
+            modification_research: report.modification_research.unwrap_or_default(),
+            squadron_research: report.squadron_research,
+            used_items: report.used_items.unwrap_or_default(),
+            earned_rewards: report.earned_rewards.unwrap_or_default(),
+            converted_research: report.converted_research,
+            balance: report.balance.unwrap_or_default(),
+        })
+    }
+}
+
+/// one table per run of events of the same kind
+fn default_tables(events: &[Event]) -> Vec<EventTable> {
+    events
+        .chunk_by(|a, b| a.kind == b.kind)
+        .map(|events| EventTable {
+            name: events[0].kind.name().to_string(),
+            kind: events[0].kind.clone(),
+            count: events.len(),
+            total: plain_total(events.iter().map(|event| &event.reward)),
+        })
+        .collect()
+}
+
+/// the sum of `rewards` without bonuses, like table headers state it
+fn plain_total<'a>(rewards: impl Iterator<Item = &'a Reward>) -> Reward {
+    let total = rewards.cloned().sum::<Reward>();
+    Reward::new(total.silverlions(), total.research())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn build_requires_session_id_and_result() {
+        let err = BattleReport::builder()
+            .with_result(BattleResult::Win)
+            .build()
+            .unwrap_err();
+        assert_eq!(err.field, "session ID");
+
+        let err = BattleReport::builder()
+            .with_session_id("1712a4f0003b2c5")
+            .build()
+            .unwrap_err();
+        assert_eq!(err.field, "result");
+    }
+
+    #[test]
+    fn build_defaults_to_empty_sections() {
+        let report = BattleReport::builder()
+            .with_session_id("1712a4f0003b2c5")
+            .with_result(BattleResult::Draw)
+            .build()
+            .unwrap();
+        assert_eq!(report.mission_name, "");
+        assert_eq!(report.events, []);
+        assert_eq!(report.reward_for_winning, None);
+        assert_eq!(report.balance, Reward::default());
+    }
+
+    #[test]
+    fn built_report_matches_parsed_report() {
+        let input = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        let parsed = from_str(&input).unwrap();
+
+        let mut built = BattleReport::builder()
+            .with_session_id(&parsed.session_id)
+            .with_result(parsed.result)
+            .with_mission_name(&parsed.mission_name)
+            .with_events(parsed.events.clone())
+            .with_awards(parsed.awards.clone())
+            .with_reward_for_winning(Reward::new(2100, 0))
+            .with_other_awards(Reward::new(5, 0))
+            .add_vehicle(
+                Vehicle::new("Z-25")
+                    .with_activity(91)
+                    .with_time_played(12 * 60 + 5)
+                    .with_reward(Reward::new(650, 55 + 720)),
+            )
+            .with_activity(91)
+            .with_damaged_vehicles(vec!["Z-25".to_string()])
+            .with_automatic_repair(2650)
+            .with_automatic_purchases(400)
+            .with_earned_rewards(Reward::new(8815, 1194))
+            .with_balance(Reward::new(5765, 1194))
+            .build()
+            .unwrap();
+        // the builder names tables by their kind, the report calls the zone
+        // table "Capture of the zone"
+        for (built, parsed) in built.event_tables.iter_mut().zip(&parsed.event_tables) {
+            assert_eq!(built.name, built.kind.name());
+            built.name.clone_from(&parsed.name);
+        }
+        assert_eq!(built, parsed);
+    }
+
+    #[test]
+    fn built_report_renders_as_report_text() {
+        let report = BattleReport::builder()
+            .with_session_id("abc123")
+            .with_result(BattleResult::Loss)
+            .with_mission_name("[Domination] Poland")
+            .add_event(
+                Event::new(95, EventKind::DestructionOfGroundVehicles, "T-34 (1940)")
+                    .with_enemy("Pz.IV F2")
+                    .with_reward(Reward::new(1050, 58)),
+            )
+            .add_award(Award {
+                time: 95,
+                name: "Without a miss".to_string(),
+                reward: Reward::new(200, 0),
+                golden_eagles: 0,
+            })
+            .add_vehicle(
+                Vehicle::new("T-34 (1940)")
+                    .with_activity(80)
+                    .with_time_played(300)
+                    .with_reward(Reward::new(300, 120)),
+            )
+            .with_activity(80)
+            .with_damaged_vehicles(vec!["T-34 (1940)".to_string()])
+            .with_earned_rewards(Reward::new(1550, 178))
+            .with_balance(Reward::new(1550, 178))
+            .build()
+            .unwrap();
+        assert_eq!(from_str(&report.to_string()).unwrap(), report);
+    }
+}
@@ -0,0 +1,245 @@
+//! Date and Time Backends
+//!
+//! Timestamp APIs use either `chrono` or `time`, whichever feature is
+//! enabled. The rest of the crate only goes through the aliases and functions
+//! here, and both backends serialize to the same JSON.
+
+#[cfg(feature = "chrono")]
+mod backend {
+    use chrono::{Duration, Utc};
+
+    /// A date and time without a time zone, as stated in reports
+    pub type NaiveDateTime = chrono::NaiveDateTime;
+    /// A date and time in UTC
+    pub type UtcDateTime = chrono::DateTime<Utc>;
+
+    const SESSION_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+    pub fn parse_session_time(input: &str) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(input, SESSION_TIME_FORMAT).ok()
+    }
+
+    pub fn format_session_time(time: &NaiveDateTime) -> String {
+        time.format(SESSION_TIME_FORMAT).to_string()
+    }
+
+    pub fn add_seconds(start: UtcDateTime, seconds: u32) -> Option<UtcDateTime> {
+        start.checked_add_signed(Duration::seconds(i64::from(seconds)))
+    }
+
+    #[cfg(any(feature = "serde", test))]
+    pub fn naive_to_json(time: &NaiveDateTime) -> String {
+        time.format("%Y-%m-%dT%H:%M:%S%.f").to_string()
+    }
+
+    #[cfg(any(feature = "serde", test))]
+    pub fn naive_from_json(input: &str) -> Option<NaiveDateTime> {
+        input.parse().ok()
+    }
+
+    #[cfg(any(feature = "serde", test))]
+    pub fn utc_to_json(time: &UtcDateTime) -> String {
+        time.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+    }
+
+    #[cfg(test)]
+    pub fn naive(date: (i32, u32, u32), time: (u32, u32, u32)) -> NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .and_then(|date| date.and_hms_opt(time.0, time.1, time.2))
+            .unwrap()
+    }
+
+    #[cfg(test)]
+    pub fn utc(date: (i32, u32, u32), time: (u32, u32, u32)) -> UtcDateTime {
+        naive(date, time).and_utc()
+    }
+
+    #[cfg(test)]
+    pub const UTC_MAX: UtcDateTime = UtcDateTime::MAX_UTC;
+}
+
+// only `chrono` if both are enabled, to leave just the error in lib.rs
+#[cfg(all(feature = "time", not(feature = "chrono")))]
+mod backend {
+    use time::{format_description::BorrowedFormatItem, macros::format_description, Duration};
+
+    /// A date and time without a time zone, as stated in reports
+    pub type NaiveDateTime = time::PrimitiveDateTime;
+    /// A date and time, in UTC when the crate creates it
+    pub type UtcDateTime = time::OffsetDateTime;
+
+    const SESSION_TIME_FORMAT: &[BorrowedFormatItem<'_>] =
+        format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+    #[cfg(any(feature = "serde", test))]
+    const JSON_FORMAT: &[BorrowedFormatItem<'_>] = format_description!(
+        "[year]-[month]-[day]T[hour]:[minute]:[second][optional [.[subsecond]]]"
+    );
+
+    pub fn parse_session_time(input: &str) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse(input, SESSION_TIME_FORMAT).ok()
+    }
+
+    pub fn format_session_time(time: &NaiveDateTime) -> String {
+        time.format(SESSION_TIME_FORMAT)
+            .expect("every date and time has a year, month, day, hour, minute and second")
+    }
+
+    pub fn add_seconds(start: UtcDateTime, seconds: u32) -> Option<UtcDateTime> {
+        start.checked_add(Duration::seconds(i64::from(seconds)))
+    }
+
+    /// like the `%.f` of chrono, fractional seconds have 3, 6 or 9 digits
+    #[cfg(any(feature = "serde", test))]
+    pub fn naive_to_json(time: &NaiveDateTime) -> String {
+        let format = match time.nanosecond() {
+            0 => format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+            nanos if nanos % 1_000_000 == 0 => format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]"
+            ),
+            nanos if nanos % 1_000 == 0 => format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:6]"
+            ),
+            _ => format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:9]"
+            ),
+        };
+        time.format(format)
+            .expect("every date and time has a year, month, day, hour, minute and second")
+    }
+
+    #[cfg(any(feature = "serde", test))]
+    pub fn naive_from_json(input: &str) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse(input, JSON_FORMAT).ok()
+    }
+
+    #[cfg(any(feature = "serde", test))]
+    pub fn utc_to_json(time: &UtcDateTime) -> String {
+        time.format(&time::format_description::well_known::Rfc3339)
+            .expect("times of reports fall into the years RFC 3339 can express")
+    }
+
+    #[cfg(test)]
+    pub fn naive(date: (i32, u32, u32), time: (u32, u32, u32)) -> NaiveDateTime {
+        let month = time::Month::try_from(date.1 as u8).unwrap();
+        NaiveDateTime::new(
+            time::Date::from_calendar_date(date.0, month, date.2 as u8).unwrap(),
+            time::Time::from_hms(time.0 as u8, time.1 as u8, time.2 as u8).unwrap(),
+        )
+    }
+
+    #[cfg(test)]
+    pub fn utc(date: (i32, u32, u32), time: (u32, u32, u32)) -> UtcDateTime {
+        naive(date, time).assume_utc()
+    }
+
+    #[cfg(test)]
+    pub const UTC_MAX: UtcDateTime = NaiveDateTime::MAX.assume_utc();
+}
+
+pub(crate) use backend::{add_seconds, format_session_time, parse_session_time};
+#[cfg(test)]
+pub(crate) use backend::{naive, utc, UTC_MAX};
+pub use backend::{NaiveDateTime, UtcDateTime};
+
+/// `#[serde(with)]` for an optional [`NaiveDateTime`], like
+/// `2023-08-03T19:42:11`
+#[cfg(feature = "serde")]
+pub(crate) mod option_naive {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{backend, NaiveDateTime};
+
+    pub fn serialize<S: Serializer>(
+        time: &Option<NaiveDateTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        time.as_ref()
+            .map(backend::naive_to_json)
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<NaiveDateTime>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|time| {
+                backend::naive_from_json(&time)
+                    .ok_or_else(|| D::Error::custom(format!("invalid date and time {time:?}")))
+            })
+            .transpose()
+    }
+}
+
+/// `serialize_with` for a [`UtcDateTime`] in RFC 3339, like
+/// `2023-08-03T19:30:00Z`
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_utc<S: serde::Serializer>(
+    time: &UtcDateTime,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&backend::utc_to_json(time))
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn session_time_round_trip() {
+        let time = super::parse_session_time("2023-08-03 19:42:11").unwrap();
+        assert_eq!(time, super::naive((2023, 8, 3), (19, 42, 11)));
+        assert_eq!(super::format_session_time(&time), "2023-08-03 19:42:11");
+        assert_eq!(super::parse_session_time("2023-08-03"), None);
+    }
+
+    #[test]
+    fn add_seconds_checks_the_range() {
+        let start = super::utc((2023, 8, 3), (19, 30, 0));
+        assert_eq!(
+            super::add_seconds(start, 125),
+            Some(super::utc((2023, 8, 3), (19, 32, 5)))
+        );
+        assert_eq!(super::add_seconds(super::UTC_MAX, 1), None);
+    }
+
+    #[test]
+    fn json() {
+        let time = super::naive((2023, 8, 3), (19, 42, 11));
+        assert_eq!(super::backend::naive_to_json(&time), "2023-08-03T19:42:11");
+        assert_eq!(
+            super::backend::naive_from_json("2023-08-03T19:42:11"),
+            Some(time)
+        );
+        assert_eq!(super::backend::naive_from_json("2023-08-03 19:42:11"), None);
+        assert_eq!(
+            super::backend::utc_to_json(&super::utc((2023, 8, 3), (19, 42, 11))),
+            "2023-08-03T19:42:11Z"
+        );
+    }
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod chrono_test {
+    use chrono::Timelike;
+
+    #[test]
+    fn json_with_fractional_seconds() {
+        let time = super::naive((2023, 8, 3), (19, 42, 11))
+            .with_nanosecond(250_000_000)
+            .unwrap();
+        let json = super::backend::naive_to_json(&time);
+        assert_eq!(json, "2023-08-03T19:42:11.250");
+        assert_eq!(super::backend::naive_from_json(&json), Some(time));
+    }
+}
+
+#[cfg(all(test, feature = "time"))]
+mod time_test {
+    #[test]
+    fn json_with_fractional_seconds() {
+        let time = super::naive((2023, 8, 3), (19, 42, 11))
+            .replace_nanosecond(250_000_000)
+            .unwrap();
+        let json = super::backend::naive_to_json(&time);
+        assert_eq!(json, "2023-08-03T19:42:11.250");
+        assert_eq!(super::backend::naive_from_json(&json), Some(time));
+    }
+}
@@ -1,23 +1,636 @@
 //! Battle Report Deserialization
 
-use std::io;
+use std::{
+    borrow::Cow,
+    fs, io,
+    path::{Path, PathBuf},
+};
 
-use crate::{battle_report::BattleReport, parser};
+use crate::{
+    battle_report::{BattleReport, PartialBattleReport},
+    borrowed::BattleReportRef,
+    encoding::{self, Encoding},
+    language::{detect_language, Language},
+    parser,
+};
 
-pub use parser::Error;
+pub use parser::{Error, ParseOptions, SyntaxError, TableRow, ValidationIssue, Warning};
 
+/// Error reading a report from a reader or a file
+#[derive(Debug, thiserror::Error)]
+pub enum ReadError {
+    #[error("Error reading battle report: {0}")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Parse(#[from] parser::Error),
+}
+
+/// Error reading or parsing the report at `path`
+#[derive(Debug, thiserror::Error)]
+#[error("{}: {source}", path.display())]
+pub struct PathError {
+    pub path: PathBuf,
+    pub source: ReadError,
+}
+
+/// Parse a report
+///
+/// The language is guessed with [`detect_language`]. If parsing in that
+/// language fails, every other supported language is tried before giving up
+/// with the first error.
 pub fn from_str(input: &str) -> Result<BattleReport, parser::Error> {
-    parser::parse(input)
+    let detected = detect_language(input).unwrap_or_default();
+    parser::parse_with_language(input, detected).or_else(|err| {
+        Language::ALL
+            .into_iter()
+            .filter(|language| *language != detected)
+            .find_map(|language| parser::parse_with_language(input, language).ok())
+            .ok_or(err)
+    })
+}
+
+/// Parse a report written by a client set to `language`
+pub fn from_str_with_language(
+    input: &str,
+    language: Language,
+) -> Result<BattleReport, parser::Error> {
+    parser::parse_with_language(input, language)
 }
 
+/// Parse a report into names borrowed from `input`
+///
+/// The language is detected like in [`from_str`]. Call
+/// [`BattleReportRef::to_owned`] to get a [`BattleReport`].
+pub fn from_str_borrowed(input: &str) -> Result<BattleReportRef<'_>, parser::Error> {
+    parser::parse_borrowed(input, detect_language(input).unwrap_or_default())
+}
+
+/// Parse a table in the format of the report
+///
+/// The table starts with a header of its name, row count and total reward,
+/// followed by one indented row per line:
+///
+/// ```text
+/// Destruction of ground vehicles and fleets     2    1940 SL     135 RP
+///     7:13     Concept 3          M6A1            1010 SL    77 RP
+///     11:47    Sherman Firefly    T-34 (1942)     930 SL     58 RP
+/// ```
+///
+/// Every line ends with a line break, and an empty line may follow. Use this
+/// to read tools that add their own tables to report-like text. Returns the
+/// name of the table and its rows.
+pub fn parse_table(input: &str) -> Result<(String, Vec<TableRow>), parser::Error> {
+    parser::parse_table(input)
+}
+
+/// Parse several reports pasted one after another
+///
+/// The reports must be separated by at least one blank line. Each report is
+/// parsed strictly in the language detected from its first line, and the
+/// first report that fails to parse fails the whole input.
+pub fn parse_many(input: &str) -> Result<Vec<BattleReport>, parser::Error> {
+    parser::parse_many(input)
+}
+
+/// Parse the sections of a report up to the first error
+///
+/// Use this to salvage the events and rewards of a report that is damaged
+/// further down. The language is detected like in [`from_str`].
+pub fn from_str_partial(input: &str) -> (PartialBattleReport, Option<parser::Error>) {
+    let language = detect_language(input).unwrap_or_default();
+    parser::parse_partial(input, ParseOptions::strict().with_language(language))
+}
+
+/// Parse a report and check the totals it states
+///
+/// Each table header total is compared with the summed rows, and the total
+/// line with the earned rewards minus costs. Every mismatch is returned as a
+/// [`ValidationIssue`], which often points at rows lost while copying the
+/// report. The language is detected like in [`from_str`].
+pub fn from_str_validated(
+    input: &str,
+) -> Result<(BattleReport, Vec<ValidationIssue>), parser::Error> {
+    let language = detect_language(input).unwrap_or_default();
+    parser::parse_validated(input, ParseOptions::strict().with_language(language))
+}
+
+pub fn from_str_with_options(
+    input: &str,
+    options: ParseOptions,
+) -> Result<(BattleReport, Vec<Warning>), parser::Error> {
+    parser::parse_with_options(input, options)
+}
+
+/// Parse a report from bytes
+///
+/// A UTF-8 or UTF-16 byte order mark is honored and stripped. Anything else
+/// is read as UTF-8, replacing invalid sequences.
 pub fn from_slice(input: &[u8]) -> Result<BattleReport, parser::Error> {
-    let buffer = String::from_utf8_lossy(input);
-    parser::parse(&buffer)
+    from_str(&decode_lossy(input))
 }
 
-pub fn from_reader<R: io::Read>(mut input: R) -> Result<BattleReport, parser::Error> {
-    let mut buffer = String::new();
-    input.read_to_string(&mut buffer).unwrap();
+/// decode a report like [`from_slice`] does
+pub(crate) fn decode_lossy(input: &[u8]) -> Cow<'_, str> {
+    match encoding::detect_encoding(input) {
+        Encoding::Utf8 | Encoding::Latin1 => String::from_utf8_lossy(input),
+        _ => encoding::decode(input),
+    }
+}
+
+/// Parse a report after detecting its encoding
+///
+/// Unlike [`from_slice`] this decodes input that is not valid UTF-8 as
+/// Latin-1 instead of replacing the offending bytes.
+pub fn from_slice_auto(input: &[u8]) -> Result<BattleReport, parser::Error> {
+    from_str(&encoding::decode(input))
+}
+
+/// Read a report to the end and parse it like [`from_slice`]
+///
+/// Not available on `wasm32`, where there is nothing to read from.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn from_reader<R: io::Read>(mut input: R) -> Result<BattleReport, ReadError> {
+    let mut buffer = Vec::new();
+    input.read_to_end(&mut buffer)?;
+
+    Ok(from_slice(&buffer)?)
+}
+
+/// Decompress a gzip stream, like a `.report.gz` archive, and parse it like
+/// [`from_slice`]
+///
+/// A truncated or corrupt stream fails with [`ReadError::Io`].
+#[cfg(feature = "flate2")]
+pub fn from_gz_reader<R: io::Read>(input: R) -> Result<BattleReport, ReadError> {
+    use io::Read;
+
+    let mut buffer = Vec::new();
+    flate2::read::GzDecoder::new(input).read_to_end(&mut buffer)?;
+
+    Ok(from_slice(&buffer)?)
+}
+
+/// Read the file at `path` and parse it like [`from_slice`]
+pub fn from_path<P: AsRef<Path>>(path: P) -> Result<BattleReport, PathError> {
+    let path = path.as_ref();
+    fs::read(path)
+        .map_err(ReadError::from)
+        .and_then(|buffer| Ok(from_slice(&buffer)?))
+        .map_err(|source| PathError {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+/// Parse every file in a directory
+///
+/// Anything that is not a file after following symlinks, like a
+/// subdirectory, is skipped. Every file is attempted, and its result is
+/// returned next to its path, ordered by path. Only failing to read the
+/// directory itself is an error.
+pub fn from_directory<P: AsRef<Path>>(
+    path: P,
+) -> io::Result<Vec<(PathBuf, Result<BattleReport, ReadError>)>> {
+    from_directory_filtered(path, |_| true)
+}
+
+/// Parse every file in a directory for which `filter` returns `true`
+///
+/// Use this to restrict parsing to `*.report` files, for example.
+pub fn from_directory_filtered<P, F>(
+    path: P,
+    mut filter: F,
+) -> io::Result<Vec<(PathBuf, Result<BattleReport, ReadError>)>>
+where
+    P: AsRef<Path>,
+    F: FnMut(&Path) -> bool,
+{
+    let dir = path.as_ref();
+    let mut paths = Vec::new();
+    let mut failed = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            // the entry cannot be named, so its failure is filed under `dir`
+            Err(err) => {
+                failed.push((dir.to_path_buf(), Err(err.into())));
+                continue;
+            }
+        };
+        if path.is_file() && filter(&path) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut results = paths
+        .into_iter()
+        .map(|path| {
+            let report = fs::read(&path)
+                .map_err(ReadError::from)
+                .and_then(|buffer| Ok(from_slice(&buffer)?));
+            (path, report)
+        })
+        .collect::<Vec<_>>();
+    results.append(&mut failed);
+    Ok(results)
+}
+
+#[cfg(feature = "tokio")]
+pub async fn from_async_reader<R: tokio::io::AsyncRead + Unpin>(
+    mut input: R,
+) -> Result<BattleReport, ReadError> {
+    use tokio::io::AsyncReadExt;
+
+    let mut buffer = Vec::new();
+    input.read_to_end(&mut buffer).await?;
+
+    Ok(from_slice(&buffer)?)
+}
+
+#[cfg(feature = "tokio")]
+pub async fn from_async_path<P: AsRef<std::path::Path>>(
+    path: P,
+) -> Result<BattleReport, ReadError> {
+    let buffer = tokio::fs::read(path).await?;
+
+    Ok(from_slice(&buffer)?)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io;
+
+    use super::{ReadError, TableRow, ValidationIssue};
+    use crate::Reward;
+
+    /// yields `data`, then fails
+    struct FailingReader<'a> {
+        data: &'a [u8],
+    }
+
+    impl io::Read for FailingReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.data.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset"));
+            }
+            let n = self.data.len().min(buf.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn reader_failing_mid_read() {
+        let input = std::fs::read("./data/1603c1c00028a36.report").unwrap();
+        let reader = FailingReader {
+            data: &input[..100],
+        };
+        let err = super::from_reader(reader).unwrap_err();
+        assert!(
+            matches!(&err, ReadError::Io(err) if err.kind() == io::ErrorKind::ConnectionReset),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn reader_with_invalid_utf8() {
+        let input = b"Victory in the \xff\xfe mission!\n\n\x80\x81\n".as_slice();
+        let err = super::from_reader(input).unwrap_err();
+        assert!(matches!(err, ReadError::Parse(_)), "{err:?}");
+    }
+
+    #[test]
+    fn parse_slice_with_bom() {
+        let mut input = b"\xef\xbb\xbf".to_vec();
+        input.extend(std::fs::read("./data/161878b0007a4c8.report").unwrap());
+        let report = super::from_slice_auto(&input).unwrap();
+        assert_eq!(
+            report.mission_name,
+            "[Domination #1] Battle of Hürtgen Forest"
+        );
+    }
+
+    #[test]
+    fn parse_utf16le_slice() {
+        let plain = super::from_slice(&std::fs::read("./data/1603c1c00028a36.report").unwrap());
+        let utf16 =
+            super::from_slice(&std::fs::read("./data/utf16/1603c1c00028a36.report").unwrap());
+        assert_eq!(utf16.unwrap(), plain.unwrap());
+    }
+
+    #[test]
+    fn parse_utf16le_reader() {
+        let input = std::fs::File::open("./data/utf16/1603c1c00028a36.report").unwrap();
+        let report = super::from_reader(input).unwrap();
+        assert_eq!(report.session_id, "1603c1c00028a36");
+    }
+
+    #[cfg(feature = "flate2")]
+    fn gzip(input: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(input).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn parse_gz_reader() {
+        let input = std::fs::read("./data/1603c1c00028a36.report").unwrap();
+        let report = super::from_gz_reader(gzip(&input).as_slice()).unwrap();
+        assert_eq!(report, super::from_slice(&input).unwrap());
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn truncated_gz_stream_is_an_error() {
+        let input = gzip(&std::fs::read("./data/1603c1c00028a36.report").unwrap());
+        let err = super::from_gz_reader(&input[..input.len() / 2]).unwrap_err();
+        assert!(matches!(err, ReadError::Io(_)), "{err:?}");
+
+        let err = super::from_gz_reader(b"not gzip at all".as_slice()).unwrap_err();
+        assert!(matches!(err, ReadError::Io(_)), "{err:?}");
+    }
+
+    #[test]
+    fn parse_path() {
+        let report = super::from_path("./data/utf16/1603c1c00028a36.report").unwrap();
+        assert_eq!(report.session_id, "1603c1c00028a36");
+    }
+
+    #[test]
+    fn missing_path_is_named_in_error() {
+        let err = super::from_path("./data/missing.report").unwrap_err();
+        assert!(matches!(err.source, ReadError::Io(_)));
+        assert!(
+            err.to_string().starts_with("./data/missing.report: "),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn parse_german_without_language() {
+        let input = std::fs::read_to_string("./data/de/1603c1c00028a36.report").unwrap();
+        let report = super::from_str(&input).unwrap();
+        assert_eq!(report.result, crate::BattleResult::Loss);
+        assert_eq!(report.events.len(), 23);
+    }
+
+    #[test]
+    fn parse_custom_table() {
+        let input = "Destruction of ground vehicles and fleets     2    1940 SL     135 RP\n\
+            \x20   7:13     Concept 3          M6A1            1010 SL    77 RP\n\
+            \x20   11:47    Sherman Firefly    T-34 (1942)     930 SL     58 RP\n";
+        let (name, rows) = super::parse_table(input).unwrap();
+        assert_eq!(name, "Destruction of ground vehicles and fleets");
+        assert_eq!(
+            rows,
+            [
+                TableRow {
+                    time: 7 * 60 + 13,
+                    vehicle: "Concept 3".to_string(),
+                    enemy_vehicle: Some("M6A1".to_string()),
+                    assisted: false,
+                    reward: Reward::new(1010, 77),
+                },
+                TableRow {
+                    time: 11 * 60 + 47,
+                    vehicle: "Sherman Firefly".to_string(),
+                    enemy_vehicle: Some("T-34 (1942)".to_string()),
+                    assisted: false,
+                    reward: Reward::new(930, 58),
+                },
+            ]
+        );
+        assert_eq!(super::parse_table(&format!("{input}\n")).unwrap().1, rows);
+
+        let (name, rows) =
+            super::parse_table("Mission bounty    1    500 SL\n    9:59    Z-25    500 SL\n")
+                .unwrap();
+        assert_eq!(name, "Mission bounty");
+        assert_eq!(rows[0].enemy_vehicle, None);
+        assert_eq!(rows[0].reward, Reward::new(500, 0));
+    }
+
+    #[test]
+    fn custom_table_with_wrong_row_count() {
+        let input = "Mission bounty    2    500 SL\n    9:59    Z-25    500 SL\n";
+        let super::Error::Syntax(err) = super::parse_table(input).unwrap_err() else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!(err.line, 1);
+
+        // anything after the table is an error too
+        let input = input.replacen("    2    ", "    1    ", 1);
+        assert!(super::parse_table(&input).is_ok());
+        assert!(super::parse_table(&format!("{input}\ntrailing text\n")).is_err());
+    }
+
+    #[test]
+    fn parse_partial_report() {
+        let input = std::fs::read_to_string("./data/160409b0002a1af.report").unwrap();
+        let complete = super::from_str(&input).unwrap();
+        let input = input.replace("Improved Parts: 220 RP", "Improved Parts: lots of RP");
+        let (partial, err) = super::from_str_partial(&input);
+
+        assert!(err.is_some());
+        assert_eq!(partial.events, Some(complete.events));
+        assert_eq!(partial.vehicles, Some(complete.vehicles));
+        assert_eq!(partial.activity, Some(complete.activity));
+        assert_eq!(partial.session_id, None);
+        assert_eq!(partial.into_report(), None);
+    }
+
+    #[test]
+    fn parse_partial_complete_report() {
+        let input = std::fs::read_to_string("./data/160409b0002a1af.report").unwrap();
+        let (partial, err) = super::from_str_partial(&input);
+        assert!(err.is_none());
+        assert_eq!(
+            partial.into_report(),
+            Some(super::from_str(&input).unwrap())
+        );
+    }
+
+    #[test]
+    fn parse_concatenated_reports() {
+        let input = std::fs::read_to_string("./data/concatenated/two-reports.report").unwrap();
+        let reports = super::parse_many(&input).unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_ne!(reports[0].session_id, reports[1].session_id);
+        for (report, path) in reports.iter().zip([
+            "./data/1603c1c00028a36.report",
+            "./data/1712a4f0003b2c5.report",
+        ]) {
+            let input = std::fs::read_to_string(path).unwrap();
+            assert_eq!(report, &super::from_str(&input).unwrap());
+        }
+    }
+
+    #[test]
+    fn parse_many_needs_a_blank_line_between_reports() {
+        let input = std::fs::read_to_string("./data/concatenated/two-reports.report").unwrap();
+        let input = input.replace("2118 RP\n\n", "2118 RP\n");
+        let Err(super::Error::Syntax(err)) = super::parse_many(&input) else {
+            panic!("reports without a blank line between them parsed");
+        };
+        assert_eq!(err.section.as_deref(), Some("report separator"));
+    }
+
+    #[test]
+    fn single_report_rejects_trailing_input() {
+        let input = std::fs::read_to_string("./data/concatenated/two-reports.report").unwrap();
+        let Err(super::Error::Syntax(err)) = super::from_str(&input) else {
+            panic!("trailing report was ignored");
+        };
+        assert_eq!(err.section.as_deref(), Some("end of report"));
+        assert_eq!(
+            err.snippet,
+            "Victory in the [Domination] Sea of Japan mission!"
+        );
+    }
+
+    #[test]
+    fn validate_real_reports() {
+        for entry in std::fs::read_dir("./data").unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                continue;
+            }
+            let input = std::fs::read_to_string(&path).unwrap();
+            let (_, issues) = super::from_str_validated(&input).unwrap();
+            assert_eq!(issues, [], "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn validate_converted_research() {
+        let input = std::fs::read_to_string("./data/premium/1712a4f0003b2c5.report").unwrap();
+        let (report, issues) = super::from_str_validated(&input).unwrap();
+        assert_eq!(report.converted_research, Some(1234));
+        assert_eq!(issues, []);
+
+        let input = input.replace("Converted RP: 1 234 CRP\n", "");
+        let (report, issues) = super::from_str_validated(&input).unwrap();
+        assert_eq!(report.converted_research, None);
+        assert_eq!(
+            issues,
+            [ValidationIssue::ConvertibleResearch {
+                stated: 2428,
+                earned: 1194,
+            }]
+        );
+    }
+
+    #[test]
+    fn validate_tampered_report() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let input = input
+            .replace(
+                "13:55    Final blow!              250 SL",
+                "13:55    Final blow!              350 SL",
+            )
+            .replace("Total: 19796 SL, 2218 CRP", "Total: 19706 SL, 2200 CRP");
+        let (_, issues) = super::from_str_validated(&input).unwrap();
+        assert_eq!(
+            issues,
+            [
+                ValidationIssue::TableTotal {
+                    table: "Awards".to_string(),
+                    stated: Box::new(Reward::new(3450, 100)),
+                    summed: Box::new(Reward::new(3550, 100)),
+                },
+                ValidationIssue::Balance {
+                    stated: 19706,
+                    computed: 19796,
+                },
+                ValidationIssue::ConvertibleResearch {
+                    stated: 2200,
+                    earned: 2218,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_latin1_slice() {
+        let input = std::fs::read_to_string("./data/161878b0007a4c8.report").unwrap();
+        let input = input.chars().map(|c| c as u8).collect::<Vec<_>>();
+        let report = super::from_slice_auto(&input).unwrap();
+        assert_eq!(
+            report.mission_name,
+            "[Domination #1] Battle of Hürtgen Forest"
+        );
+    }
+
+    #[test]
+    fn parse_directory() {
+        let files = std::fs::read_dir("./data")
+            .unwrap()
+            .filter(|entry| entry.as_ref().unwrap().file_type().unwrap().is_file())
+            .count();
+        let reports = super::from_directory("./data").unwrap();
+        assert_eq!(reports.len(), files);
+        assert!(reports.iter().all(|(_, report)| report.is_ok()));
+        assert!(reports.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parse_directory_with_failing_files_and_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let dir = std::env::temp_dir().join("wt-battle-report-directory-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::copy("./data/1603c1c00028a36.report", dir.join("a.report")).unwrap();
+        std::fs::write(dir.join("b.report"), "Victory in the mission!\n").unwrap();
+        symlink(dir.join("nested"), dir.join("c.report")).unwrap();
+        symlink(dir.join("missing"), dir.join("d.report")).unwrap();
+        symlink(dir.join("a.report"), dir.join("e.report")).unwrap();
+
+        let reports = super::from_directory(&dir).unwrap();
+        let names = reports
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["a.report", "b.report", "e.report"]);
+        assert!(reports[0].1.is_ok());
+        assert!(matches!(reports[1].1, Err(ReadError::Parse(_))));
+        assert!(reports[2].1.is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn parse_directory_filtered() {
+        let reports = super::from_directory_filtered("./data", |path| {
+            path.file_name().is_some_and(|name| {
+                name == "160409b0002a1af.report" || name == "1603c1c00028a36.report"
+            })
+        })
+        .unwrap();
+        assert_eq!(reports.len(), 2);
+        assert!(reports.iter().all(|(_, report)| report.is_ok()));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn parse_from_async_reader() {
+        let input = std::fs::read("./data/1603c1c00028a36.report").unwrap();
+        let report = super::from_async_reader(input.as_slice()).await.unwrap();
+        assert_eq!(report.session_id, "1603c1c00028a36");
+    }
 
-    parser::parse(&buffer)
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn parse_from_async_path() {
+        let report = super::from_async_path("./data/1603c1c00028a36.report")
+            .await
+            .unwrap();
+        assert_eq!(report.session_id, "1603c1c00028a36");
+    }
 }
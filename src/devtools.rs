@@ -0,0 +1,287 @@
+//! Parser Development Tools
+//!
+//! Compare two versions of the parser over an archive of reports to see what
+//! a change does before releasing it.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{de::decode_lossy, BattleReport};
+
+/// Names of the fields in which two reports differ, in declaration order
+pub fn diff(a: &BattleReport, b: &BattleReport) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    // destructures `a` without `..`, so a new field does not compile until
+    // it is listed here
+    macro_rules! compare {
+        ($($(#[$attr:meta])* $field:ident),* $(,)?) => {
+            let BattleReport { $($(#[$attr])* $field),* } = a;
+            $(
+                $(#[$attr])*
+                if *$field != b.$field {
+                    fields.push(stringify!($field));
+                }
+            )*
+        };
+    }
+
+    compare!(
+        session_id,
+        #[cfg(feature = "timestamps")]
+        session_time,
+        result,
+        mission_name,
+        player_name,
+        squadron,
+        events,
+        event_tables,
+        awards,
+        awards_header_count,
+        awards_header_total,
+        reward_for_winning,
+        first_victory_bonus,
+        other_awards,
+        completed_tasks,
+        wagers,
+        vehicles,
+        crew_experience,
+        activity,
+        activity_basis_points,
+        damaged_vehicles,
+        automatic_repair,
+        automatic_purchases,
+        vehicle_research,
+        modification_research,
+        squadron_research,
+        used_items,
+        earned_rewards,
+        converted_research,
+        balance,
+    );
+    fields
+}
+
+/// What two parsers made of one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileOutcome {
+    /// Both parsed the same report
+    Equal,
+    /// Both parsed the file, into reports differing in these fields
+    Differ(Vec<&'static str>),
+    /// Only the first parser succeeded, the second failed with this error
+    OnlyA(String),
+    /// Only the second parser succeeded, the first failed with this error
+    OnlyB(String),
+    /// Neither parser succeeded
+    BothFailed { a: String, b: String },
+}
+
+/// Outcomes of comparing two parsers over many files
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorpusDiff {
+    /// Every compared file in the order it was added
+    pub files: Vec<(PathBuf, FileOutcome)>,
+}
+
+impl CorpusDiff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compare the results of both parsers for the file at `path`
+    pub fn add<A, B>(
+        &mut self,
+        path: impl Into<PathBuf>,
+        a: Result<BattleReport, A>,
+        b: Result<BattleReport, B>,
+    ) where
+        A: Display,
+        B: Display,
+    {
+        let outcome = match (a, b) {
+            (Ok(a), Ok(b)) => match diff(&a, &b) {
+                fields if fields.is_empty() => FileOutcome::Equal,
+                fields => FileOutcome::Differ(fields),
+            },
+            (Ok(_), Err(b)) => FileOutcome::OnlyA(b.to_string()),
+            (Err(a), Ok(_)) => FileOutcome::OnlyB(a.to_string()),
+            (Err(a), Err(b)) => FileOutcome::BothFailed {
+                a: a.to_string(),
+                b: b.to_string(),
+            },
+        };
+        self.files.push((path.into(), outcome));
+    }
+
+    pub fn equal(&self) -> usize {
+        self.count(|outcome| matches!(outcome, FileOutcome::Equal))
+    }
+
+    pub fn differ(&self) -> usize {
+        self.count(|outcome| matches!(outcome, FileOutcome::Differ(_)))
+    }
+
+    pub fn only_a(&self) -> usize {
+        self.count(|outcome| matches!(outcome, FileOutcome::OnlyA(_)))
+    }
+
+    pub fn only_b(&self) -> usize {
+        self.count(|outcome| matches!(outcome, FileOutcome::OnlyB(_)))
+    }
+
+    pub fn both_failed(&self) -> usize {
+        self.count(|outcome| matches!(outcome, FileOutcome::BothFailed { .. }))
+    }
+
+    /// Number of differing files per field
+    pub fn field_counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for (_, outcome) in &self.files {
+            if let FileOutcome::Differ(fields) = outcome {
+                for field in fields {
+                    *counts.entry(*field).or_default() += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// `true` if both parsers agree on every file
+    pub fn is_unchanged(&self) -> bool {
+        self.files.iter().all(|(_, outcome)| match outcome {
+            FileOutcome::Equal | FileOutcome::BothFailed { .. } => true,
+            FileOutcome::Differ(_) | FileOutcome::OnlyA(_) | FileOutcome::OnlyB(_) => false,
+        })
+    }
+
+    fn count(&self, filter: impl Fn(&FileOutcome) -> bool) -> usize {
+        self.files
+            .iter()
+            .filter(|(_, outcome)| filter(outcome))
+            .count()
+    }
+}
+
+/// Parse every `*.report` file in `dir` with both parsers and compare them
+///
+/// The files are decoded like in [`from_slice`](crate::from_slice) and
+/// compared in path order. Subdirectories are skipped.
+pub fn compare_corpus<P, A, B, EA, EB>(dir: P, parse_a: A, parse_b: B) -> io::Result<CorpusDiff>
+where
+    P: AsRef<Path>,
+    A: Fn(&str) -> Result<BattleReport, EA>,
+    B: Fn(&str) -> Result<BattleReport, EB>,
+    EA: Display,
+    EB: Display,
+{
+    let mut diff = CorpusDiff::new();
+    for path in report_files(dir.as_ref())? {
+        let buffer = fs::read(&path)?;
+        let input = decode_lossy(&buffer);
+        diff.add(path, parse_a(&input), parse_b(&input));
+    }
+    Ok(diff)
+}
+
+/// Every `*.report` file in `dir`, sorted by path
+pub fn report_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_file()
+            && path
+                .extension()
+                .is_some_and(|extension| extension == "report")
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn diff_names_changed_fields() {
+        let input = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        let report = from_str(&input).unwrap();
+        assert_eq!(diff(&report, &report), Vec::<&str>::new());
+
+        let mut changed = report.clone();
+        changed.activity += 1;
+        changed.events.pop();
+        assert_eq!(diff(&report, &changed), ["events", "activity"]);
+    }
+
+    #[test]
+    fn compare_corpus_sorts_files_into_outcomes() {
+        // the old parser reads one battle differently and fails on another,
+        // the new one fails on a third, and both fail on a fourth
+        let old = |input: &str| {
+            let mut report = from_str(input).map_err(|err| err.to_string())?;
+            match report.session_id.as_str() {
+                "1712a4f0003b2c5" => report.activity += 1,
+                "161878b0007a4c8" | "1714b3e0001c7a2" => return Err("old".to_string()),
+                _ => {}
+            }
+            Ok(report)
+        };
+        let new = |input: &str| {
+            let report = from_str(input).map_err(|err| err.to_string())?;
+            match report.session_id.as_str() {
+                "16dae2c0000e753" | "1714b3e0001c7a2" => Err("new".to_string()),
+                _ => Ok(report),
+            }
+        };
+
+        let diff = compare_corpus("./data", old, new).unwrap();
+        assert_eq!(diff.files.len(), 18);
+        assert_eq!(diff.equal(), 12);
+        // the battle with completed tasks and a first victory bonus has a
+        // copy without each of them
+        assert_eq!(diff.differ(), 3);
+        assert_eq!(diff.only_a(), 1);
+        assert_eq!(diff.only_b(), 1);
+        assert_eq!(diff.both_failed(), 1);
+        assert_eq!(diff.field_counts(), BTreeMap::from([("activity", 3)]));
+        assert!(!diff.is_unchanged());
+
+        let outcome = |name: &str| {
+            diff.files
+                .iter()
+                .find(|(path, _)| path.ends_with(name))
+                .map(|(_, outcome)| outcome.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            outcome("1712a4f0003b2c5.report"),
+            FileOutcome::Differ(vec!["activity"])
+        );
+        assert_eq!(
+            outcome("16dae2c0000e753.report"),
+            FileOutcome::OnlyA("new".to_string())
+        );
+        assert_eq!(
+            outcome("161878b0007a4c8.report"),
+            FileOutcome::OnlyB("old".to_string())
+        );
+    }
+
+    #[test]
+    fn same_parser_is_unchanged() {
+        let diff = compare_corpus("./data", from_str, from_str).unwrap();
+        assert_eq!(diff.equal(), diff.files.len());
+        assert!(diff.is_unchanged());
+    }
+}
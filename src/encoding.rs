@@ -0,0 +1,97 @@
+//! Text Encoding Detection
+
+use std::{borrow::Cow, str};
+
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+const UTF16LE_BOM: &[u8] = b"\xff\xfe";
+const UTF16BE_BOM: &[u8] = b"\xfe\xff";
+
+/// Encoding of a pasted battle report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf8WithBom,
+    /// UTF-16 little endian with a byte order mark, as saved by Windows
+    Utf16Le,
+    /// UTF-16 big endian with a byte order mark
+    Utf16Be,
+    /// Not valid UTF-8, assumed to be ISO-8859-1
+    Latin1,
+}
+
+/// Guess the encoding of `input`
+pub fn detect_encoding(input: &[u8]) -> Encoding {
+    if input.starts_with(UTF8_BOM) {
+        Encoding::Utf8WithBom
+    } else if input.starts_with(UTF16LE_BOM) {
+        Encoding::Utf16Le
+    } else if input.starts_with(UTF16BE_BOM) {
+        Encoding::Utf16Be
+    } else if str::from_utf8(input).is_ok() {
+        Encoding::Utf8
+    } else {
+        Encoding::Latin1
+    }
+}
+
+/// Decode `input` using the detected encoding, dropping any byte order mark
+pub fn decode(input: &[u8]) -> Cow<'_, str> {
+    match detect_encoding(input) {
+        Encoding::Utf8 => String::from_utf8_lossy(input),
+        Encoding::Utf8WithBom => String::from_utf8_lossy(&input[UTF8_BOM.len()..]),
+        Encoding::Utf16Le => Cow::Owned(decode_utf16(
+            &input[UTF16LE_BOM.len()..],
+            u16::from_le_bytes,
+        )),
+        Encoding::Utf16Be => Cow::Owned(decode_utf16(
+            &input[UTF16BE_BOM.len()..],
+            u16::from_be_bytes,
+        )),
+        Encoding::Latin1 => Cow::Owned(input.iter().map(|&byte| char::from(byte)).collect()),
+    }
+}
+
+/// Decode UTF-16 code units, replacing invalid sequences and a dangling byte
+fn decode_utf16(input: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let chunks = input.chunks_exact(2);
+    let dangling = !chunks.remainder().is_empty();
+
+    let mut output = char::decode_utf16(chunks.map(|pair| from_bytes([pair[0], pair[1]])))
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect::<String>();
+    if dangling {
+        output.push(char::REPLACEMENT_CHARACTER);
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        b"Victory in the [Domination] Battle of H\xc3\xbcrtgen Forest mission!",
+        Encoding::Utf8
+    )]
+    #[case(b"\xef\xbb\xbfVictory", Encoding::Utf8WithBom)]
+    #[case(b"\xff\xfeV\x00", Encoding::Utf16Le)]
+    #[case(b"\xfe\xff\x00V", Encoding::Utf16Be)]
+    #[case(b"Battle of H\xfcrtgen Forest", Encoding::Latin1)]
+    fn detect(#[case] input: &[u8], #[case] expected: Encoding) {
+        assert_eq!(detect_encoding(input), expected);
+    }
+
+    #[rstest]
+    #[case(b"H\xc3\xbcrtgen", "Hürtgen")]
+    #[case(b"\xef\xbb\xbfH\xc3\xbcrtgen", "Hürtgen")]
+    #[case(b"H\xfcrtgen", "Hürtgen")]
+    #[case(b"\xff\xfeH\x00\xfc\x00r\x00", "Hür")]
+    #[case(b"\xfe\xff\x00H\x00\xfc\x00r", "Hür")]
+    #[case(b"\xff\xfeH\x00r", "H\u{fffd}")]
+    fn decode_to_string(#[case] input: &[u8], #[case] expected: &str) {
+        assert_eq!(decode(input), expected);
+    }
+}
@@ -0,0 +1,299 @@
+//! CSV and JSON Export
+
+#[cfg(feature = "csv")]
+use std::io;
+
+use serde::Serialize;
+
+use crate::{timeline::phase_index, BattleReport, BattleResult};
+
+#[cfg(feature = "csv")]
+#[derive(Serialize)]
+struct EventRow<'a> {
+    session_id: &'a str,
+    result: BattleResult,
+    mission_name: &'a str,
+    time: u32,
+    kind: &'a str,
+    vehicle: &'a str,
+    enemy: Option<&'a str>,
+    silverlions: u32,
+    research: u32,
+}
+
+#[cfg(feature = "csv")]
+#[derive(Serialize)]
+struct VehicleRow<'a> {
+    session_id: &'a str,
+    result: BattleResult,
+    mission_name: &'a str,
+    name: &'a str,
+    activity: u8,
+    time_played: u32,
+    silverlions: u32,
+    research: u32,
+}
+
+#[derive(Serialize)]
+struct AwardRow<'a> {
+    session_id: &'a str,
+    result: BattleResult,
+    mission_name: &'a str,
+    time: u32,
+    minute: u32,
+    /// left out when the report has no events to infer the phases from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phase_index: Option<usize>,
+    name: &'a str,
+    silverlions: u32,
+    research: u32,
+}
+
+impl BattleReport {
+    /// Write one row per event
+    ///
+    /// The columns are `session_id`, `result`, `mission_name`, `time`
+    /// (seconds), `kind` (the table name), `vehicle`, `enemy` (empty when
+    /// the event has none), `silverlions` and `research`. A header row is
+    /// written first unless the writer was built with `has_headers(false)`.
+    #[cfg(feature = "csv")]
+    pub fn events_to_csv_writer<W: io::Write>(
+        &self,
+        writer: &mut csv::Writer<W>,
+    ) -> csv::Result<()> {
+        for event in &self.events {
+            writer.serialize(EventRow {
+                session_id: &self.session_id,
+                result: self.result,
+                mission_name: &self.mission_name,
+                time: event.time,
+                kind: event.kind.name(),
+                vehicle: &event.vehicle,
+                enemy: event.enemy.as_deref(),
+                silverlions: event.reward.silverlions(),
+                research: event.reward.research(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Write one row per vehicle played
+    ///
+    /// The columns are `session_id`, `result`, `mission_name`, `name`,
+    /// `activity` (percent), `time_played` (seconds), `silverlions` and
+    /// `research`.
+    #[cfg(feature = "csv")]
+    pub fn vehicles_to_csv_writer<W: io::Write>(
+        &self,
+        writer: &mut csv::Writer<W>,
+    ) -> csv::Result<()> {
+        for vehicle in &self.vehicles {
+            writer.serialize(VehicleRow {
+                session_id: &self.session_id,
+                result: self.result,
+                mission_name: &self.mission_name,
+                name: &vehicle.name,
+                activity: vehicle.activity,
+                time_played: vehicle.time_played,
+                silverlions: vehicle.reward.silverlions(),
+                research: vehicle.reward.research(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Write one row per award
+    ///
+    /// The columns are `session_id`, `result`, `mission_name`, `time`
+    /// (seconds), `minute` (whole minutes since the start of the battle),
+    /// `phase_index` (the spawn, see [`BattleReport::phases`]), `name`,
+    /// `silverlions` and `research`. The `phase_index` column is left out
+    /// when the report has no events to infer the phases from.
+    #[cfg(feature = "csv")]
+    pub fn awards_to_csv_writer<W: io::Write>(
+        &self,
+        writer: &mut csv::Writer<W>,
+    ) -> csv::Result<()> {
+        for row in self.award_rows() {
+            writer.serialize(row)?;
+        }
+        Ok(())
+    }
+
+    /// Serialize the awards as a JSON array of objects
+    ///
+    /// The objects have the columns of [`Self::awards_to_csv_writer`] as
+    /// keys, without `phase_index` when the phases are unknown.
+    #[cfg(feature = "json")]
+    pub fn awards_to_json_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&self.award_rows())
+    }
+
+    fn award_rows(&self) -> Vec<AwardRow<'_>> {
+        let phases = self.phases();
+        self.awards
+            .iter()
+            .map(|award| AwardRow {
+                session_id: &self.session_id,
+                result: self.result,
+                mission_name: &self.mission_name,
+                time: award.time,
+                minute: award.time / 60,
+                phase_index: phase_index(&phases, award.time),
+                name: &award.name,
+                silverlions: award.reward.silverlions(),
+                research: award.reward.research(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn report() -> BattleReport {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        from_str(&input).unwrap()
+    }
+
+    #[cfg(feature = "csv")]
+    fn write(f: impl Fn(&BattleReport, &mut csv::Writer<Vec<u8>>) -> csv::Result<()>) -> String {
+        write_report(&report(), f)
+    }
+
+    #[cfg(feature = "csv")]
+    fn write_report(
+        report: &BattleReport,
+        f: impl Fn(&BattleReport, &mut csv::Writer<Vec<u8>>) -> csv::Result<()>,
+    ) -> String {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        f(report, &mut writer).unwrap();
+        String::from_utf8(writer.into_inner().unwrap()).unwrap()
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn events_csv() {
+        let csv = write(BattleReport::events_to_csv_writer);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("session_id,result,mission_name,time,kind,vehicle,enemy,silverlions,research")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("1603c1c00028a36,loss,[Domination] Poland (winter),634,Destruction of aircraft,Wyvern S4,Pe-8,4396,226")
+        );
+        assert_eq!(lines.count(), 22);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn vehicles_csv() {
+        let csv = write(BattleReport::vehicles_to_csv_writer);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("session_id,result,mission_name,name,activity,time_played,silverlions,research")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("1603c1c00028a36,loss,[Domination] Poland (winter),Concept 3,97,501,730,748")
+        );
+        assert_eq!(lines.count(), 2);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn awards_csv() {
+        let csv = write(BattleReport::awards_to_csv_writer);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some(
+                "session_id,result,mission_name,time,minute,phase_index,name,silverlions,research"
+            )
+        );
+        assert_eq!(
+            lines.next(),
+            Some("1603c1c00028a36,loss,[Domination] Poland (winter),226,3,0,Intelligence,100,0")
+        );
+        assert_eq!(lines.clone().count(), 13);
+        assert_eq!(
+            lines.last(),
+            Some(
+                "1603c1c00028a36,loss,[Domination] Poland (winter),835,13,2,The Best Squad,1000,100"
+            )
+        );
+    }
+
+    /// the minute and phase of every award row match its timeline entry
+    #[cfg(feature = "csv")]
+    #[test]
+    fn awards_csv_matches_timeline() {
+        let report = report();
+        let csv = write_report(&report, BattleReport::awards_to_csv_writer);
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let rows = reader
+            .deserialize::<(String, String, String, u32, u32, usize, String, u32, u32)>()
+            .map(|row| {
+                let (_, _, _, time, minute, phase_index, ..) = row.unwrap();
+                (time, minute, Some(phase_index))
+            })
+            .collect::<Vec<_>>();
+        let timeline = report
+            .timeline()
+            .into_iter()
+            .filter(|entry| matches!(entry.item, TimelineItem::Award(_)))
+            .map(|entry| (entry.time, entry.minute, entry.phase_index))
+            .collect::<Vec<_>>();
+        assert_eq!(rows.len(), report.awards.len());
+        assert_eq!(rows, timeline);
+        assert!(rows.iter().any(|(.., phase_index)| *phase_index == Some(0)));
+        assert!(rows.iter().any(|(.., phase_index)| *phase_index == Some(2)));
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn awards_csv_without_phases() {
+        let mut report = report();
+        report.events.clear();
+        let csv = write_report(&report, BattleReport::awards_to_csv_writer);
+        assert_eq!(
+            csv.lines().next(),
+            Some("session_id,result,mission_name,time,minute,name,silverlions,research")
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn awards_json() {
+        let mut report = report();
+        let json =
+            serde_json::from_str::<serde_json::Value>(&report.awards_to_json_string().unwrap())
+                .unwrap();
+        assert_eq!(json.as_array().unwrap().len(), report.awards.len());
+        assert_eq!(
+            json[0],
+            serde_json::json!({
+                "session_id": "1603c1c00028a36",
+                "result": "loss",
+                "mission_name": "[Domination] Poland (winter)",
+                "time": 226,
+                "minute": 3,
+                "phase_index": 0,
+                "name": "Intelligence",
+                "silverlions": 100,
+                "research": 0,
+            })
+        );
+
+        report.events.clear();
+        let json =
+            serde_json::from_str::<serde_json::Value>(&report.awards_to_json_string().unwrap())
+                .unwrap();
+        assert!(json[0].get("minute").is_some());
+        assert!(json[0].get("phase_index").is_none());
+    }
+}
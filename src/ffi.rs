@@ -0,0 +1,334 @@
+//! C Interface
+//!
+//! Functions for scripts that can call into a shared library, like
+//! AutoHotkey, PowerShell or Python with `ctypes`, without linking against
+//! the crate. `wtbr_parse_file_to_json` converts a file in one call, the
+//! `wt_*` functions hand out a parsed report to read fields from. Their C
+//! declarations are in `include/wt_battle_report.h`, generated by `cbindgen`.
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_int, CStr, CString},
+    fs,
+    path::{Path, PathBuf},
+    ptr, slice,
+};
+
+use crate::{from_path, from_slice, BattleReport, BattleResult};
+
+/// The report was written as JSON
+pub const WTBR_OK: c_int = 0;
+/// A path is null or not valid UTF-8
+pub const WTBR_INVALID_PATH: c_int = 1;
+/// The report could not be read or parsed
+pub const WTBR_PARSE_ERROR: c_int = 2;
+/// The JSON could not be written
+pub const WTBR_WRITE_ERROR: c_int = 3;
+
+/// [`wt_report_result`] of a won battle
+pub const WT_WIN: c_int = 0;
+/// [`wt_report_result`] of a lost battle
+pub const WT_LOSS: c_int = 1;
+/// [`wt_report_result`] of a draw
+pub const WT_DRAW: c_int = 2;
+/// [`wt_report_result`] of a report that does not state the result
+pub const WT_UNKNOWN: c_int = 3;
+
+thread_local! {
+    /// message of the last failed [`wt_parse_report`] on this thread
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// A parsed report, opaque to C
+///
+/// Created by [`wt_parse_report`] and released with [`wt_report_free`].
+pub struct WtBattleReport {
+    report: BattleReport,
+    /// the session ID as a C string, kept so returned pointers stay valid
+    session_id: CString,
+}
+
+/// Parse the `len` bytes at `input` as a report
+///
+/// The input is decoded like in [`from_slice`], so it does not need to be
+/// NUL-terminated or UTF-8. Returns null if the report cannot be parsed, the
+/// reason is then available from [`wt_parse_error_message`]. Release the
+/// report with [`wt_report_free`].
+///
+/// # Safety
+///
+/// `input` must be null or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn wt_parse_report(input: *const c_char, len: usize) -> *mut WtBattleReport {
+    let result = if input.is_null() {
+        Err("input is null".to_string())
+    } else {
+        from_slice(slice::from_raw_parts(input.cast::<u8>(), len)).map_err(|err| err.to_string())
+    };
+    match result {
+        Ok(report) => {
+            set_last_error(None);
+            let session_id = CString::new(report.session_id.clone()).unwrap_or_default();
+            Box::into_raw(Box::new(WtBattleReport { report, session_id }))
+        }
+        Err(message) => {
+            set_last_error(Some(message));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// The reason the last [`wt_parse_report`] on this thread failed
+///
+/// Returns null if it succeeded or was never called. The message stays
+/// valid until the next call to [`wt_parse_report`] on the same thread.
+#[no_mangle]
+pub extern "C" fn wt_parse_error_message() -> *const c_char {
+    LAST_ERROR.with(|error| {
+        error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// The session ID of `report` as a NUL-terminated string
+///
+/// Returns null if `report` is null. The string is owned by the report and
+/// freed with it.
+///
+/// # Safety
+///
+/// `report` must be null or returned by [`wt_parse_report`] and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn wt_report_session_id(report: *const WtBattleReport) -> *const c_char {
+    report
+        .as_ref()
+        .map_or(ptr::null(), |report| report.session_id.as_ptr())
+}
+
+/// The result of the battle, one of the `WT_*` result constants
+///
+/// Returns -1 if `report` is null.
+///
+/// # Safety
+///
+/// `report` must be null or returned by [`wt_parse_report`] and not freed.
+#[no_mangle]
+pub unsafe extern "C" fn wt_report_result(report: *const WtBattleReport) -> c_int {
+    match report.as_ref().map(|report| report.report.result) {
+        Some(BattleResult::Win) => WT_WIN,
+        Some(BattleResult::Loss) => WT_LOSS,
+        Some(BattleResult::Draw) => WT_DRAW,
+        Some(BattleResult::Unknown) => WT_UNKNOWN,
+        None => -1,
+    }
+}
+
+/// Release a report returned by [`wt_parse_report`]
+///
+/// Does nothing if `report` is null.
+///
+/// # Safety
+///
+/// `report` must be null or returned by [`wt_parse_report`] and not freed
+/// before.
+#[no_mangle]
+pub unsafe extern "C" fn wt_report_free(report: *mut WtBattleReport) {
+    if !report.is_null() {
+        drop(Box::from_raw(report));
+    }
+}
+
+/// remember the message for [`wt_parse_error_message`]
+fn set_last_error(message: Option<String>) {
+    let message = message.map(|message| {
+        // the message is cut at the first NUL rather than lost
+        let end = message.find('\0').unwrap_or(message.len());
+        CString::new(&message[..end]).unwrap_or_default()
+    });
+    LAST_ERROR.with(|error| *error.borrow_mut() = message);
+}
+
+/// Parse the report at `in_path` and write it as JSON to `out_path`
+///
+/// Both paths are NUL-terminated UTF-8, also on Windows, where they are
+/// handed to the wide (UTF-16) file APIs. Encode them as UTF-8 instead of in
+/// the ANSI code page to reach any file name.
+///
+/// Returns [`WTBR_OK`] and removes a stale error file on success. Otherwise
+/// the error message is written to `out_path` with `.err` appended and one
+/// of the other `WTBR_*` codes is returned. Nothing is written if `out_path`
+/// itself is invalid.
+///
+/// # Safety
+///
+/// Both pointers must be null or point to NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn wtbr_parse_file_to_json(
+    in_path: *const c_char,
+    out_path: *const c_char,
+) -> c_int {
+    let Some(out_path) = path(out_path) else {
+        return WTBR_INVALID_PATH;
+    };
+    let error_path = error_path(out_path);
+
+    let result = match path(in_path) {
+        Some(in_path) => convert(in_path, out_path),
+        None => Err((
+            WTBR_INVALID_PATH,
+            "input path is null or not valid UTF-8".to_string(),
+        )),
+    };
+    match result {
+        Ok(()) => {
+            let _ = fs::remove_file(error_path);
+            WTBR_OK
+        }
+        Err((code, message)) => {
+            let _ = fs::write(error_path, message);
+            code
+        }
+    }
+}
+
+/// read a path passed from C, `None` if it is null or not UTF-8
+///
+/// # Safety
+///
+/// `path` must be null or point to a NUL-terminated string.
+unsafe fn path<'a>(path: *const c_char) -> Option<&'a Path> {
+    if path.is_null() {
+        return None;
+    }
+    CStr::from_ptr(path).to_str().ok().map(Path::new)
+}
+
+/// the file errors for `out_path` are written to
+fn error_path(out_path: &Path) -> PathBuf {
+    let mut path = out_path.as_os_str().to_owned();
+    path.push(".err");
+    path.into()
+}
+
+fn convert(in_path: &Path, out_path: &Path) -> Result<(), (c_int, String)> {
+    let report = from_path(in_path).map_err(|err| (WTBR_PARSE_ERROR, err.to_string()))?;
+    let json = report
+        .to_json_string()
+        .map_err(|err| (WTBR_WRITE_ERROR, err.to_string()))?;
+    fs::write(out_path, json)
+        .map_err(|err| (WTBR_WRITE_ERROR, format!("{}: {err}", out_path.display())))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{ffi::CString, fs, path::Path, ptr};
+
+    use super::*;
+    use crate::*;
+
+    const HEADER: &str = "./include/wt_battle_report.h";
+
+    fn c_path(path: &Path) -> CString {
+        CString::new(path.to_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parse_file_to_json() {
+        let report = Path::new("./data/1603c1c00028a36.report");
+        let out = std::env::temp_dir().join("wt-battle-report-ffi-test.json");
+        let err = error_path(&out);
+        fs::write(&err, "stale").unwrap();
+
+        let code =
+            unsafe { wtbr_parse_file_to_json(c_path(report).as_ptr(), c_path(&out).as_ptr()) };
+        assert_eq!(code, WTBR_OK);
+        assert!(!err.exists());
+
+        let json = fs::read_to_string(&out).unwrap();
+        fs::remove_file(&out).unwrap();
+        assert_eq!(from_json_str(&json).unwrap(), from_path(report).unwrap());
+    }
+
+    #[test]
+    fn errors_are_written_next_to_the_output() {
+        let out = std::env::temp_dir().join("wt-battle-report-ffi-error-test.json");
+        let err = error_path(&out);
+
+        let code = unsafe {
+            wtbr_parse_file_to_json(
+                c_path(Path::new("./data/invalid/debriefing.txt")).as_ptr(),
+                c_path(&out).as_ptr(),
+            )
+        };
+        assert_eq!(code, WTBR_PARSE_ERROR);
+        assert!(!out.exists());
+        let message = fs::read_to_string(&err).unwrap();
+        assert!(message.contains("debriefing"), "{message}");
+
+        let code = unsafe { wtbr_parse_file_to_json(ptr::null(), c_path(&out).as_ptr()) };
+        assert_eq!(code, WTBR_INVALID_PATH);
+        fs::remove_file(&err).unwrap();
+
+        let code = unsafe { wtbr_parse_file_to_json(ptr::null(), ptr::null()) };
+        assert_eq!(code, WTBR_INVALID_PATH);
+    }
+
+    #[test]
+    fn parse_report_from_memory() {
+        let input = fs::read("./data/1603c1c00028a36.report").unwrap();
+        unsafe {
+            let report = wt_parse_report(input.as_ptr().cast(), input.len());
+            assert!(!report.is_null());
+            assert!(wt_parse_error_message().is_null());
+            assert_eq!(
+                CStr::from_ptr(wt_report_session_id(report)).to_str(),
+                Ok("1603c1c00028a36")
+            );
+            assert_eq!(wt_report_result(report), WT_LOSS);
+            wt_report_free(report);
+        }
+    }
+
+    #[test]
+    fn parse_report_error_message() {
+        let input = b"My results\n";
+        unsafe {
+            let report = wt_parse_report(input.as_ptr().cast(), input.len());
+            assert!(report.is_null());
+            let message = CStr::from_ptr(wt_parse_error_message()).to_str().unwrap();
+            assert!(message.contains("debriefing"), "{message}");
+
+            assert!(wt_parse_report(ptr::null(), 0).is_null());
+            assert_eq!(
+                CStr::from_ptr(wt_parse_error_message()).to_str(),
+                Ok("input is null")
+            );
+            assert!(wt_report_session_id(ptr::null()).is_null());
+            assert_eq!(wt_report_result(ptr::null()), -1);
+            wt_report_free(ptr::null_mut());
+        }
+    }
+
+    /// Fails when the exported functions changed without updating the header
+    ///
+    /// Run with `UPDATE_FFI_HEADER=1` to regenerate it.
+    #[test]
+    fn header_matches_generated() {
+        let mut header = Vec::new();
+        cbindgen::generate(env!("CARGO_MANIFEST_DIR"))
+            .unwrap()
+            .write(&mut header);
+        let header = String::from_utf8(header).unwrap();
+        if std::env::var_os("UPDATE_FFI_HEADER").is_some() {
+            fs::write(HEADER, &header).unwrap();
+            return;
+        }
+
+        assert!(
+            fs::read_to_string(HEADER).is_ok_and(|committed| committed == header),
+            "{HEADER} is outdated, rerun with UPDATE_FFI_HEADER=1 to regenerate it"
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Flat Reward Serialization
+//!
+//! With the `flat-rewards` feature every [`Reward`] is written as two keys
+//! next to the other fields of its parent, named after its field with `_sl`
+//! and `_rp` appended. An event then has `reward_sl` and `reward_rp` instead
+//! of a nested `reward` object. The keys hold the [`RewardBreakdown`] of each
+//! currency, so no bonuses are lost. Squadron research points and other
+//! currencies are written as keys ending in `_srp` and `_other`, only if
+//! there are any.
+
+use std::fmt;
+
+use serde::{
+    de::{IgnoredAny, MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserializer, Serializer,
+};
+
+use crate::{Reward, RewardBreakdown};
+
+/// Generate a module for `#[serde(flatten, with = "...")]` per field name
+macro_rules! flat_reward {
+    ($($field:ident),* $(,)?) => {$(
+        // not every field is optional, and not every optional field is flat
+        #[allow(dead_code)]
+        pub(crate) mod $field {
+            use serde::{Deserializer, Serializer};
+
+            use crate::Reward;
+
+            const SL: &str = concat!(stringify!($field), "_sl");
+            const RP: &str = concat!(stringify!($field), "_rp");
+            const SRP: &str = concat!(stringify!($field), "_srp");
+            const OTHER: &str = concat!(stringify!($field), "_other");
+
+            pub(crate) fn serialize<S: Serializer>(
+                reward: &Reward,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                super::serialize(Some(reward), [SL, RP, SRP, OTHER], serializer)
+            }
+
+            pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Reward, D::Error> {
+                super::deserialize(deserializer, [SL, RP, SRP, OTHER]).map(Option::unwrap_or_default)
+            }
+
+            /// A missing reward is written as no keys at all
+            pub(crate) mod option {
+                use serde::{Deserializer, Serializer};
+
+                use crate::Reward;
+
+                pub(crate) fn serialize<S: Serializer>(
+                    reward: &Option<Reward>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    super::super::serialize(
+                        reward.as_ref(),
+                        [super::SL, super::RP, super::SRP, super::OTHER],
+                        serializer,
+                    )
+                }
+
+                pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Option<Reward>, D::Error> {
+                    super::super::deserialize(deserializer, [super::SL, super::RP, super::SRP, super::OTHER])
+                }
+            }
+        }
+    )*};
+}
+
+flat_reward!(
+    reward,
+    total,
+    awards_header_total,
+    reward_for_winning,
+    first_victory_bonus,
+    other_awards,
+    earned_rewards,
+    balance,
+);
+
+/// names of the keys holding silver lions, research, squadron research and
+/// other currencies
+type Keys = [&'static str; 4];
+
+fn serialize<S: Serializer>(
+    reward: Option<&Reward>,
+    [sl, rp, srp, other]: Keys,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let len = reward.map_or(0, |reward| {
+        2 + usize::from(reward.squadron_research > 0)
+            + usize::from(!reward.other_currencies.is_empty())
+    });
+    let mut map = serializer.serialize_map(Some(len))?;
+    if let Some(reward) = reward {
+        map.serialize_entry(sl, &reward.sl)?;
+        map.serialize_entry(rp, &reward.rp)?;
+        if reward.squadron_research > 0 {
+            map.serialize_entry(srp, &reward.squadron_research)?;
+        }
+        if !reward.other_currencies.is_empty() {
+            map.serialize_entry(other, &reward.other_currencies)?;
+        }
+    }
+    map.end()
+}
+
+/// read the reward from the `keys`, `None` if all are missing
+fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    [sl, rp, srp, other]: Keys,
+) -> Result<Option<Reward>, D::Error> {
+    deserializer.deserialize_map(FlatVisitor { sl, rp, srp, other })
+}
+
+struct FlatVisitor {
+    sl: &'static str,
+    rp: &'static str,
+    srp: &'static str,
+    other: &'static str,
+}
+
+impl<'de> Visitor<'de> for FlatVisitor {
+    type Value = Option<Reward>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a map with `{}` and `{}`", self.sl, self.rp)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut reward = None::<Reward>;
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.sl {
+                reward.get_or_insert_with(Reward::default).sl =
+                    map.next_value::<RewardBreakdown>()?;
+            } else if key == self.rp {
+                reward.get_or_insert_with(Reward::default).rp =
+                    map.next_value::<RewardBreakdown>()?;
+            } else if key == self.srp {
+                reward.get_or_insert_with(Reward::default).squadron_research =
+                    map.next_value::<u32>()?;
+            } else if key == self.other {
+                reward.get_or_insert_with(Reward::default).other_currencies =
+                    map.next_value::<Vec<(String, u32)>>()?;
+            } else {
+                // the other fields of the parent
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(reward)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::*;
+
+    #[test]
+    fn event_reward_is_flat() {
+        let event = Event::new(252, EventKind::DestructionOfFleets, "Z-25")
+            .with_enemy("Fletcher")
+            .with_reward(Reward::new(1630, 98));
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "time": 252,
+                "kind": "DestructionOfFleets",
+                "vehicle": "Z-25",
+                "enemy": "Fletcher",
+                "ally": null,
+                "assisted": false,
+                "reward_sl": { "base": 1630, "bonuses": [], "total": 1630 },
+                "reward_rp": { "base": 98, "bonuses": [], "total": 98 },
+            })
+        );
+        assert_eq!(serde_json::from_value::<Event>(value).unwrap(), event);
+    }
+
+    #[test]
+    fn squadron_research_is_flat() {
+        let vehicle = Vehicle {
+            reward: Reward::new(730, 68).with_squadron_research(50),
+            ..Vehicle::new("Concept 3")
+        };
+        let value = serde_json::to_value(&vehicle).unwrap();
+        assert_eq!(value["reward_srp"], 50);
+        assert_eq!(serde_json::from_value::<Vehicle>(value).unwrap(), vehicle);
+
+        let value = serde_json::to_value(Vehicle::new("Concept 3")).unwrap();
+        assert!(value.get("reward_srp").is_none(), "{value}");
+    }
+
+    #[test]
+    fn other_currencies_are_flat() {
+        let vehicle = Vehicle {
+            reward: Reward::new(730, 68).with_other_currency("WB", 15),
+            ..Vehicle::new("Concept 3")
+        };
+        let value = serde_json::to_value(&vehicle).unwrap();
+        assert_eq!(value["reward_other"], serde_json::json!([["WB", 15]]));
+        assert_eq!(serde_json::from_value::<Vehicle>(value).unwrap(), vehicle);
+
+        let value = serde_json::to_value(Vehicle::new("Concept 3")).unwrap();
+        assert!(value.get("reward_other").is_none(), "{value}");
+    }
+
+    #[test]
+    fn report_rewards_are_flat() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let report = from_str(&input).unwrap();
+        let value = serde_json::to_value(&report).unwrap();
+
+        let keys = value.as_object().unwrap().keys().collect::<Vec<_>>();
+        for key in [
+            "awards_header_total_sl",
+            "other_awards_rp",
+            "earned_rewards_sl",
+            "balance_rp",
+        ] {
+            assert!(keys.iter().any(|k| *k == key), "{key} in {keys:?}");
+        }
+        assert!(!keys.iter().any(|k| k.starts_with("reward_for_winning")));
+        assert_eq!(value["balance_sl"]["total"], 19796);
+        assert_eq!(value["vehicles"][0]["reward_rp"]["total"], 748);
+        assert_eq!(value["event_tables"][0]["total_sl"]["total"], 4396);
+
+        assert_eq!(
+            serde_json::from_value::<BattleReport>(value).unwrap(),
+            report
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! JSON Conversion
+
+use crate::BattleReport;
+
+impl BattleReport {
+    /// Serialize the report as compact JSON
+    pub fn to_json_string(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Serialize the report as indented JSON
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Deserialize a report written by [`BattleReport::to_json_string`]
+pub fn from_json_str(input: &str) -> serde_json::Result<BattleReport> {
+    serde_json::from_str(input)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn round_trip() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let report = from_str(&input).unwrap();
+
+        let compact = report.to_json_string().unwrap();
+        assert!(!compact.contains('\n'));
+        assert_eq!(from_json_str(&compact).unwrap(), report);
+
+        let pretty = report.to_json_pretty().unwrap();
+        assert!(pretty.contains("\n  \"session_id\": \"1603c1c00028a36\""));
+        assert_eq!(from_json_str(&pretty).unwrap(), report);
+    }
+
+    /// the `chrono` and `time` backends write the same JSON
+    #[cfg(feature = "timestamps")]
+    #[test]
+    fn round_trip_session_time() {
+        let input = std::fs::read_to_string("./data/dated/1712a4f0003b2c5.report").unwrap();
+        let report = from_str(&input).unwrap();
+        assert!(report.session_time.is_some());
+
+        let json = report.to_json_string().unwrap();
+        assert!(
+            json.contains("\"session_time\":\"2023-08-03T19:42:11\""),
+            "{json}"
+        );
+        assert_eq!(from_json_str(&json).unwrap(), report);
+
+        let json = json.replace("\"2023-08-03T19:42:11\"", "\"yesterday\"");
+        assert!(from_json_str(&json).is_err());
+    }
+}
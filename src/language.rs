@@ -0,0 +1,235 @@
+//! Client Languages
+
+use crate::EventKind;
+
+/// Language of the game client that wrote a report
+///
+/// Only labels and table names are localized. Amounts are always read with
+/// the English unit abbreviations (SL, RP, CRP).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+/// Fixed strings a report contains in a given language
+pub(crate) struct Vocabulary {
+    pub victory: &'static str,
+    pub defeat: &'static str,
+    pub draw: &'static str,
+    /// text between the battle result and the mission name
+    pub mission_prefix: &'static str,
+    /// text ending the first line
+    pub mission_suffix: &'static str,
+    pub player: &'static str,
+    pub awards: &'static str,
+    pub activity_time: &'static str,
+    pub time_played: &'static str,
+    /// labels of the reward for winning, the current one first
+    pub reward_for_winning: &'static [&'static str],
+    /// labels of the bonus for the first victory of the day, if known
+    pub first_victory: &'static [&'static str],
+    /// labels of the crew experience section, if known
+    pub crew_experience: &'static [&'static str],
+    pub other_awards: &'static str,
+    /// labels of completed battle tasks and challenges, if known
+    pub completed_task: &'static [&'static str],
+    /// labels starting a wager line, if known
+    pub wager: &'static [&'static str],
+    pub earned: &'static str,
+    /// labels of the automatically converted research, if known
+    pub converted_research: &'static [&'static str],
+    pub activity: &'static str,
+    pub damaged_vehicles: &'static str,
+    pub automatic_repair: &'static str,
+    pub automatic_purchase: &'static str,
+    pub researched_unit: &'static str,
+    pub researching_progress: &'static str,
+    /// labels of the squadron research points, if known
+    pub squadron_research: &'static [&'static str],
+    /// units after the squadron research points, if known
+    pub squadron_research_points: &'static [&'static str],
+    pub used_items: &'static str,
+    /// labels starting the active boosters in the used items, if known
+    pub active_boosters: &'static [&'static str],
+    pub session: &'static str,
+    pub total: &'static str,
+    /// event table names paired with their English name
+    pub event_tables: &'static [(&'static str, &'static str)],
+}
+
+const ENGLISH: Vocabulary = Vocabulary {
+    victory: "Victory",
+    defeat: "Defeat",
+    draw: "Draw",
+    mission_prefix: " in the ",
+    mission_suffix: " mission!",
+    player: "Player:",
+    awards: "Awards",
+    activity_time: "Activity Time",
+    time_played: "Time Played",
+    reward_for_winning: &["Reward for winning", "Reward for the victory"],
+    first_victory: &["First victory of the day"],
+    crew_experience: &["Crew experience:"],
+    other_awards: "Other awards",
+    completed_task: &["Battle task completed:", "Challenge completed:"],
+    wager: &["Wager"],
+    earned: "Earned:",
+    converted_research: &["Converted RP:"],
+    activity: "Activity:",
+    damaged_vehicles: "Damaged Vehicles:",
+    automatic_repair: "Automatic repair of all vehicles:",
+    automatic_purchase: "Automatic purchasing of ammo and \"Crew Replenishment\":",
+    researched_unit: "Researched unit:",
+    researching_progress: "Researching progress:",
+    squadron_research: &["Squadron research:"],
+    squadron_research_points: &["points"],
+    used_items: "Used items:",
+    active_boosters: &["Active boosters"],
+    session: "Session:",
+    total: "Total:",
+    event_tables: &[],
+};
+
+const GERMAN: Vocabulary = Vocabulary {
+    victory: "Sieg",
+    defeat: "Niederlage",
+    draw: "Unentschieden",
+    mission_prefix: " in der Mission ",
+    mission_suffix: "!",
+    player: "Spieler:",
+    awards: "Auszeichnungen",
+    activity_time: "Aktivitätszeit",
+    time_played: "Spielzeit",
+    reward_for_winning: &["Belohnung für den Sieg"],
+    first_victory: &[],
+    crew_experience: &[],
+    other_awards: "Andere Auszeichnungen",
+    completed_task: &[],
+    wager: &[],
+    earned: "Verdient:",
+    converted_research: &[],
+    activity: "Aktivität:",
+    damaged_vehicles: "Beschädigte Fahrzeuge:",
+    automatic_repair: "Automatische Reparatur aller Fahrzeuge:",
+    automatic_purchase: "Automatischer Kauf von Munition und \"Besatzungsauffüllung\":",
+    researched_unit: "Erforschte Einheit:",
+    researching_progress: "Forschungsfortschritt:",
+    squadron_research: &[],
+    squadron_research_points: &[],
+    used_items: "Verwendete Gegenstände:",
+    active_boosters: &[],
+    session: "Sitzung:",
+    total: "Gesamt:",
+    event_tables: &[
+        ("Zerstörung von Flugzeugen", "Destruction of aircraft"),
+        (
+            "Zerstörung von Bodenfahrzeugen und Schiffen",
+            "Destruction of ground vehicles and fleets",
+        ),
+        (
+            "Zerstörung feindlicher Schiffe",
+            "Destruction of enemy fleets",
+        ),
+        (
+            "Unterstützung bei der Zerstörung des Feindes",
+            "Assistance in destroying the enemy",
+        ),
+        (
+            "Kritischer Schaden am Feind",
+            "Critical damage to the enemy",
+        ),
+        (
+            "Schwerer Schaden an Flugzeugen",
+            "Severe damage to aircraft",
+        ),
+        ("Schaden am Feind", "Damage to the enemy"),
+        ("Aufklärung des Feindes", "Scouting of the enemy"),
+        (
+            "Schaden an aufgeklärten Feinden",
+            "Damage taken by scouted enemies",
+        ),
+        (
+            "Zerstörung aufgeklärter Feinde durch Verbündete",
+            "Destruction by allies of scouted enemies",
+        ),
+        ("Eroberung von Zonen", "Capture of zones"),
+        ("Schaden an der Basis", "Damage to the base"),
+        ("Angriff auf die Basis", "Attack of the base"),
+        ("Zerstörung der Basis", "Destruction of the base"),
+    ],
+};
+
+impl Language {
+    /// Every supported language, English first
+    pub const ALL: [Self; 2] = [Self::English, Self::German];
+
+    pub(crate) fn vocabulary(self) -> &'static Vocabulary {
+        match self {
+            Self::English => &ENGLISH,
+            Self::German => &GERMAN,
+        }
+    }
+
+    /// Translate an event table name in this language to English
+    pub(crate) fn event_table_name(self, name: &str) -> &str {
+        self.vocabulary()
+            .event_tables
+            .iter()
+            .find(|(local, _)| *local == name)
+            .map_or(name, |(_, english)| english)
+    }
+
+    /// Look up the kind for an event table name in this language
+    pub(crate) fn event_kind(self, name: &str) -> EventKind {
+        EventKind::from_name(self.event_table_name(name))
+    }
+}
+
+/// Guess the language of a report from the battle result on its first line
+///
+/// Returns `None` if the first line is not a result line in any supported
+/// language.
+pub fn detect_language(input: &str) -> Option<Language> {
+    let line = input.lines().next()?.trim_start_matches('\u{feff}');
+    Language::ALL.into_iter().find(|language| {
+        let words = language.vocabulary();
+        [words.victory, words.defeat, words.draw]
+            .into_iter()
+            .any(|result| {
+                line.strip_prefix(result)
+                    .is_some_and(|rest| rest.starts_with(words.mission_prefix))
+            })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        "Defeat in the [Domination] Poland (winter) mission!\n",
+        Some(Language::English)
+    )]
+    #[case(
+        "Victory in the [Domination] Sea of Japan mission!",
+        Some(Language::English)
+    )]
+    #[case(
+        "Niederlage in der Mission [Domination] Poland (winter)!\n",
+        Some(Language::German)
+    )]
+    #[case(
+        "Sieg in der Mission [Herrschaft] Hürtgenwald!",
+        Some(Language::German)
+    )]
+    #[case("Siegfried Line", None)]
+    #[case("", None)]
+    fn detect(#[case] input: &str, #[case] expected: Option<Language>) {
+        assert_eq!(detect_language(input), expected);
+    }
+}
@@ -1,9 +1,271 @@
+#[cfg(all(feature = "chrono", feature = "time"))]
+compile_error!("the `chrono` and `time` features are mutually exclusive, enable only one of them");
+#[cfg(all(feature = "timestamps", not(any(feature = "chrono", feature = "time"))))]
+compile_error!("timestamps need a backend, enable either the `chrono` or the `time` feature");
+
 pub mod battle_report;
+pub mod borrowed;
+pub mod builder;
+#[cfg(feature = "timestamps")]
+mod datetime;
 pub mod de;
+pub mod devtools;
+pub mod encoding;
+#[cfg(any(feature = "csv", feature = "json"))]
+mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "flat-rewards")]
+mod flat;
+#[cfg(feature = "json")]
+mod json;
+pub mod language;
+pub mod nation;
 mod parser;
+pub mod prelude;
+mod render;
+pub mod score;
+pub mod stats;
+#[cfg(feature = "sqlite")]
+pub mod store;
+pub mod summary;
+pub mod timeline;
+#[cfg(feature = "timestamps")]
+pub mod timestamped;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 pub use battle_report::{
-    Award, BattleReport, BattleResult, Event, ModificationResearch, Reward, Vehicle,
-    VehicleResearch,
+    normalize_vehicle_name, Award, BattleReport, BattleResult, BonusKind, CompletedTask,
+    CrewExperience, Event, EventFilter, EventKind, EventTable, ModificationResearch,
+    ParseBattleResultError, PartialBattleReport, Reward, RewardBreakdown, UsedItem, Vehicle,
+    VehicleResearch, WagerProgress,
+};
+pub use borrowed::BattleReportRef;
+pub use builder::{BattleReportBuilder, MissingFieldError};
+#[cfg(feature = "timestamps")]
+pub use datetime::{NaiveDateTime, UtcDateTime};
+#[cfg(feature = "flate2")]
+pub use de::from_gz_reader;
+#[cfg(not(target_arch = "wasm32"))]
+pub use de::from_reader;
+#[cfg(feature = "tokio")]
+pub use de::{from_async_path, from_async_reader};
+pub use de::{
+    from_directory, from_directory_filtered, from_path, from_slice, from_slice_auto, from_str,
+    from_str_borrowed, from_str_partial, from_str_validated, from_str_with_language,
+    from_str_with_options, parse_many, parse_table, Error, ParseOptions, PathError, ReadError,
+    SyntaxError, TableRow, ValidationIssue, Warning,
 };
-pub use de::{from_reader, from_slice, from_str};
+pub use encoding::{detect_encoding, Encoding};
+#[cfg(feature = "json")]
+pub use json::from_json_str;
+pub use language::{detect_language, Language};
+pub use nation::Nation;
+pub use score::{ScoreFormula, ScoreInputs};
+pub use stats::{AggregateStats, EnemyStats, Matchup, SessionStats};
+#[cfg(feature = "sqlite")]
+pub use store::BattleStore;
+pub use summary::BattleSummary;
+pub use timeline::{Phase, TimelineEntry, TimelineItem};
+#[cfg(feature = "timestamps")]
+pub use timestamped::{TimeOffsetError, TimestampedAward, TimestampedEvent, TimestampedReport};
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::Path};
+
+    const API_SNAPSHOT: &str = "./public-api.txt";
+
+    /// List the public items declared in `source`
+    ///
+    /// This is a line based scan that relies on the crate being formatted
+    /// with rustfmt. It picks up top level `pub` items, `pub` fields and
+    /// methods inside public structs and impl blocks, and enum variants.
+    fn public_items(source: &str) -> Vec<String> {
+        let mut items = Vec::new();
+        let mut block = None::<String>;
+        let mut pending = String::new();
+        let mut cfg = None;
+        let mut attribute = false;
+
+        for line in source.lines() {
+            if line.starts_with("#[cfg(test)]") {
+                break;
+            }
+
+            let trimmed = line.trim();
+            let indent = line.len() - line.trim_start().len();
+
+            // skip attributes spanning several lines, like long error messages
+            if attribute || (trimmed.starts_with("#[") && !trimmed.ends_with(']')) {
+                attribute = !trimmed.ends_with(']');
+                continue;
+            }
+
+            if !pending.is_empty() {
+                pending.push(' ');
+                pending.push_str(trimmed);
+            } else if indent == 0 {
+                if trimmed.starts_with("#[cfg(") {
+                    cfg = Some(trimmed.to_string());
+                    continue;
+                }
+                if trimmed.ends_with('{') {
+                    block = Some(trimmed.to_string());
+                } else if trimmed == "}" {
+                    block = None;
+                }
+                if trimmed.starts_with("pub ") {
+                    pending.push_str(trimmed);
+                }
+            } else if indent == 4 {
+                let header = block.as_deref().unwrap_or_default();
+                let name = header.trim_end_matches(" {");
+                let comment = trimmed.starts_with("//") || trimmed.starts_with("#[");
+                let closing = trimmed.starts_with('}');
+                if header.starts_with("pub enum") && !comment && !closing {
+                    let variant = trimmed
+                        .split(['(', '{', ',', ' '])
+                        .next()
+                        .unwrap_or_default();
+                    items.push(format!("{name}::{variant}"));
+                } else if (header.starts_with("pub struct") || header.starts_with("impl"))
+                    && trimmed.starts_with("pub ")
+                {
+                    pending.push_str(name);
+                    pending.push_str(" => ");
+                    pending.push_str(trimmed);
+                }
+            }
+
+            let done = if pending.starts_with("pub use") {
+                trimmed.ends_with(';')
+            } else if pending.starts_with("pub struct") && pending.contains(" => ") {
+                trimmed.ends_with(',')
+            } else {
+                trimmed.ends_with(['{', '}', ';'])
+            };
+            if !pending.is_empty() && done {
+                let item = pending
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .replace("( ", "(")
+                    .replace("{ ", "{")
+                    .replace(", }", "}")
+                    .replace(", )", ")")
+                    .replace(" )", ")");
+                let item = item
+                    .trim_end_matches("{}")
+                    .trim_end_matches(['{', ';', ',', ' ']);
+                items.push(match cfg.take() {
+                    Some(cfg) => format!("{cfg} {item}"),
+                    None => item.to_string(),
+                });
+                pending.clear();
+            } else if pending.is_empty() && !trimmed.starts_with("#[") && indent == 0 {
+                cfg = None;
+            }
+        }
+
+        items
+    }
+
+    fn public_api() -> String {
+        let mut paths = fs::read_dir("./src")
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.file_name().is_some_and(|name| name != "main.rs"))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let mut api = String::new();
+        for path in paths {
+            let source = fs::read_to_string(&path).unwrap();
+            let name = path.file_name().unwrap().to_string_lossy();
+            for item in public_items(&source) {
+                api.push_str(&format!("{name}: {item}\n"));
+            }
+        }
+        api
+    }
+
+    /// Fails when the public API changed without updating the snapshot
+    ///
+    /// Run with `UPDATE_API_SNAPSHOT=1` to accept the current API.
+    #[test]
+    fn public_api_matches_snapshot() {
+        let api = public_api();
+        if std::env::var_os("UPDATE_API_SNAPSHOT").is_some() {
+            fs::write(API_SNAPSHOT, &api).unwrap();
+            return;
+        }
+
+        let snapshot = fs::read_to_string(Path::new(API_SNAPSHOT)).unwrap_or_default();
+        let removed = snapshot
+            .lines()
+            .filter(|line| !api.lines().any(|item| item == *line))
+            .collect::<Vec<_>>();
+        let added = api
+            .lines()
+            .filter(|item| !snapshot.lines().any(|line| line == *item))
+            .collect::<Vec<_>>();
+        assert!(
+            removed.is_empty() && added.is_empty(),
+            "public API changed, rerun with UPDATE_API_SNAPSHOT=1 to accept\nremoved:\n  {}\nadded:\n  {}",
+            removed.join("\n  "),
+            added.join("\n  "),
+        );
+    }
+
+    #[test]
+    fn public_items_of_source() {
+        let source = "\
+pub struct Reward {
+    pub silverlions: u32,
+    research: u32,
+}
+
+pub(crate) struct Hidden {
+    pub field: u32,
+}
+
+impl Reward {
+    pub fn new(
+        silverlions: u32,
+    ) -> Self {
+        todo!()
+    }
+
+    fn private() {}
+}
+
+#[cfg(feature = \"tokio\")]
+pub fn from_async() {}
+
+pub enum Kind {
+    /// documented
+    Plain,
+    #[error(
+        \"long message\",
+    )]
+    Long,
+    Other(String),
+}
+";
+        assert_eq!(
+            public_items(source),
+            [
+                "pub struct Reward",
+                "pub struct Reward => pub silverlions: u32",
+                "impl Reward => pub fn new(silverlions: u32) -> Self",
+                "#[cfg(feature = \"tokio\")] pub fn from_async()",
+                "pub enum Kind",
+                "pub enum Kind::Plain",
+                "pub enum Kind::Long",
+                "pub enum Kind::Other",
+            ]
+        );
+    }
+}
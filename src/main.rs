@@ -0,0 +1,692 @@
+use std::{
+    env, fs,
+    io::{self, BufRead, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use wt_battle_report::{BattleReport, ScoreFormula, SessionStats};
+
+const USAGE: &str = "\
+Usage: wt-battle-report [--json] [FILE]...
+       wt-battle-report stats [--enemies] [--top N] [--score] [--matchup NAME]
+                              <PATH>...
+       wt-battle-report watch [--json] <DIR>
+       wt-battle-report compare-corpus <OLD> <DIR>
+       wt-battle-report --repl
+
+Without a command, every FILE is printed, or the report on stdin if no FILE
+is given. Files ending in .gz are decompressed first.
+
+Commands:
+    stats    Print statistics over the reports in PATH (files or directories)
+    watch    Print every report file created in DIR until interrupted
+    compare-corpus
+             Compare the reports in DIR with the JSON an earlier version
+             printed for them, one OLD/<name>.json per DIR/<name>.report
+    --repl   Summarize reports pasted into stdin, one after another
+
+Options:
+    --json       Print reports as JSON instead of debug output
+    --enemies    List the enemy vehicles encountered most often
+    --top N      Number of enemies to list [default: 10]
+    --score      Print the standard battle score of every battle
+    --matchup NAME
+                 List the kills of or against the vehicle NAME";
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    match args.split_first() {
+        Some((command, args)) if command == "stats" => stats(args),
+        Some((command, args)) if command == "watch" => watch(args),
+        Some((command, args)) if command == "compare-corpus" => {
+            compare_corpus(args, io::stdout().lock())
+        }
+        Some((command, [])) if command == "--repl" => {
+            repl(io::stdin().lock(), io::stdout(), io::stderr()).map_err(|err| err.to_string())
+        }
+        Some((command, _)) if command == "--help" || command == "-h" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => show(args, io::stdin().lock(), io::stdout().lock()),
+    }
+}
+
+/// Print the reports in the files named by `args`, or the report on `input`
+///
+/// Every file is printed even if an earlier one fails to parse.
+fn show(args: &[String], mut input: impl Read, mut output: impl Write) -> Result<(), String> {
+    let mut json = false;
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}")),
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+
+    if paths.is_empty() {
+        let mut buffer = Vec::new();
+        input
+            .read_to_end(&mut buffer)
+            .map_err(|err| format!("<stdin>: {err}"))?;
+        let report =
+            wt_battle_report::from_slice(&buffer).map_err(|err| format!("<stdin>: {err}"))?;
+        return print_report(&report, json, &mut output);
+    }
+
+    let mut failed = 0;
+    for path in &paths {
+        match load_report(path) {
+            Ok(report) => {
+                print_report(&report, json, &mut output).map_err(|err| err.to_string())?
+            }
+            Err(message) => {
+                eprintln!("{message}");
+                failed += 1;
+            }
+        }
+    }
+
+    match failed {
+        0 => Ok(()),
+        _ => Err(format!(
+            "failed to parse {failed} of {} reports",
+            paths.len()
+        )),
+    }
+}
+
+fn print_report(report: &BattleReport, json: bool, mut output: impl Write) -> Result<(), String> {
+    let text = if json {
+        to_json(report)?
+    } else {
+        format!("{report:#?}")
+    };
+    writeln!(output, "{text}").map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "json")]
+fn to_json(report: &BattleReport) -> Result<String, String> {
+    report.to_json_pretty().map_err(|err| err.to_string())
+}
+
+#[cfg(not(feature = "json"))]
+fn to_json(_: &BattleReport) -> Result<String, String> {
+    Err("--json requires the json feature".to_string())
+}
+
+/// Time a report file has to stay unchanged before it is parsed
+#[cfg(feature = "watch")]
+const DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Print every report file created in a directory
+///
+/// A file is parsed once no event arrived for it during [`DEBOUNCE`]. If it
+/// fails to parse, for example because the game is still writing it, the
+/// next change to the file triggers another attempt.
+#[cfg(feature = "watch")]
+fn watch(args: &[String]) -> Result<(), String> {
+    use std::{sync::mpsc, time::Instant};
+
+    use notify::{EventKind, RecursiveMode, Watcher};
+
+    let mut json = false;
+    let mut dirs = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}")),
+            dir => dirs.push(PathBuf::from(dir)),
+        }
+    }
+    let [dir] = dirs.as_slice() else {
+        return Err(USAGE.to_string());
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(|err| err.to_string())?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|err| format!("{}: {err}", dir.display()))?;
+
+    let mut debouncer = Debouncer::default();
+    loop {
+        match receiver.recv_timeout(DEBOUNCE) {
+            Ok(Ok(event)) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => {
+                let now = Instant::now();
+                for path in event.paths {
+                    if path
+                        .extension()
+                        .is_some_and(|extension| extension == "report")
+                    {
+                        debouncer.event(path, now);
+                    }
+                }
+            }
+            Ok(Ok(_)) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Ok(Err(err)) => eprintln!("{}: {err}", dir.display()),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err(format!("{}: stopped watching", dir.display()))
+            }
+        }
+
+        for path in debouncer.due(Instant::now()) {
+            match load_report(&path) {
+                Ok(report) => {
+                    print_report(&report, json, io::stdout().lock())?;
+                    debouncer.done(path);
+                }
+                Err(message) => eprintln!("{message}, retrying when the file changes"),
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "watch"))]
+fn watch(_: &[String]) -> Result<(), String> {
+    Err("watch requires the watch feature".to_string())
+}
+
+/// Collects file events until a file stays unchanged for [`DEBOUNCE`]
+#[cfg(feature = "watch")]
+#[derive(Debug, Default)]
+struct Debouncer {
+    /// files with unhandled events and the time of their last event
+    pending: std::collections::HashMap<PathBuf, std::time::Instant>,
+    /// files that were parsed, whose later events are ignored
+    done: std::collections::HashSet<PathBuf>,
+}
+
+#[cfg(feature = "watch")]
+impl Debouncer {
+    fn event(&mut self, path: PathBuf, now: std::time::Instant) {
+        if !self.done.contains(&path) {
+            self.pending.insert(path, now);
+        }
+    }
+
+    /// Take the files without an event since `now - DEBOUNCE`, sorted
+    fn due(&mut self, now: std::time::Instant) -> Vec<PathBuf> {
+        let mut due = self
+            .pending
+            .iter()
+            .filter(|(_, last)| now.duration_since(**last) >= DEBOUNCE)
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        for path in &due {
+            self.pending.remove(path);
+        }
+        due.sort();
+        due
+    }
+
+    fn done(&mut self, path: PathBuf) {
+        self.done.insert(path);
+    }
+}
+
+/// Compare the reports in a directory with JSON exported by an earlier version
+///
+/// Fails if any report parses differently than before.
+#[cfg(feature = "json")]
+fn compare_corpus(args: &[String], mut output: impl Write) -> Result<(), String> {
+    use wt_battle_report::devtools::{self, CorpusDiff};
+
+    let [old, dir] = args else {
+        return Err(USAGE.to_string());
+    };
+    let (old, dir) = (Path::new(old), Path::new(dir));
+
+    let mut diff = CorpusDiff::new();
+    for path in devtools::report_files(dir).map_err(|err| format!("{}: {err}", dir.display()))? {
+        let mut name = path.file_stem().unwrap_or_default().to_owned();
+        name.push(".json");
+        let json = old.join(name);
+        let before = fs::read_to_string(&json)
+            .map_err(|err| err.to_string())
+            .and_then(|json| wt_battle_report::from_json_str(&json).map_err(|err| err.to_string()))
+            .map_err(|err| format!("{}: {err}", json.display()));
+        diff.add(path.clone(), before, load_report(&path));
+    }
+
+    print_corpus_diff(&diff, &mut output).map_err(|err| err.to_string())?;
+
+    match diff.is_unchanged() {
+        true => Ok(()),
+        false => Err(format!(
+            "{} of {} reports changed",
+            diff.files.len() - diff.equal() - diff.both_failed(),
+            diff.files.len()
+        )),
+    }
+}
+
+/// Print the files that changed, followed by the number of files per outcome
+#[cfg(feature = "json")]
+fn print_corpus_diff(
+    diff: &wt_battle_report::devtools::CorpusDiff,
+    mut output: impl Write,
+) -> io::Result<()> {
+    use wt_battle_report::devtools::FileOutcome;
+
+    for (path, outcome) in &diff.files {
+        let path = path.display();
+        match outcome {
+            FileOutcome::Equal => {}
+            FileOutcome::Differ(fields) => {
+                writeln!(output, "{path}: differs in {}", fields.join(", "))?
+            }
+            FileOutcome::OnlyA(err) => writeln!(output, "{path}: fails now: {err}")?,
+            FileOutcome::OnlyB(err) => writeln!(output, "{path}: failed before: {err}")?,
+            FileOutcome::BothFailed { b, .. } => {
+                writeln!(output, "{path}: fails before and now: {b}")?
+            }
+        }
+    }
+
+    writeln!(output)?;
+    writeln!(output, "Equal:       {}", diff.equal())?;
+    writeln!(output, "Different:   {}", diff.differ())?;
+    writeln!(output, "Only before: {}", diff.only_a())?;
+    writeln!(output, "Only now:    {}", diff.only_b())?;
+    writeln!(output, "Both failed: {}", diff.both_failed())?;
+    for (field, count) in diff.field_counts() {
+        writeln!(output, "    {field}: {count}")?;
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "json"))]
+fn compare_corpus(_: &[String], _: impl Write) -> Result<(), String> {
+    Err("compare-corpus requires the json feature".to_string())
+}
+
+fn stats(args: &[String]) -> Result<(), String> {
+    let mut enemies = false;
+    let mut top = 10;
+    let mut score = false;
+    let mut matchup = None;
+    let mut paths = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--enemies" => enemies = true,
+            "--score" => score = true,
+            "--top" => {
+                top = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or("--top expects a number")?;
+            }
+            "--matchup" => {
+                matchup = Some(args.next().ok_or("--matchup expects a vehicle name")?);
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}")),
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+
+    if paths.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let reports = load_reports(&paths)?;
+    let stats = SessionStats::from_reports(&reports);
+
+    println!("Battles: {}", stats.battles);
+
+    if score {
+        let formula = ScoreFormula::standard();
+        let scores = reports
+            .iter()
+            .map(|report| (&report.session_id, report.score_inputs().score(&formula)))
+            .collect::<Vec<_>>();
+
+        println!();
+        println!("{:<24} {:>9}", "Session", "Score");
+        for (session_id, score) in &scores {
+            println!("{:<24} {:>9.2}", session_id, score);
+        }
+        let total = scores.iter().map(|(_, score)| score).sum::<f64>();
+        println!("{:<24} {:>9.2}", "Total", total);
+        if !scores.is_empty() {
+            println!("{:<24} {:>9.2}", "Average", total / scores.len() as f64);
+        }
+    }
+
+    if enemies {
+        println!();
+        println!(
+            "{:<24} {:>9} {:>9} {:>9}",
+            "Enemy", "Destroyed", "Damaged", "Scouted"
+        );
+        for (name, enemy) in stats.top_enemies(top) {
+            println!(
+                "{:<24} {:>9} {:>9} {:>9}",
+                name, enemy.destroyed, enemy.damaged, enemy.scouted
+            );
+        }
+    }
+
+    if let Some(name) = matchup {
+        println!();
+        println!("{:<24} {:<24} {:>5}", "Vehicle", "Enemy", "Kills");
+        for matchup in stats.matchups(name) {
+            println!(
+                "{:<24} {:<24} {:>5}",
+                matchup.vehicle, matchup.enemy, matchup.kills
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Summarize every report read from `input`
+///
+/// A report ends at the first empty line after its total line, or at the end
+/// of the input. Reports that fail to parse are reported on `errors`.
+fn repl(input: impl BufRead, mut output: impl Write, mut errors: impl Write) -> io::Result<()> {
+    let mut buffer = String::new();
+    let mut complete = false;
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            if complete {
+                summarize(&buffer, &mut output, &mut errors)?;
+                buffer.clear();
+                complete = false;
+            } else if !buffer.is_empty() {
+                buffer.push('\n');
+            }
+            continue;
+        }
+
+        complete = is_total_line(&line);
+        buffer.push_str(&line);
+        buffer.push('\n');
+    }
+
+    if !buffer.trim().is_empty() {
+        summarize(&buffer, &mut output, &mut errors)?;
+    }
+
+    Ok(())
+}
+
+/// The total line ends every report, e.g. `Total: 19796 SL, 2218 CRP, 2118 RP`
+fn is_total_line(line: &str) -> bool {
+    line.contains(" CRP, ") && line.trim_end().ends_with(" RP")
+}
+
+fn summarize(input: &str, mut output: impl Write, mut errors: impl Write) -> io::Result<()> {
+    match wt_battle_report::from_str(input) {
+        Ok(report) => writeln!(
+            output,
+            "{}: {:?} in {}, {} kills, {} SL, {} RP",
+            report.session_id,
+            report.result,
+            report.mission_name,
+            report.score_inputs().kills,
+            report.balance.silverlions(),
+            report.balance.research(),
+        ),
+        Err(err) => writeln!(errors, "{err}"),
+    }
+}
+
+/// Load every report in `paths`, descending one level into directories
+///
+/// Files that fail to parse are reported on stderr and skipped.
+fn load_reports(paths: &[PathBuf]) -> Result<Vec<BattleReport>, String> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let entries = fs::read_dir(path).map_err(|err| format!("{}: {err}", path.display()))?;
+            for entry in entries {
+                let path = entry
+                    .map_err(|err| format!("{}: {err}", path.display()))?
+                    .path();
+                if path.is_file() {
+                    files.push(path);
+                }
+            }
+        } else {
+            files.push(path.clone());
+        }
+    }
+    files.sort();
+
+    let mut reports = Vec::new();
+    for file in files {
+        match load_report(&file) {
+            Ok(report) => reports.push(report),
+            Err(message) => eprintln!("skipping {message}"),
+        }
+    }
+
+    Ok(reports)
+}
+
+fn load_report(path: &Path) -> Result<BattleReport, String> {
+    if path.extension().is_some_and(|extension| extension == "gz") {
+        return load_gz_report(path);
+    }
+    wt_battle_report::from_path(path).map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "flate2")]
+fn load_gz_report(path: &Path) -> Result<BattleReport, String> {
+    fs::File::open(path)
+        .map_err(wt_battle_report::ReadError::from)
+        .and_then(|file| wt_battle_report::from_gz_reader(io::BufReader::new(file)))
+        .map_err(|err| format!("{}: {err}", path.display()))
+}
+
+#[cfg(not(feature = "flate2"))]
+fn load_gz_report(path: &Path) -> Result<BattleReport, String> {
+    Err(format!(
+        "{}: reading .gz files requires the flate2 feature",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    fn run_show(args: &[&str], input: &str) -> (Result<(), String>, String) {
+        let args = args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let mut output = Vec::new();
+        let result = super::show(&args, input.as_bytes(), &mut output);
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn show_prints_json_from_stdin() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let (result, output) = run_show(&["--json"], &input);
+
+        assert_eq!(result, Ok(()));
+        let report = serde_json::from_str::<wt_battle_report::BattleReport>(&output).unwrap();
+        assert_eq!(report.session_id, "1603c1c00028a36");
+    }
+
+    #[test]
+    fn show_prints_every_file() {
+        let (result, output) = run_show(
+            &[
+                "./data/1603c1c00028a36.report",
+                "./data/1712a4f0003b2c5.report",
+            ],
+            "",
+        );
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(output.matches("BattleReport {").count(), 2);
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn show_decompresses_gz_files() {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join("wt-battle-report-show-test.report.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder
+            .write_all(&std::fs::read("./data/1603c1c00028a36.report").unwrap())
+            .unwrap();
+        encoder.finish().unwrap();
+
+        let (result, output) = run_show(&[path.to_str().unwrap()], "");
+        assert_eq!(result, Ok(()));
+        assert!(output.contains("\"1603c1c00028a36\""), "{output}");
+    }
+
+    #[test]
+    fn show_fails_on_broken_input() {
+        let (result, output) = run_show(&[], "Victory in the mission!\n");
+        let err = result.unwrap_err();
+        assert!(
+            err.starts_with("<stdin>: Error parsing battle report"),
+            "{err}"
+        );
+        assert_eq!(output, "");
+
+        let (result, output) = run_show(
+            &["./data/missing.report", "./data/1712a4f0003b2c5.report"],
+            "",
+        );
+        assert_eq!(result, Err("failed to parse 1 of 2 reports".to_string()));
+        assert_eq!(output.matches("BattleReport {").count(), 1);
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn debouncer_waits_for_quiet_files() {
+        use std::{path::PathBuf, time::Instant};
+
+        use super::{Debouncer, DEBOUNCE};
+
+        let start = Instant::now();
+        let report = PathBuf::from("a.report");
+        let mut debouncer = Debouncer::default();
+        for offset in 0..5 {
+            debouncer.event(report.clone(), start + DEBOUNCE / 10 * offset);
+        }
+
+        assert_eq!(debouncer.due(start + DEBOUNCE), Vec::<PathBuf>::new());
+        assert_eq!(debouncer.due(start + DEBOUNCE * 2), vec![report.clone()]);
+        assert_eq!(debouncer.due(start + DEBOUNCE * 3), Vec::<PathBuf>::new());
+
+        // a file that failed to parse is retried on its next event
+        debouncer.event(report.clone(), start + DEBOUNCE * 3);
+        assert_eq!(debouncer.due(start + DEBOUNCE * 4), vec![report.clone()]);
+
+        debouncer.done(report.clone());
+        debouncer.event(report, start + DEBOUNCE * 4);
+        assert_eq!(debouncer.due(start + DEBOUNCE * 5), Vec::<PathBuf>::new());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn compare_corpus_against_exported_json() {
+        let old = std::env::temp_dir().join("wt-battle-report-compare-corpus-test");
+        std::fs::create_dir_all(&old).unwrap();
+        for name in [
+            "1603c1c00028a36",
+            "160409b0002a1af",
+            "1703c87000d01c0-items",
+        ] {
+            let mut report =
+                wt_battle_report::from_path(format!("./data/crlf/{name}.report")).unwrap();
+            if name == "160409b0002a1af" {
+                report.activity += 1;
+            }
+            std::fs::write(
+                old.join(format!("{name}.json")),
+                report.to_json_string().unwrap(),
+            )
+            .unwrap();
+        }
+
+        let args = [old.to_str().unwrap().to_string(), "./data/crlf".to_string()];
+        let mut output = Vec::new();
+        let result = super::compare_corpus(&args, &mut output);
+        std::fs::remove_dir_all(&old).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(result, Err("3 of 5 reports changed".to_string()));
+        let lines = output.lines().collect::<Vec<_>>();
+        assert!(lines[0].starts_with("./data/crlf/1603c1c00028a36-mixed.report: failed before: "));
+        assert_eq!(
+            lines[1],
+            "./data/crlf/160409b0002a1af.report: differs in activity"
+        );
+        assert!(output.ends_with(
+            "Equal:       2\n\
+            Different:   1\n\
+            Only before: 0\n\
+            Only now:    2\n\
+            Both failed: 0\n    \
+            activity: 1\n"
+        ));
+    }
+
+    fn run_repl(input: &str) -> (String, String) {
+        let mut output = Vec::new();
+        let mut errors = Vec::new();
+        super::repl(input.as_bytes(), &mut output, &mut errors).unwrap();
+        (
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(errors).unwrap(),
+        )
+    }
+
+    #[test]
+    fn repl_summarizes_each_report() {
+        let first = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let second = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        let (output, errors) = run_repl(&format!("{first}\n\n{second}"));
+
+        assert_eq!(
+            output,
+            "1603c1c00028a36: Loss in [Domination] Poland (winter), 7 kills, 19796 SL, 2118 RP\n\
+            1712a4f0003b2c5: Win in [Domination] Sea of Japan, 2 kills, 5765 SL, 1194 RP\n"
+        );
+        assert_eq!(errors, "");
+    }
+
+    #[test]
+    fn repl_continues_after_error() {
+        let report = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        let (output, errors) = run_repl(&format!(
+            "Victory in the mission!\nTotal: 1 SL, 1 CRP, 1 RP\n\n{report}\n"
+        ));
+
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.starts_with("1712a4f0003b2c5: "));
+        assert!(
+            errors.starts_with("Error parsing battle report"),
+            "{errors}"
+        );
+    }
+}
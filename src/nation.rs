@@ -0,0 +1,82 @@
+//! Vehicle Nations
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::battle_report::normalize_vehicle_name;
+
+/// Nation whose tech tree a vehicle belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Nation {
+    Usa,
+    Germany,
+    Ussr,
+    Britain,
+    Japan,
+    China,
+    Italy,
+    France,
+    Sweden,
+    Israel,
+    /// The vehicle is not in the lookup table
+    Unknown,
+}
+
+/// Known vehicles by their normalized name
+///
+/// This only covers vehicles seen in reports so far and grows as needed.
+const VEHICLES: &[(&str, Nation)] = &[
+    ("B18B", Nation::Sweden),
+    ("Beaufighter Mk VIc", Nation::Britain),
+    ("Bf 109 F-4", Nation::Germany),
+    ("Chi-Nu II", Nation::Japan),
+    ("Chi-To Late", Nation::Japan),
+    ("Concept 3", Nation::Britain),
+    ("Fletcher", Nation::Usa),
+    ("Hurricane Mk IV", Nation::Britain),
+    ("ISU-122", Nation::Ussr),
+    ("Ju 87 D-5", Nation::Germany),
+    ("KV-1 (L-11)", Nation::Ussr),
+    ("KV-85", Nation::Ussr),
+    ("M36 GMC", Nation::Usa),
+    ("M6A1", Nation::Usa),
+    ("Pe-8", Nation::Ussr),
+    ("Porter", Nation::Usa),
+    ("Pvkv II", Nation::Sweden),
+    ("Sherman Firefly", Nation::Britain),
+    ("Spitfire F Mk IX", Nation::Britain),
+    ("T-34 (1940)", Nation::Ussr),
+    ("T-34 (1942)", Nation::Ussr),
+    ("Typhoon Mk Ib", Nation::Britain),
+    ("Wyvern S4", Nation::Britain),
+    ("YaG-10 (29-K)", Nation::Ussr),
+    ("Z-25", Nation::Germany),
+];
+
+impl Nation {
+    /// Look up the nation of a vehicle by its name as it appears in a report
+    pub fn of_vehicle(name: &str) -> Self {
+        let name = normalize_vehicle_name(name);
+        VEHICLES
+            .iter()
+            .find(|(vehicle, _)| *vehicle == name)
+            .map_or(Self::Unknown, |(_, nation)| *nation)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
+
+    use super::Nation;
+
+    #[rstest]
+    #[case("Concept 3", Nation::Britain)]
+    #[case("ISU-122()", Nation::Ussr)]
+    #[case("Maus", Nation::Unknown)]
+    fn nation_of_vehicle(#[case] name: &str, #[case] nation: Nation) {
+        assert_eq!(Nation::of_vehicle(name), nation);
+    }
+}
@@ -1,142 +1,1024 @@
 //! Battle Report Parser
 
-use std::fmt::Debug;
+use std::{fmt::Debug, ops::RangeInclusive};
 
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_until, take_while},
-    character::complete::{alpha1, digit1, hex_digit1, line_ending, space1, u32, u8},
-    combinator::{map, map_parser, opt, success, value},
-    error::{context, convert_error, VerboseError},
-    multi::{many0, many1, many_m_n, separated_list1},
+    bytes::complete::{is_not, tag, take_until, take_while, take_while1, take_while_m_n},
+    character::complete::{
+        alpha1, anychar, digit1, hex_digit1, line_ending, not_line_ending, one_of, satisfy, space0,
+        space1, u32, u8,
+    },
+    combinator::{
+        all_consuming, consumed, cut, eof, map, map_opt, map_parser, map_res, not, opt, peek,
+        recognize, success, value, verify,
+    },
+    error::{context, convert_error, VerboseError, VerboseErrorKind},
+    multi::{many0, many1, many_till, separated_list1},
     sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
+    Offset,
 };
 
 use crate::{
-    battle_report::BattleReport, Award, BattleResult, Event, ModificationResearch, Reward, Vehicle,
-    VehicleResearch,
+    battle_report::{
+        BattleReport, BonusKind, EventKind, EventTable, PartialBattleReport, RewardBreakdown,
+    },
+    borrowed::{
+        AwardRef, BattleReportRef, CompletedTaskRef, CrewExperienceRef, EventRef,
+        ModificationResearchRef, UsedItemRef, VehicleRef, VehicleResearchRef, WagerProgressRef,
+    },
+    language::{detect_language, Language},
+    BattleResult, Reward,
 };
 
 type IResult<'a, O> = nom::IResult<&'a str, O, VerboseError<&'a str>>;
 
-const INDENT: &str = "    "; // 4 spaces
+/// characters used to group digits in large numbers (space, NBSP and narrow NBSP)
+const GROUP_SEPARATORS: &str = " \u{a0}\u{202f}";
+
+/// headings of the debriefing screen, which players sometimes copy instead of
+/// the battle report
+const DEBRIEFING_HEADINGS: &[&str] = &["My results", "Squad results", "Meine Ergebnisse"];
 
 #[derive(Debug, thiserror::Error)]
-#[error("Error parsing battle report: {message}")]
-pub struct Error {
-    message: String,
+pub enum Error {
+    /// Boxed to keep results with this error small
+    #[error(transparent)]
+    Syntax(#[from] Box<SyntaxError>),
+    /// The input is text of a different screen, not a battle report
+    #[error("Input is not a battle report: {hint}")]
+    WrongReportFormat { hint: String },
 }
 
-pub fn parse(input: &str) -> Result<BattleReport, Error> {
-    battle_report(input)
-        .map(|(_, report)| report)
-        .map_err(|err| {
-            let message = if let nom::Err::Error(err) = err {
-                convert_error(input, err)
-            } else {
-                "Unknown error".to_string()
+impl Error {
+    fn new(input: &str, err: nom::Err<VerboseError<&str>>) -> Self {
+        if looks_like_debriefing(input) {
+            return Self::WrongReportFormat {
+                hint: "this looks like the text of the debriefing screen. Open the battle \
+                    report in the message log and use its copy button instead"
+                    .to_string(),
             };
-            Error { message }
+        }
+        Self::Syntax(Box::new(SyntaxError::new(input, err)))
+    }
+}
+
+/// the input starts like the debriefing screen instead of with a result line
+fn looks_like_debriefing(input: &str) -> bool {
+    detect_language(input).is_none()
+        && input
+            .lines()
+            .take(5)
+            .any(|line| DEBRIEFING_HEADINGS.contains(&line.trim()))
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Error parsing battle report: {} at line {line}, column {column}: {snippet:?}",
+    section.as_deref().unwrap_or("unexpected input")
+)]
+pub struct SyntaxError {
+    /// Output of [`nom::error::convert_error`]
+    verbose: String,
+    /// Section of the report the parser failed in, like "activity"
+    pub section: Option<String>,
+    /// Every context the parser failed in, the section first and the
+    /// innermost last, like `["awards", "row ending"]`
+    pub context: Vec<String>,
+    /// Byte offset in the report the parser failed at
+    pub offset: usize,
+    /// Line of the report the parser failed on, starting at 1
+    pub line: usize,
+    /// Character in the line the parser failed on, starting at 1
+    pub column: usize,
+    /// The line the parser failed on
+    pub snippet: String,
+}
+
+impl SyntaxError {
+    fn new(input: &str, err: nom::Err<VerboseError<&str>>) -> Self {
+        let (verbose, context, rest) = match err {
+            nom::Err::Error(err) | nom::Err::Failure(err) => {
+                let rest = err.errors.first().map_or("", |(rest, _)| *rest);
+                let context = err
+                    .errors
+                    .iter()
+                    .rev()
+                    .filter_map(|(_, kind)| match kind {
+                        VerboseErrorKind::Context(context) => Some(context.to_string()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                (convert_error(input, err), context, rest)
+            }
+            nom::Err::Incomplete(_) => ("Unexpected end of input".to_string(), Vec::new(), ""),
+        };
+
+        let offset = if rest.is_empty() {
+            input.len()
+        } else {
+            input.offset(rest)
+        };
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+
+        Self {
+            verbose,
+            section: context.first().cloned(),
+            context,
+            offset,
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            snippet: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        }
+    }
+
+    /// Trace through every parser that failed, for debugging
+    pub fn verbose(&self) -> &str {
+        &self.verbose
+    }
+}
+
+/// Options controlling how forgiving the parser is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Fail on anything unexpected.
+    ///
+    /// When disabled the parser tries to recover from damaged input and
+    /// reports everything it had to guess or skip as a [`Warning`].
+    pub strict: bool,
+    /// Language of the client that wrote the report
+    pub language: Language,
+}
+
+impl ParseOptions {
+    pub fn strict() -> Self {
+        Self {
+            strict: true,
+            language: Language::English,
+        }
+    }
+
+    pub fn lenient() -> Self {
+        Self {
+            strict: false,
+            language: Language::English,
+        }
+    }
+
+    pub fn with_language(self, language: Language) -> Self {
+        Self { language, ..self }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::strict()
+    }
+}
+
+/// Something the parser recovered from in lenient mode
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message}")]
+pub struct Warning {
+    pub message: String,
+    /// Lines of the report the warning is about, starting at 1
+    pub lines: Option<RangeInclusive<usize>>,
+}
+
+impl Warning {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            lines: None,
+        }
+    }
+
+    /// Attach the lines `part` spans in `report`
+    fn at(self, report: &str, part: &str) -> Self {
+        let start = report[..report.offset(part)].matches('\n').count() + 1;
+        let end = start + part.trim_end_matches(['\r', '\n']).matches('\n').count();
+        Self {
+            lines: Some(start..=end),
+            ..self
+        }
+    }
+}
+
+/// A total stated in a report that does not add up
+///
+/// Row counts are not checked here, a table with fewer or more rows than its
+/// header announces already fails to parse.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationIssue {
+    /// The rows of a table do not sum up to the reward in its header
+    #[error(
+        "{table}: rows sum up to {} SL {} RP, but the header states {} SL {} RP",
+        summed.silverlions(),
+        summed.research(),
+        stated.silverlions(),
+        stated.research()
+    )]
+    TableTotal {
+        table: String,
+        stated: Box<Reward>,
+        summed: Box<Reward>,
+    },
+    /// The silver lions on the total line are not the earned silver lions
+    /// minus repair and purchase costs
+    #[error("total states {stated} SL, but earned minus costs is {computed} SL")]
+    Balance { stated: u32, computed: i64 },
+    /// The convertible research on the total line differs from the earned
+    /// and automatically converted research
+    #[error("total states {stated} CRP, but {earned} CRP were earned")]
+    ConvertibleResearch { stated: u32, earned: u32 },
+}
+
+/// A row of a table in the format of the report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRow {
+    /// Seconds since the start of the battle
+    pub time: u32,
+    pub vehicle: String,
+    /// Missing for targets without a name, like an air field
+    pub enemy_vehicle: Option<String>,
+    /// The row is marked with `×`
+    pub assisted: bool,
+    pub reward: Reward,
+}
+
+impl From<Row<'_>> for TableRow {
+    fn from(row: Row<'_>) -> Self {
+        Self {
+            time: row.time,
+            vehicle: row.vehicle.to_string(),
+            enemy_vehicle: row.enemy_vehicle.map(str::to_string),
+            assisted: row.assisted,
+            reward: row.reward,
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<BattleReport, Error> {
+    parse_with_options(input, ParseOptions::strict()).map(|(report, _)| report)
+}
+
+pub fn parse_with_language(input: &str, language: Language) -> Result<BattleReport, Error> {
+    parse_with_options(input, ParseOptions::strict().with_language(language))
+        .map(|(report, _)| report)
+}
+
+pub fn parse_with_options(
+    input: &str,
+    options: ParseOptions,
+) -> Result<(BattleReport, Vec<Warning>), Error> {
+    parse_sections(input, options)
+        .into_result()
+        .map(|(report, warnings, _)| (report, warnings))
+}
+
+/// Parse a report and check the totals it states against its rows
+pub fn parse_validated(
+    input: &str,
+    options: ParseOptions,
+) -> Result<(BattleReport, Vec<ValidationIssue>), Error> {
+    parse_sections(input, options)
+        .into_result()
+        .map(|(report, _, issues)| (report, issues))
+}
+
+/// Parse a report without copying the names in it
+///
+/// Skipping chat lines would mean copying the report, so this always parses
+/// strictly.
+pub fn parse_borrowed(input: &str, language: Language) -> Result<BattleReportRef<'_>, Error> {
+    let options = ParseOptions::strict().with_language(language);
+    let mut parts = ReportParts::default();
+    battle_report(input, options, &mut parts, &mut Vec::new(), &mut Vec::new())
+        .and_then(|(rest, ())| end_of_report(input, rest, options, &mut Vec::new()))
+        .map_err(|err| Error::new(input, err))?;
+    Ok(parts
+        .into_report()
+        .expect("a report without errors has every section"))
+}
+
+/// Parse reports pasted one after another
+///
+/// Reports are separated by at least one blank line and each is parsed
+/// strictly in the language detected from its first line.
+pub fn parse_many(input: &str) -> Result<Vec<BattleReport>, Error> {
+    let mut reports = Vec::new();
+    let mut rest = input;
+    loop {
+        let options =
+            ParseOptions::strict().with_language(detect_language(rest).unwrap_or_default());
+        let mut parts = ReportParts::default();
+        let (next, ()) = battle_report(rest, options, &mut parts, &mut Vec::new(), &mut Vec::new())
+            .map_err(|err| Error::new(input, err))?;
+        reports.push(
+            parts
+                .into_report()
+                .expect("a report without errors has every section")
+                .to_owned(),
+        );
+        if next.trim().is_empty() {
+            return Ok(reports);
+        }
+        (rest, _) = context(
+            "report separator",
+            pair(line_ending, many1(pair(space0, line_ending))),
+        )(next)
+        .map_err(|err| Error::new(input, err))?;
+    }
+}
+
+/// Parse a single table into its name and rows
+pub fn parse_table(input: &str) -> Result<(String, Vec<TableRow>), Error> {
+    let (_, table) = all_consuming(table)(input)
+        .map_err(|err| Error::Syntax(Box::new(SyntaxError::new(input, err))))?;
+    Ok((
+        table.name.to_string(),
+        table.rows.into_iter().map(TableRow::from).collect(),
+    ))
+}
+
+/// Parse as many sections of a report as possible
+///
+/// Returns the sections parsed before the first error, and the error.
+pub fn parse_partial(input: &str, options: ParseOptions) -> (PartialBattleReport, Option<Error>) {
+    let sections = parse_sections(input, options);
+    (sections.report, sections.error)
+}
+
+/// everything [`parse_sections`] found in a report
+struct Sections {
+    report: PartialBattleReport,
+    warnings: Vec<Warning>,
+    issues: Vec<ValidationIssue>,
+    error: Option<Error>,
+}
+
+impl Sections {
+    fn into_result(self) -> Result<(BattleReport, Vec<Warning>, Vec<ValidationIssue>), Error> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok((
+                self.report
+                    .into_report()
+                    .expect("a report without errors has every section"),
+                self.warnings,
+                self.issues,
+            )),
+        }
+    }
+}
+
+fn parse_sections(input: &str, options: ParseOptions) -> Sections {
+    let mut warnings = Vec::new();
+    let mut removed = Vec::new();
+    let filtered;
+    let input = if options.strict {
+        input
+    } else {
+        filtered = skip_chat_lines(input, &mut warnings, &mut removed);
+        &filtered
+    };
+
+    let mut parts = ReportParts::default();
+    let mut more_warnings = Vec::new();
+    let mut issues = Vec::new();
+    let err = battle_report(input, options, &mut parts, &mut more_warnings, &mut issues)
+        .and_then(|(rest, ())| end_of_report(input, rest, options, &mut more_warnings))
+        .err()
+        .map(|err| Error::new(input, err));
+    warnings.extend(more_warnings.into_iter().map(|warning| Warning {
+        lines: warning.lines.map(|lines| {
+            original_line(&removed, *lines.start())..=original_line(&removed, *lines.end())
+        }),
+        ..warning
+    }));
+
+    Sections {
+        report: parts.to_partial(),
+        warnings,
+        issues,
+        error: err,
+    }
+}
+
+/// remove chat messages that were copied along with the report
+///
+/// The numbers of the removed lines are added to `removed` in ascending order.
+fn skip_chat_lines(input: &str, warnings: &mut Vec<Warning>, removed: &mut Vec<usize>) -> String {
+    let mut output = String::with_capacity(input.len());
+    for (index, line) in input.split_inclusive('\n').enumerate() {
+        if chat_line(line).is_ok() {
+            warnings.push(
+                Warning::new(format!("skipped chat message {:?}", line.trim_end())).at(input, line),
+            );
+            removed.push(index + 1);
+        } else {
+            output.push_str(line);
+        }
+    }
+    output
+}
+
+/// map a line of the report without the `removed` lines back to the original
+fn original_line(removed: &[usize], line: usize) -> usize {
+    removed.iter().fold(
+        line,
+        |line, removed| if *removed <= line { line + 1 } else { line },
+    )
+}
+
+/// parse a chat message
+///
+/// # Examples
+/// ```text
+/// [Squad] Chronophylos: gg
+/// ```
+/// ```text
+/// [All] Chronophylos (Concept 3): o7
+/// ```
+fn chat_line(input: &str) -> IResult<'_, &str> {
+    recognize(tuple((
+        delimited(tag("["), is_not("]\r\n"), tag("]")),
+        space1,
+        is_not(":\r\n"),
+        tag(":"),
+        not_line_ending,
+    )))(input)
+}
+
+/// the sections of a report parsed so far, borrowing from the report
+///
+/// Mirrors [`PartialBattleReport`].
+#[derive(Debug, Default)]
+struct ReportParts<'a> {
+    session_id: Option<&'a str>,
+    #[cfg_attr(not(feature = "timestamps"), allow(dead_code))]
+    session_time: Option<&'a str>,
+    result: Option<BattleResult>,
+    mission_name: Option<&'a str>,
+    player_name: Option<&'a str>,
+    squadron: Option<&'a str>,
+    events: Option<Vec<EventRef<'a>>>,
+    event_tables: Option<Vec<EventTable>>,
+    awards: Option<Vec<AwardRef<'a>>>,
+    awards_header_count: Option<usize>,
+    awards_header_total: Option<Reward>,
+    reward_for_winning: Option<Reward>,
+    first_victory_bonus: Option<Reward>,
+    other_awards: Option<Reward>,
+    completed_tasks: Option<Vec<CompletedTaskRef<'a>>>,
+    wagers: Option<Vec<WagerProgressRef<'a>>>,
+    vehicles: Option<Vec<VehicleRef<'a>>>,
+    crew_experience: Option<Vec<CrewExperienceRef<'a>>>,
+    /// in hundredths of a percent
+    activity: Option<u16>,
+    damaged_vehicles: Option<Vec<&'a str>>,
+    automatic_repair: Option<u32>,
+    automatic_purchases: Option<u32>,
+    vehicle_research: Option<Vec<VehicleResearchRef<'a>>>,
+    modification_research: Option<Vec<ModificationResearchRef<'a>>>,
+    squadron_research: Option<u32>,
+    used_items: Option<Vec<UsedItemRef<'a>>>,
+    earned_rewards: Option<Reward>,
+    converted_research: Option<u32>,
+    balance: Option<Reward>,
+}
+
+impl<'a> ReportParts<'a> {
+    /// the session time, `None` if it is missing or not a valid date
+    #[cfg(feature = "timestamps")]
+    fn session_time(&self) -> Option<crate::NaiveDateTime> {
+        self.session_time
+            .and_then(crate::datetime::parse_session_time)
+    }
+
+    fn to_partial(&self) -> PartialBattleReport {
+        fn owned<T, U>(items: &Option<Vec<T>>, to_owned: impl Fn(&T) -> U) -> Option<Vec<U>> {
+            items
+                .as_ref()
+                .map(|items| items.iter().map(to_owned).collect())
+        }
+
+        PartialBattleReport {
+            session_id: self.session_id.map(str::to_string),
+            #[cfg(feature = "timestamps")]
+            session_time: self.session_time(),
+            result: self.result,
+            mission_name: self.mission_name.map(str::to_string),
+            player_name: self.player_name.map(str::to_string),
+            squadron: self.squadron.map(str::to_string),
+            events: owned(&self.events, EventRef::to_owned),
+            event_tables: self.event_tables.clone(),
+            awards: owned(&self.awards, AwardRef::to_owned),
+            awards_header_count: self.awards_header_count,
+            awards_header_total: self.awards_header_total.clone(),
+            reward_for_winning: self.reward_for_winning.clone(),
+            first_victory_bonus: self.first_victory_bonus.clone(),
+            other_awards: self.other_awards.clone(),
+            completed_tasks: owned(&self.completed_tasks, CompletedTaskRef::to_owned),
+            wagers: owned(&self.wagers, WagerProgressRef::to_owned),
+            vehicles: owned(&self.vehicles, VehicleRef::to_owned),
+            crew_experience: owned(&self.crew_experience, CrewExperienceRef::to_owned),
+            activity: self.activity.map(whole_percent),
+            activity_basis_points: self.activity,
+            damaged_vehicles: owned(&self.damaged_vehicles, |name| name.to_string()),
+            automatic_repair: self.automatic_repair,
+            automatic_purchases: self.automatic_purchases,
+            vehicle_research: owned(&self.vehicle_research, VehicleResearchRef::to_owned),
+            modification_research: owned(
+                &self.modification_research,
+                ModificationResearchRef::to_owned,
+            ),
+            squadron_research: self.squadron_research,
+            used_items: owned(&self.used_items, UsedItemRef::to_owned),
+            earned_rewards: self.earned_rewards.clone(),
+            converted_research: self.converted_research,
+            balance: self.balance.clone(),
+        }
+    }
+
+    fn into_report(self) -> Option<BattleReportRef<'a>> {
+        Some(BattleReportRef {
+            session_id: self.session_id?,
+            #[cfg(feature = "timestamps")]
+            session_time: self.session_time(),
+            result: self.result?,
+            mission_name: self.mission_name?,
+            player_name: self.player_name,
+            squadron: self.squadron,
+            events: self.events?,
+            event_tables: self.event_tables?,
+            awards: self.awards?,
+            awards_header_count: self.awards_header_count?,
+            awards_header_total: self.awards_header_total?,
+            reward_for_winning: self.reward_for_winning,
+            first_victory_bonus: self.first_victory_bonus,
+            other_awards: self.other_awards?,
+            completed_tasks: self.completed_tasks?,
+            wagers: self.wagers?,
+            vehicles: self.vehicles?,
+            crew_experience: self.crew_experience?,
+            activity: whole_percent(self.activity?),
+            activity_basis_points: self.activity?,
+            damaged_vehicles: self.damaged_vehicles?,
+            automatic_repair: self.automatic_repair?,
+            automatic_purchases: self.automatic_purchases?,
+            vehicle_research: self.vehicle_research?,
+            modification_research: self.modification_research?,
+            squadron_research: self.squadron_research,
+            used_items: self.used_items?,
+            earned_rewards: self.earned_rewards?,
+            converted_research: self.converted_research,
+            balance: self.balance?,
         })
+    }
 }
 
-fn battle_report(input: &str) -> IResult<BattleReport> {
-    let (input, (result, mission_name)) = context("first line", result_line)(input)?;
+/// parse a report into `partial`, section by section
+///
+/// Totals that do not add up are collected in `issues`.
+fn battle_report<'a>(
+    input: &'a str,
+    options: ParseOptions,
+    partial: &mut ReportParts<'a>,
+    warnings: &mut Vec<Warning>,
+    issues: &mut Vec<ValidationIssue>,
+) -> IResult<'a, ()> {
+    let report = input;
+    let language = options.language;
 
-    let (
-        input,
-        (
-            events,
-            awards,
-            vehicles,
-            reward_for_winning,
-            other_awards,
-            earned_rewards,
-            activity,
-            damaged_vehicles,
-            automatic_repair,
-            automatic_purchases,
-            _,
-            vehicle_research,
-            modification_research,
-            _,
-            session_id,
-            (balance, _raw_research),
-        ),
-    ) = tuple((
-        context("events", parse_events),
-        context("awards", award_table),
-        context("activity and time played", vehicle_tables),
-        context("reward for winning", opt(parse_reward_for_winning)),
-        context("other awards", parse_other_awards),
-        context("earned", parse_earned),
-        context("activity", parse_activity),
-        context("damaged vehicles", parse_damaged_vehicles),
-        context("automatic repair", parse_automatic_repair),
-        context("automatic purchase", parse_automatic_purchase),
-        line_ending,
-        context("researched vehicles", opt(parse_researched_units)),
+    let (input, ((result, mission_name), player)) = match pair(
+        context("first line", result_line(language)),
         context(
-            "researched modifications",
-            opt(parse_researched_modifications),
+            "player",
+            terminated(opt(player_line(language)), line_ending),
         ),
-        context("used items", opt(parse_used_items)),
-        context("session id", parse_session_id),
-        context("total", parse_total),
-    ))(input)?;
+    )(input)
+    {
+        Ok(ok) => ok,
+        Err(nom::Err::Error(_)) if !options.strict => {
+            let (input, _) = context("first table", skip_to_table)(input)?;
+            warnings.push(Warning::new(
+                "report does not start with a result line; battle result and mission name are unknown",
+            ));
+            (input, ((BattleResult::Unknown, ""), None))
+        }
+        Err(err) => return Err(err),
+    };
+    partial.result = Some(result);
+    partial.mission_name = Some(mission_name);
+    if let Some((squadron, name)) = player {
+        partial.player_name = Some(name);
+        partial.squadron = squadron;
+    }
 
-    Ok((
+    let (
         input,
-        BattleReport {
-            session_id,
-            result,
-            mission_name: mission_name.to_string(),
+        EventTables {
             events,
+            tables,
+            duplicates,
+            issues: event_issues,
+        },
+    ) = context("events", parse_events(language))(input)?;
+    partial.events = Some(events);
+    partial.event_tables = Some(tables);
+    issues.extend(event_issues);
+    for duplicate in duplicates {
+        if options.strict {
+            return error(duplicate, "duplicated row exceeds the table header count");
+        }
+        warnings.push(
+            Warning::new(format!("dropped duplicated row {:?}", duplicate.trim()))
+                .at(report, duplicate),
+        );
+    }
+
+    let mut skipped = Vec::new();
+    let (
+        input,
+        AwardTable {
             awards,
-            reward_for_winning,
-            other_awards,
-            vehicles,
-            activity,
-            damaged_vehicles,
-            automatic_repair,
-            automatic_purchases,
-            vehicle_research: vehicle_research.unwrap_or_default(),
-            modification_research: modification_research.unwrap_or_default(),
-            earned_rewards,
-            balance,
+            count,
+            total,
+            issue: award_issue,
         },
-    ))
+    ) = section(input, "awards", options, &mut skipped, award_table)?;
+    partial.awards = Some(awards);
+    partial.awards_header_count = Some(count);
+    partial.awards_header_total = Some(total);
+    issues.extend(award_issue);
+    let (input, (vehicles, vehicle_issues, unplayed)) = section(
+        input,
+        "activity and time played",
+        options,
+        &mut skipped,
+        vehicle_tables(options),
+    )?;
+    for (name, row) in unplayed {
+        warnings.push(
+            Warning::new(format!(
+                "vehicle {name:?} has no time played row; its activity and time played are zero"
+            ))
+            .at(report, row),
+        );
+    }
+    partial.vehicles = Some(vehicles);
+    issues.extend(vehicle_issues);
+    let (input, crew_experience) = optional_section(
+        input,
+        "crew experience",
+        options,
+        &mut skipped,
+        parse_crew_experience(language),
+    )?;
+    partial.crew_experience = Some(crew_experience.unwrap_or_default());
+    let (input, ()) = collect_wagers(input, language, partial)?;
+    // clients list the first victory bonus before or after the reward for
+    // winning, only the later position may skip lines
+    let (input, first_victory_bonus) = opt(parse_first_victory_bonus(language))(input)?;
+    let (input, reward_for_winning) = optional_section(
+        input,
+        "reward for winning",
+        options,
+        &mut skipped,
+        parse_reward_for_winning(language),
+    )?;
+    partial.reward_for_winning = reward_for_winning;
+    let (input, first_victory_bonus) = match first_victory_bonus {
+        Some(bonus) => (input, Some(bonus)),
+        None => optional_section(
+            input,
+            "first victory bonus",
+            options,
+            &mut skipped,
+            parse_first_victory_bonus(language),
+        )?,
+    };
+    partial.first_victory_bonus = first_victory_bonus;
+    let (input, ()) = collect_wagers(input, language, partial)?;
+    let (input, other_awards) = section(
+        input,
+        "other awards",
+        options,
+        &mut skipped,
+        parse_other_awards(language),
+    )?;
+    partial.other_awards = Some(other_awards);
+    let (input, ()) = collect_wagers(input, language, partial)?;
+    let (input, completed_tasks) = optional_section(
+        input,
+        "completed tasks",
+        options,
+        &mut skipped,
+        parse_completed_tasks(language),
+    )?;
+    partial.completed_tasks = Some(completed_tasks.unwrap_or_default());
+    let (input, ()) = collect_wagers(input, language, partial)?;
+    let (input, earned_rewards) = section(
+        input,
+        "earned",
+        options,
+        &mut skipped,
+        parse_earned(language),
+    )?;
+    partial.earned_rewards = Some(earned_rewards.clone());
+    let (input, ()) = collect_wagers(input, language, partial)?;
+    let (input, converted_research) = optional_section(
+        input,
+        "converted research",
+        options,
+        &mut skipped,
+        parse_converted_research(language),
+    )?;
+    partial.converted_research = converted_research;
+    let (input, ()) = collect_wagers(input, language, partial)?;
+    let (input, activity) = section(
+        input,
+        "activity",
+        options,
+        &mut skipped,
+        parse_activity(language),
+    )?;
+    partial.activity = Some(activity);
+    let (input, ()) = collect_wagers(input, language, partial)?;
+    let (input, damaged_vehicles) = section(
+        input,
+        "damaged vehicles",
+        options,
+        &mut skipped,
+        parse_damaged_vehicles(language),
+    )?;
+    partial.damaged_vehicles = Some(damaged_vehicles);
+    let (input, ()) = collect_wagers(input, language, partial)?;
+    // reports leave out costs that did not occur
+    let (input, automatic_repair) = optional_section(
+        input,
+        "automatic repair",
+        options,
+        &mut skipped,
+        parse_automatic_repair(language),
+    )?;
+    let automatic_repair = automatic_repair.unwrap_or_default();
+    partial.automatic_repair = Some(automatic_repair);
+    let (input, ()) = collect_wagers(input, language, partial)?;
+    let (input, automatic_purchases) = optional_section(
+        input,
+        "automatic purchase",
+        options,
+        &mut skipped,
+        parse_automatic_purchase(language),
+    )?;
+    let automatic_purchases = automatic_purchases.unwrap_or_default();
+    partial.automatic_purchases = Some(automatic_purchases);
+    let (input, ()) = collect_wagers(input, language, partial)?;
+    let (input, _) = context("empty line", line_ending)(input)?;
+    let (input, vehicle_research) = optional_section(
+        input,
+        "researched vehicles",
+        options,
+        &mut skipped,
+        parse_researched_units(language),
+    )?;
+    partial.vehicle_research = Some(vehicle_research.unwrap_or_default());
+    let (input, modification_research) = optional_section(
+        input,
+        "researched modifications",
+        options,
+        &mut skipped,
+        parse_researched_modifications(language),
+    )?;
+    partial.modification_research = Some(modification_research.unwrap_or_default());
+    let (input, squadron_research) = optional_section(
+        input,
+        "squadron research",
+        options,
+        &mut skipped,
+        parse_squadron_research_line(language),
+    )?;
+    partial.squadron_research = squadron_research;
+    let (input, used_items) = optional_section(
+        input,
+        "used items",
+        options,
+        &mut skipped,
+        parse_used_items(language, options),
+    )?;
+    let (used_items, skipped_lines) = used_items.unwrap_or_default();
+    for line in skipped_lines {
+        warnings.push(
+            Warning::new(format!("skipped unknown used item {:?}", line.trim_end()))
+                .at(report, line),
+        );
+    }
+    partial.used_items = Some(used_items);
+    let (input, (session_id, session_time)) = match section(
+        input,
+        "session id",
+        options,
+        &mut skipped,
+        parse_session(language),
+    ) {
+        Err(nom::Err::Error(_)) if !options.strict => {
+            warnings.push(Warning::new(
+                "report has no session line; the session ID is unknown",
+            ));
+            (input, ("", None))
+        }
+        result => result?,
+    };
+    partial.session_id = Some(session_id);
+    partial.session_time = session_time;
+    let (input, (total, (balance, convertible_research))) = section(
+        input,
+        "total",
+        options,
+        &mut skipped,
+        consumed(parse_total(language)),
+    )?;
+    let computed = i64::from(earned_rewards.silverlions())
+        - i64::from(automatic_repair)
+        - i64::from(automatic_purchases);
+    if i64::from(balance.silverlions()) != computed {
+        issues.push(ValidationIssue::Balance {
+            stated: balance.silverlions(),
+            computed,
+        });
+    }
+    let earned_research = earned_rewards.research() + converted_research.unwrap_or_default();
+    if convertible_research != earned_research {
+        issues.push(ValidationIssue::ConvertibleResearch {
+            stated: convertible_research,
+            earned: earned_research,
+        });
+    }
+    partial.balance = Some(balance);
+    for (name, lines) in skipped {
+        warnings.push(
+            Warning::new(format!("skipped unrecognized lines before {name}")).at(report, lines),
+        );
+    }
+
+    // some clients paste the total twice
+    let (input, repeated_total) = opt(preceded(
+        line_ending,
+        verify(recognize(parse_total(language)), |repeated: &str| {
+            repeated == total
+        }),
+    ))(input)?;
+    if repeated_total.is_some() {
+        warnings.push(Warning::new("ignored repeated total line"));
+    }
+
+    Ok((input, ()))
+}
+
+/// check that nothing but whitespace follows the report
+///
+/// In lenient mode trailing lines are skipped with a warning instead.
+fn end_of_report<'a>(
+    report: &'a str,
+    input: &'a str,
+    options: ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> IResult<'a, ()> {
+    if input.trim().is_empty() {
+        return Ok((input, ()));
+    }
+    let trailing = input.trim_start_matches(['\r', '\n']);
+    if options.strict {
+        return error(trailing, "end of report");
+    }
+    warnings.push(Warning::new("skipped unrecognized trailing lines").at(report, trailing));
+    Ok((input, ()))
 }
 
 /// parse the first line in a battle report
-fn result_line(input: &str) -> IResult<(BattleResult, &str)> {
-    let (input, result) = battle_result(input)?;
-    let (input, _) = tag(" in the ")(input)?;
-    let (input, mission) = take_until(" mission!")(input)?;
-    let (input, _) = tag(" mission!")(input)?;
-    let (input, _) = line_ending(input)?;
-    let (input, _) = line_ending(input)?;
+fn result_line<'a>(
+    language: Language,
+) -> impl FnMut(&'a str) -> IResult<'a, (BattleResult, &'a str)> {
+    let words = language.vocabulary();
+    terminated(
+        separated_pair(
+            battle_result(language),
+            tag(words.mission_prefix),
+            terminated(take_until(words.mission_suffix), tag(words.mission_suffix)),
+        ),
+        line_ending,
+    )
+}
+
+/// parse the optional line naming the player and their squadron
+///
+/// # Examples
+/// ```text
+/// Player: Chronophylos
+/// ```
+/// ```text
+/// Player: [WTBR] Chronophylos
+/// ```
+fn player_line<'a>(
+    language: Language,
+) -> impl FnMut(&'a str) -> IResult<'a, (Option<&'a str>, &'a str)> {
+    delimited(
+        pair(tag(language.vocabulary().player), space1),
+        pair(
+            opt(terminated(
+                delimited(tag("["), take_until("]"), tag("]")),
+                space1,
+            )),
+            map(not_line_ending, str::trim_end),
+        ),
+        line_ending,
+    )
+}
+
+/// parse a section of the report
+///
+/// In lenient mode, lines the section does not start at are skipped until it
+/// matches. The skipped lines are added to `skipped` along with `name`.
+fn section<'a, O>(
+    input: &'a str,
+    name: &'static str,
+    options: ParseOptions,
+    skipped: &mut Vec<(&'static str, &'a str)>,
+    mut parser: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> IResult<'a, O> {
+    let result = context(name, &mut parser)(input);
+    match result {
+        Err(nom::Err::Error(err)) if !options.strict => {
+            let Ok((rest, lines)) = skip_until(&mut parser)(input) else {
+                return Err(nom::Err::Error(err));
+            };
+            skipped.push((name, lines));
+            context(name, parser)(rest)
+        }
+        result => result,
+    }
+}
+
+/// parse a section that may be missing, see [`section`]
+fn optional_section<'a, O>(
+    input: &'a str,
+    name: &'static str,
+    options: ParseOptions,
+    skipped: &mut Vec<(&'static str, &'a str)>,
+    parser: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> IResult<'a, Option<O>> {
+    match section(input, name, options, skipped, parser) {
+        Ok((input, output)) => Ok((input, Some(output))),
+        Err(nom::Err::Error(_)) => Ok((input, None)),
+        Err(err) => Err(err),
+    }
+}
+
+/// skip whole lines until `parser` matches, without consuming its input
+fn skip_until<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, &'a str> {
+    recognize(many_till(pair(not_line_ending, line_ending), peek(parser)))
+}
 
-    Ok((input, (result, mission)))
+/// skip everything up to the next line that looks like a table header
+fn skip_to_table(input: &str) -> IResult<'_, &str> {
+    skip_until(table_header)(input)
 }
 
-fn battle_result(input: &str) -> IResult<BattleResult> {
+fn battle_result<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, BattleResult> {
+    let words = language.vocabulary();
     alt((
-        map(tag("Victory"), |_| BattleResult::Win),
-        map(tag("Defeat"), |_| BattleResult::Loss),
-    ))(input)
+        value(BattleResult::Win, tag(words.victory)),
+        value(BattleResult::Loss, tag(words.defeat)),
+        value(BattleResult::Draw, tag(words.draw)),
+    ))
 }
 
-struct Table {
-    name: String,
-    rows: Vec<Row>,
+struct Table<'a> {
+    name: &'a str,
+    /// total reward stated in the header
+    total: Reward,
+    rows: Vec<Row<'a>>,
+    /// rows that were dropped because they repeated the previous row
+    duplicates: Vec<&'a str>,
 }
 
 #[derive(Debug)]
-struct Row {
+struct Row<'a> {
     time: u32,
-    vehicle: String,
-    enemy_vehicle: String,
+    vehicle: &'a str,
+    /// missing for targets without a name, like an air field
+    enemy_vehicle: Option<&'a str>,
+    /// marked with `×`
+    assisted: bool,
     reward: Reward,
 }
 
@@ -153,47 +1035,146 @@ struct Row {
 ///     13:43    Sherman Firefly    KV-85           930 SL     64 RP
 ///
 /// ```
-fn table(input: &str) -> IResult<Table> {
-    let (input, (name, count, _)) = context("table header", table_header)(input)?;
+///
+/// If there are more rows than the header announces, rows that exactly repeat
+/// the previous row are dropped and returned as duplicates. This undoes a
+/// clipboard artifact that pastes some rows twice.
+fn table(input: &str) -> IResult<'_, Table<'_>> {
+    let start = input;
+    let (input, (name, count, total)) = context("table header", table_header)(input)?;
+    let count = count as usize;
 
-    let (input, rows) = context(
-        "table rows",
-        many_m_n(count as usize, count as usize, table_row),
-    )(input)?;
-    let (input, _) = line_ending(input)?; // empty line
+    // a table without rows is followed by the empty line right away
+    let (input, lines) = match count {
+        0 => (input, Vec::new()),
+        _ => context("table rows", many0(consumed(table_row)))(input)?,
+    };
+    // empty line, missing if the table is all there is
+    let (input, _) = alt((line_ending, eof))(input)?;
+
+    let mut excess = lines.len().saturating_sub(count);
+    let mut rows = Vec::with_capacity(count);
+    let mut duplicates = Vec::new();
+    let mut previous = None;
+    for (line, row) in lines {
+        if excess > 0 && previous == Some(line) {
+            excess -= 1;
+            duplicates.push(line);
+            continue;
+        }
+        previous = Some(line);
+        rows.push(row);
+    }
+
+    if rows.len() != count {
+        return error(start, "row count does not match table header");
+    }
 
     Ok((
         input,
         Table {
-            name: name.to_string(),
+            name,
+            total,
             rows,
+            duplicates,
         },
     ))
 }
 
-fn table_header(input: &str) -> IResult<(String, u32, Reward)> {
-    //let (input, (name, _, reward)) = tuple((
-    //    context("table name", terminated(take_until(INDENT), row_separator)),
-    //    context("row count", terminated(digit1, row_separator)),
-    //    context("total reward", terminated(parse_reward, row_ending)),
-    //))(input)?;
+/// compare the total reward in a table header with the summed `rows`
+fn check_total<'r>(
+    table: &str,
+    stated: &Reward,
+    rows: impl IntoIterator<Item = &'r Reward>,
+) -> Option<ValidationIssue> {
+    let (silverlions, research) = rows.into_iter().fold((0, 0), |(sl, rp), reward| {
+        (sl + reward.silverlions(), rp + reward.research())
+    });
+    (stated.silverlions() != silverlions || stated.research() != research).then(|| {
+        ValidationIssue::TableTotal {
+            table: table.to_string(),
+            stated: Box::new(Reward::new(stated.silverlions(), stated.research())),
+            summed: Box::new(Reward::new(silverlions, research)),
+        }
+    })
+}
+
+/// fail with a message pointing at `input`
+fn error<'a, T>(input: &'a str, message: &'static str) -> IResult<'a, T> {
+    Err(nom::Err::Error(VerboseError {
+        errors: vec![(input, VerboseErrorKind::Context(message))],
+    }))
+}
 
-    let (input, name) =
-        context("table name", terminated(take_until(INDENT), row_separator))(input)?;
+fn table_header(input: &str) -> IResult<'_, (&str, u32, Reward)> {
+    let (input, name) = context("table name", terminated(column, row_separator))(input)?;
     let (input, count) = context("row count", terminated(u32, row_separator))(input)?;
     let (input, reward) = context("total reward", terminated(parse_reward, row_ending))(input)?;
 
-    Ok((input, (name.to_string(), count, reward)))
+    Ok((input, (name, count, reward)))
+}
+
+/// parse the whitespace between two columns
+///
+/// Columns are separated by at least two spaces or by a tab. Reports
+/// usually use four spaces, but some clients collapse or replace them.
+fn row_separator(input: &str) -> IResult<'_, ()> {
+    context(
+        "row separator",
+        value(
+            (),
+            verify(space1, |separator: &str| {
+                separator.len() >= 2 || separator.contains('\t')
+            }),
+        ),
+    )(input)
+}
+
+/// parse the indentation of a table row
+fn row_indent(input: &str) -> IResult<'_, ()> {
+    context("row indent", row_separator)(input)
 }
 
-fn row_separator(input: &str) -> IResult<()> {
-    context("row separator", value((), pair(tag(INDENT), many0(space1))))(input)
+/// parse a column value
+///
+/// A value may contain single spaces but ends at the next [`row_separator`]
+/// or at the end of the line.
+fn column(input: &str) -> IResult<'_, &str> {
+    context(
+        "column",
+        recognize(many1(alt((
+            is_not(" \t\r\n"),
+            terminated(tag(" "), not(one_of(" \t\r\n"))),
+        )))),
+    )(input)
 }
 
-fn row_ending(input: &str) -> IResult<()> {
+fn row_ending(input: &str) -> IResult<'_, ()> {
     context("row ending", value((), pair(many0(space1), line_ending)))(input)
 }
 
+/// parse a label in the summary section and any whitespace after it
+///
+/// Client layouts differ in how much space follows a label, so only the
+/// label text itself is matched exactly.
+fn label<'a>(name: &'static str) -> impl FnMut(&'a str) -> IResult<'a, &'a str> {
+    context(name, terminated(tag(name), space0))
+}
+
+/// parse the first of `tags` that matches
+fn any_tag<'a>(tags: &'static [&'static str]) -> impl FnMut(&'a str) -> IResult<'a, &'a str> {
+    move |input| {
+        tags.iter()
+            .find_map(|name| tag::<_, _, VerboseError<_>>(*name)(input).ok())
+            .map_or_else(|| error(input, "label"), Ok)
+    }
+}
+
+/// parse a comma separating list items and any whitespace after it
+fn list_separator(input: &str) -> IResult<'_, ()> {
+    value((), pair(tag(","), space0))(input)
+}
+
 /// parse a table row
 ///
 /// # Examples
@@ -205,483 +1186,2290 @@ fn row_ending(input: &str) -> IResult<()> {
 ///     13:14    Sherman Firefly    Chi-Nu II       930 SL     61 RP
 ///     13:43    Sherman Firefly    KV-85           930 SL     64 RP
 ///     3:45    Concept 3    M36 GMC()     ×    505 SL    10 + (PA)10 + (Booster)10 + (Talismans)10 = 40 RP
+///     14:03    Ju 87 D-5    620 SL    42 RP
 /// ```
-fn table_row(input: &str) -> IResult<Row> {
-    let (input, (time, vehicle, enemy_vehicle, _, reward)) = tuple((
+fn table_row(input: &str) -> IResult<'_, Row<'_>> {
+    map_parser(
+        terminated(not_line_ending, line_ending),
+        all_consuming(row_columns),
+    )(input)
+}
+
+/// parse the columns of a table row without its line ending
+///
+/// Rows are split into lines first, so no column parser looks past the end of
+/// its row.
+fn row_columns(input: &str) -> IResult<'_, Row<'_>> {
+    let (input, (time, vehicle, enemy_vehicle, assisted, reward)) = tuple((
         context(
             "time column",
-            preceded(tag(INDENT), terminated(timestamp, row_separator)),
+            preceded(row_indent, terminated(timestamp, row_separator)),
         ),
+        context("vehicle column", terminated(column, row_separator)),
         context(
-            "vehicle column",
-            terminated(take_until(INDENT), row_separator),
+            "enemy vehicle column",
+            opt(terminated(
+                verify(column, |column: &str| {
+                    column != "\u{d7}" && all_consuming(parse_reward)(column).is_err()
+                }),
+                pair(
+                    row_separator,
+                    peek(alt((tag("\u{d7}"), recognize(parse_reward)))),
+                ),
+            )),
         ),
         context(
-            "enemy vehicle column",
-            terminated(take_until(INDENT), row_separator),
+            "optional x",
+            map(opt(pair(tag("\u{d7}"), row_separator)), |x| x.is_some()),
         ),
-        context("optional x", opt(pair(tag("\u{d7}"), row_separator))),
-        context("reward column", terminated(parse_reward, row_ending)),
+        context("reward column", terminated(parse_reward, space0)),
     ))(input)?;
 
     Ok((
         input,
         Row {
             time,
-            vehicle: vehicle.to_string(),
-            enemy_vehicle: enemy_vehicle.to_string(),
+            vehicle,
+            enemy_vehicle,
+            assisted,
             reward,
         },
     ))
 }
 
-fn timestamp(input: &str) -> IResult<u32> {
-    map(separated_pair(u32, tag(":"), u32), |(hours, minutes)| {
-        hours * 60 + minutes
-    })(input)
-}
-
-/// parse a reward
+/// parse a timestamp into seconds
+///
+/// Minutes after hours and seconds are two digits below 60. Timestamps that
+/// do not fit into a `u32` of seconds fail to parse.
 ///
 /// # Examples
 /// ```text
-/// 5820 SL     413 RP
-/// ```
-/// ```text
-/// 1000 SL
+/// 7:13
 /// ```
 /// ```text
-/// 505 SL    10 + (PA)10 + (Booster)10 + (Talismans)10 = 40 RP
+/// 1:02:33
 /// ```
-fn parse_reward(input: &str) -> IResult<Reward> {
-    let (input, (silverlions, research)) = alt((
-        pair(
-            parse_silverlions,
-            map(opt(preceded(row_separator, parse_research_points)), |rp| {
-                rp.unwrap_or_default()
-            }),
-        ),
-        pair(success(0), parse_research_points),
-    ))(input)?;
+fn timestamp(input: &str) -> IResult<'_, u32> {
+    /// two digits below 60
+    fn sixtieths(input: &str) -> IResult<'_, u32> {
+        verify(
+            map_res(
+                take_while_m_n(2, 2, |c: char| c.is_ascii_digit()),
+                str::parse,
+            ),
+            |value: &u32| *value < 60,
+        )(input)
+    }
+
+    context(
+        "timestamp",
+        map_opt(
+            tuple((
+                u32,
+                preceded(tag(":"), sixtieths),
+                opt(preceded(tag(":"), sixtieths)),
+            )),
+            |parts| match parts {
+                (hours, minutes, Some(seconds)) => {
+                    hours.checked_mul(3600)?.checked_add(minutes * 60 + seconds)
+                }
+                (minutes, seconds, None) => minutes.checked_mul(60)?.checked_add(seconds),
+            },
+        ),
+    )(input)
+}
+
+/// parse a reward
+///
+/// # Examples
+/// ```text
+/// 5820 SL     413 RP
+/// ```
+/// ```text
+/// 1000 SL
+/// ```
+/// ```text
+/// 505 SL    10 + (PA)10 + (Booster)10 + (Talismans)10 = 40 RP
+/// ```
+/// ```text
+/// 1630 SL    98 RP    50 SRP
+/// ```
+/// ```text
+/// 1000 SL    15 WB    Holiday Toys: 3
+/// ```
+fn parse_reward(input: &str) -> IResult<'_, Reward> {
+    let (input, ((sl, rp, squadron_research), other_currencies)) = alt((
+        pair(
+            alt((
+                tuple((
+                    amount_in(Currency::Silverlions),
+                    map(opt(preceded(space1, amount_in(Currency::Research))), |rp| {
+                        rp.unwrap_or_default()
+                    }),
+                    opt(preceded(space1, parse_squadron_research)),
+                )),
+                tuple((
+                    success(RewardBreakdown::default()),
+                    amount_in(Currency::Research),
+                    opt(preceded(space1, parse_squadron_research)),
+                )),
+                tuple((
+                    success(RewardBreakdown::default()),
+                    success(RewardBreakdown::default()),
+                    map(parse_squadron_research, Some),
+                )),
+            )),
+            many0(preceded(space1, other_currency)),
+        ),
+        pair(
+            success(Default::default()),
+            separated_list1(space1, other_currency),
+        ),
+    ))(input)?;
 
     Ok((
         input,
         Reward {
-            silverlions,
-            research,
+            sl,
+            rp,
+            squadron_research: squadron_research.unwrap_or_default(),
+            other_currencies: other_currencies
+                .into_iter()
+                .map(|(currency, amount)| (currency.to_string(), amount))
+                .collect(),
         },
     ))
 }
 
-fn parse_silverlions(input: &str) -> IResult<u32> {
+/// parse an amount in a currency that [`Reward`] has no field for
+///
+/// Any unit other than the ones of the reward fields is accepted, so new
+/// event currencies do not break the report.
+///
+/// # Examples
+/// ```text
+/// 15 WB
+/// ```
+/// ```text
+/// Holiday Toys: 3
+/// ```
+fn other_currency(input: &str) -> IResult<'_, (&str, u32)> {
+    // golden eagles of awards have their own column
+    const REWARD_FIELDS: [Currency; 4] = [
+        Currency::Silverlions,
+        Currency::Research,
+        Currency::SquadronResearch,
+        Currency::GoldenEagles,
+    ];
+
     context(
-        "silverlions",
-        alt((parse_silverlions_simple, parse_silverlions_complex)),
+        "other currency",
+        alt((
+            map(
+                separated_pair(
+                    number,
+                    tag(" "),
+                    verify(alpha1, |unit: &str| {
+                        !REWARD_FIELDS.iter().any(|currency| currency.unit() == unit)
+                    }),
+                ),
+                |(amount, unit)| (unit, amount),
+            ),
+            separated_pair(
+                recognize(separated_list1(tag(" "), alpha1)),
+                tag(": "),
+                number,
+            ),
+        )),
     )(input)
 }
 
-fn parse_silverlions_simple(input: &str) -> IResult<u32> {
-    context("silverlions simple", terminated(u32, tag(" SL")))(input)
+/// parse an amount of squadron research points
+///
+/// # Examples
+/// ```text
+/// 50 SRP
+/// ```
+fn parse_squadron_research(input: &str) -> IResult<'_, u32> {
+    simple_amount(Currency::SquadronResearch)(input)
 }
 
-fn parse_silverlions_complex(input: &str) -> IResult<u32> {
-    let (input, (_, _, silverlions)) = tuple((
-        digit1,
-        context(
-            "additions",
-            many1(tuple((
-                tag(" + "),
-                delimited(tag("("), alpha1, tag(")")),
+/// parse a number whose digits may be grouped by spaces
+///
+/// # Examples
+/// ```text
+/// 12450
+/// ```
+/// ```text
+/// 12 450
+/// ```
+fn number(input: &str) -> IResult<'_, u32> {
+    context(
+        "number",
+        map_res(
+            recognize(pair(
                 digit1,
-            ))),
+                many0(pair(one_of(GROUP_SEPARATORS), digit_group)),
+            )),
+            |digits: &str| {
+                digits
+                    .chars()
+                    .filter(char::is_ascii_digit)
+                    .collect::<String>()
+                    .parse::<u32>()
+            },
         ),
-        preceded(tag(" = "), parse_silverlions_simple),
-    ))(input)?;
-    Ok((input, silverlions))
+    )(input)
 }
 
-fn parse_research_points(input: &str) -> IResult<u32> {
+/// exactly three digits that are not followed by another digit
+fn digit_group(input: &str) -> IResult<'_, &str> {
+    terminated(
+        take_while_m_n(3, 3, |c: char| c.is_ascii_digit()),
+        not(satisfy(|c| c.is_ascii_digit())),
+    )(input)
+}
+
+/// parse a percentage in hundredths of a percent (basis points)
+///
+/// Some locales put a space before the percent sign or write decimals with
+/// a comma. Decimals past the second are rounded.
+///
+/// # Examples
+/// ```text
+/// 85%
+/// ```
+/// ```text
+/// 97,5 %
+/// ```
+fn percent(input: &str) -> IResult<'_, u16> {
     context(
-        "research points",
-        alt((parse_research_points_simple, parse_research_points_complex)),
+        "percent",
+        map(
+            terminated(
+                pair(u8, opt(preceded(one_of(",."), digit1))),
+                pair(opt(one_of(GROUP_SEPARATORS)), tag("%")),
+            ),
+            |(whole, fraction): (u8, Option<&str>)| {
+                u16::from(whole) * 100 + fraction.map_or(0, hundredths)
+            },
+        ),
     )(input)
 }
 
-fn parse_research_points_simple(input: &str) -> IResult<u32> {
-    context("research points simple", terminated(u32, tag(" RP")))(input)
+/// the decimals of a percentage in hundredths, from 0 to 100
+fn hundredths(fraction: &str) -> u16 {
+    let mut digits = fraction
+        .chars()
+        .map(|digit| digit.to_digit(10).map_or(0, |digit| digit as u16));
+    let tenths = digits.next().unwrap_or(0);
+    let hundredths = digits.next().unwrap_or(0);
+    let round_up = digits.next().is_some_and(|digit| digit >= 5);
+    tenths * 10 + hundredths + u16::from(round_up)
 }
 
-fn parse_research_points_complex(input: &str) -> IResult<u32> {
-    let (input, (_, _, research_points)) = tuple((
-        digit1,
-        context(
-            "additions",
-            many1(tuple((
-                tag(" + "),
-                delimited(tag("("), alpha1, tag(")")),
-                digit1,
-            ))),
-        ),
-        preceded(tag(" = "), parse_research_points_simple),
-    ))(input)?;
-    Ok((input, research_points))
+/// round a percentage in basis points to whole percent
+pub(crate) fn whole_percent(basis_points: u16) -> u8 {
+    u8::try_from(basis_points.saturating_add(50) / 100).unwrap_or(u8::MAX)
 }
 
-fn parse_crp(input: &str) -> IResult<u32> {
-    terminated(u32, tag(" CRP"))(input)
+/// Currencies an amount in a report can be given in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Currency {
+    Silverlions,
+    Research,
+    ConvertibleResearch,
+    SquadronResearch,
+    GoldenEagles,
+    WarBonds,
 }
 
-fn parse_events(input: &str) -> IResult<Vec<Event>> {
-    let (input, tables) = context("event tables", many0(table))(input)?;
+impl Currency {
+    const ALL: [Self; 6] = [
+        Self::Silverlions,
+        Self::Research,
+        Self::ConvertibleResearch,
+        Self::SquadronResearch,
+        Self::GoldenEagles,
+        Self::WarBonds,
+    ];
+
+    /// abbreviation following an amount in this currency
+    fn unit(self) -> &'static str {
+        match self {
+            Self::Silverlions => "SL",
+            Self::Research => "RP",
+            Self::ConvertibleResearch => "CRP",
+            Self::SquadronResearch => "SRP",
+            Self::GoldenEagles => "GE",
+            Self::WarBonds => "WB",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Silverlions => "silverlions",
+            Self::Research => "research points",
+            Self::ConvertibleResearch => "convertible research points",
+            Self::SquadronResearch => "squadron research points",
+            Self::GoldenEagles => "golden eagles",
+            Self::WarBonds => "war bonds",
+        }
+    }
+}
 
-    let events = tables
+/// parse a currency abbreviation
+fn currency(input: &str) -> IResult<'_, Currency> {
+    let (rest, unit) = context("currency", alpha1)(input)?;
+    match Currency::ALL
         .into_iter()
-        .map(|table| {
-            table
-                .rows
-                .into_iter()
-                .map(move |row| {
-                    let time = row.time;
-                    let vehicle = row.vehicle.to_string();
-                    let enemy = Some(row.enemy_vehicle.to_string());
-                    let reward = row.reward;
-                    let kind = table.name.to_string();
-
-                    Event {
-                        time,
-                        kind,
-                        vehicle,
-                        enemy,
-                        reward,
-                    }
-                })
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .collect::<Vec<_>>();
+        .find(|currency| currency.unit() == unit)
+    {
+        Some(currency) => Ok((rest, currency)),
+        None => error(input, "unknown currency"),
+    }
+}
+
+/// parse an amount in any currency, with or without bonuses
+///
+/// # Examples
+/// ```text
+/// 1 010 SL
+/// ```
+/// ```text
+/// 10 + (PA)10 + (Booster)10 + (Talismans)10 = 40 RP
+/// ```
+fn amount(input: &str) -> IResult<'_, (Currency, RewardBreakdown)> {
+    context(
+        "amount",
+        alt((
+            map(
+                separated_pair(number, tag(" "), currency),
+                |(total, currency)| (currency, RewardBreakdown::from(total)),
+            ),
+            map(
+                tuple((
+                    number,
+                    context(
+                        "additions",
+                        many1(preceded(
+                            tag(" + "),
+                            pair(
+                                map(delimited(tag("("), alpha1, tag(")")), BonusKind::from_name),
+                                number,
+                            ),
+                        )),
+                    ),
+                    preceded(tag(" = "), separated_pair(number, tag(" "), currency)),
+                )),
+                |(base, bonuses, (total, currency))| {
+                    (
+                        currency,
+                        RewardBreakdown {
+                            base,
+                            bonuses,
+                            total,
+                        },
+                    )
+                },
+            ),
+        )),
+    )(input)
+}
+
+/// parse an amount in `currency`, with or without bonuses
+fn amount_in<'a>(currency: Currency) -> impl FnMut(&'a str) -> IResult<'a, RewardBreakdown> {
+    context(
+        currency.name(),
+        map(
+            verify(amount, move |(parsed, _)| *parsed == currency),
+            |(_, breakdown)| breakdown,
+        ),
+    )
+}
+
+/// parse an amount in `currency` without bonuses
+fn simple_amount<'a>(currency: Currency) -> impl FnMut(&'a str) -> IResult<'a, u32> {
+    map(
+        verify(amount_in(currency), |breakdown| {
+            breakdown.bonuses.is_empty()
+        }),
+        |breakdown| breakdown.total,
+    )
+}
+
+/// everything [`parse_events`] gathers from the event tables
+struct EventTables<'a> {
+    events: Vec<EventRef<'a>>,
+    tables: Vec<EventTable>,
+    /// rows that were dropped because they repeated the previous row
+    duplicates: Vec<&'a str>,
+    issues: Vec<ValidationIssue>,
+}
+
+/// parse all event tables, returning the events and any duplicated rows
+fn parse_events<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, EventTables<'a>> {
+    move |input| {
+        let (input, tables) = context(
+            "event tables",
+            many0(preceded(not(summary_table_name(language)), table)),
+        )(input)?;
 
-    Ok((input, events))
+        let duplicates = tables
+            .iter()
+            .flat_map(|table| table.duplicates.iter().copied())
+            .collect();
+
+        let issues = tables
+            .iter()
+            .filter_map(|table| {
+                check_total(
+                    table.name,
+                    &table.total,
+                    table.rows.iter().map(|row| &row.reward),
+                )
+            })
+            .collect();
+
+        let event_tables = tables
+            .iter()
+            .map(|table| EventTable {
+                name: language.event_table_name(table.name).to_string(),
+                kind: language.event_kind(table.name),
+                count: table.rows.len(),
+                total: table.total.clone(),
+            })
+            .collect();
+
+        let events = tables
+            .into_iter()
+            .flat_map(|table| {
+                table
+                    .rows
+                    .into_iter()
+                    .map(move |row| {
+                        let time = row.time;
+                        let vehicle = row.vehicle;
+                        let assisted = row.assisted;
+                        let reward = row.reward;
+                        let kind = language.event_kind(table.name);
+                        // rows helping allies name the ally in the enemy column
+                        let (enemy, ally) = match kind {
+                            EventKind::AllyAssistance => (None, row.enemy_vehicle),
+                            _ => (row.enemy_vehicle, None),
+                        };
+
+                        EventRef {
+                            time,
+                            kind,
+                            vehicle,
+                            enemy,
+                            ally,
+                            assisted,
+                            reward,
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        Ok((
+            input,
+            EventTables {
+                events,
+                tables: event_tables,
+                duplicates,
+                issues,
+            },
+        ))
+    }
+}
+
+/// names of the tables following the events, whose rows would otherwise pass
+/// for events without an enemy
+fn summary_table_name<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, &'a str> {
+    let words = language.vocabulary();
+    alt((tag(words.awards), tag(words.activity_time)))
+}
+
+/// The awards and the header of their table
+struct AwardTable<'a> {
+    awards: Vec<AwardRef<'a>>,
+    /// row count stated in the header
+    count: usize,
+    total: Reward,
+    issue: Option<ValidationIssue>,
 }
 
-fn award_table(input: &str) -> IResult<Vec<Award>> {
-    let (input, rows) = context("award header", preceded(table_header, many1(short_row)))(input)?;
+fn award_table(input: &str) -> IResult<'_, AwardTable<'_>> {
+    let (input, ((name, count, total), rows)) =
+        context("award header", pair(table_header, many1(short_row)))(input)?;
     let (input, _) = line_ending(input)?; // empty line
 
+    let issue = check_total(name, &total, rows.iter().map(|(_, _, reward, _)| reward));
     let awards = rows
         .into_iter()
-        .map(|(time, name, reward)| Award {
+        .map(|(time, name, reward, golden_eagles)| AwardRef {
             time,
-            name: name.to_string(),
+            name,
             reward,
+            golden_eagles,
         })
         .collect();
 
-    Ok((input, awards))
+    Ok((
+        input,
+        AwardTable {
+            awards,
+            count: count as usize,
+            total,
+            issue,
+        },
+    ))
 }
 
-fn short_row(input: &str) -> IResult<(u32, &str, Reward)> {
+/// parse a row of a time, a name and a reward
+///
+/// Research points after the silver lions belong to the reward, and the
+/// padding after them may hold one more cell with golden eagles. The golden
+/// eagles are returned on their own, zero if there are none.
+///
+/// # Examples
+/// ```text
+///     13:55    The Best Squad           1000 SL    100 RP
+/// ```
+/// ```text
+///     13:55    The Best Squad           1000 SL    100 RP    5 GE
+/// ```
+fn short_row(input: &str) -> IResult<'_, (u32, &str, Reward, u32)> {
     tuple((
-        preceded(tag(INDENT), terminated(timestamp, row_separator)),
-        terminated(take_until(INDENT), row_separator),
-        terminated(parse_reward, row_ending),
+        preceded(row_indent, terminated(timestamp, row_separator)),
+        terminated(column, row_separator),
+        parse_reward,
+        terminated(
+            map(
+                opt(preceded(row_separator, amount_in(Currency::GoldenEagles))),
+                |golden_eagles| golden_eagles.map_or(0, |golden_eagles| golden_eagles.total),
+            ),
+            row_ending,
+        ),
     ))(input)
 }
 
-fn vehicle_tables(input: &str) -> IResult<Vec<Vehicle>> {
-    // activity time
-    let (input, activity_rows) = preceded(table_header, many1(short_row))(input)?;
-    let (input, _) = line_ending(input)?; // empty line
+/// parse the activity time and time played tables into vehicles
+///
+/// Every row needs a row for the same vehicle in the other table. In lenient
+/// mode, an activity time row without a time played row is kept with zero
+/// activity and time played, and returned with the name of its vehicle to
+/// warn about it.
+#[allow(clippy::type_complexity)]
+fn vehicle_tables<'a>(
+    options: ParseOptions,
+) -> impl FnMut(
+    &'a str,
+) -> IResult<
+    'a,
+    (
+        Vec<VehicleRef<'a>>,
+        Vec<ValidationIssue>,
+        Vec<(&'a str, &'a str)>,
+    ),
+> {
+    let language = options.language;
+    move |input| {
+        // activity time
+        let (input, ((name, _, activity_total), activity_rows)) =
+            pair(table_header, many1(consumed(short_row)))(input)?;
+        let (input, _) = line_ending(input)?; // empty line
 
-    // time played
-    let (input, _) = tuple((
-        context("Time Played literal", tag("Time Played")),
-        pair(many1(space1), digit1),
-        row_separator,
-        parse_research_points,
-        row_ending,
-    ))(input)?;
+        // time played
+        let time_played_table = input;
+        let (input, (time_played, _, _, time_played_total, _)) = tuple((
+            context(
+                "Time Played literal",
+                tag(language.vocabulary().time_played),
+            ),
+            pair(many1(space1), digit1),
+            row_separator,
+            amount_in(Currency::Research),
+            row_ending,
+        ))(input)?;
 
-    let (input, time_played_rows) = many1(tuple((
-        preceded(tag(INDENT), terminated(take_until(INDENT), row_separator)), // name
-        terminated(terminated(u8, tag("%")), row_separator),                  // activity
-        terminated(timestamp, row_separator),                                 // time played
-        terminated(parse_research_points, row_ending),                        // reward
-    )))(input)?;
+        let (input, time_played_rows) = many1(tuple((
+            preceded(row_indent, terminated(column, row_separator)), // name
+            terminated(map(percent, whole_percent), row_separator),  // activity
+            terminated(timestamp, row_separator),                    // time played
+            terminated(amount_in(Currency::Research), row_ending),   // reward
+        )))(input)?;
 
-    let (input, _) = line_ending(input)?; // empty line
+        let (input, _) = line_ending(input)?; // empty line
 
-    let vehicles = activity_rows
+        let time_played_rewards = time_played_rows
+            .iter()
+            .map(|(.., research)| Reward::new(0, research.total))
+            .collect::<Vec<_>>();
+        let issues = [
+            check_total(
+                name,
+                &activity_total,
+                activity_rows.iter().map(|(_, (_, _, reward, _))| reward),
+            ),
+            check_total(
+                time_played,
+                &Reward::new(0, time_played_total.total),
+                &time_played_rewards,
+            ),
+        ]
         .into_iter()
-        .zip(time_played_rows.into_iter())
-        .map(
-            |((_, name, reward), (_, activity, time_played, additional_rp))| Vehicle {
-                name: name.to_string(),
+        .flatten()
+        .collect();
+
+        // the tables may list the vehicles in different orders, so the rows
+        // are joined by name and every row needs one in the other table
+        let mut time_played_rows = time_played_rows;
+        let mut vehicles = Vec::with_capacity(activity_rows.len());
+        let mut unplayed = Vec::new();
+        for (row, (_, name, mut reward, _)) in activity_rows {
+            let (activity, time_played) = match time_played_rows
+                .iter()
+                .position(|(played, ..)| *played == name)
+            {
+                Some(index) => {
+                    let (_, activity, time_played, additional_rp) = time_played_rows.remove(index);
+                    reward.rp += additional_rp;
+                    (activity, time_played)
+                }
+                None if !options.strict => {
+                    unplayed.push((name, row));
+                    (0, 0)
+                }
+                None => return error(row, "activity time row without a time played row"),
+            };
+            vehicles.push(VehicleRef {
+                name,
                 activity,
                 time_played,
-                reward: Reward {
-                    silverlions: reward.silverlions,
-                    research: reward.research + additional_rp,
-                },
-            },
-        )
-        .collect();
+                reward,
+            });
+        }
+        if !time_played_rows.is_empty() {
+            return error(
+                time_played_table,
+                "time played row without an activity time row",
+            );
+        }
 
-    Ok((input, vehicles))
+        Ok((input, (vehicles, issues, unplayed)))
+    }
 }
 
-fn parse_other_awards(input: &str) -> IResult<Reward> {
+fn parse_other_awards<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, Reward> {
     delimited(
-        pair(tag("Other awards"), row_separator),
+        pair(tag(language.vocabulary().other_awards), space1),
         parse_reward,
         pair(row_ending, line_ending),
-    )(input)
+    )
 }
 
-fn parse_reward_for_winning(input: &str) -> IResult<Reward> {
+fn parse_reward_for_winning<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, Reward> {
     delimited(
-        pair(tag("Reward for winning"), row_separator),
+        pair(any_tag(language.vocabulary().reward_for_winning), space1),
         parse_reward,
         pair(row_ending, line_ending),
-    )(input)
+    )
+}
+
+/// parse the bonus for the first victory of the day
+///
+/// # Examples
+/// ```text
+/// First victory of the day    2000 SL    300 RP
+/// ```
+fn parse_first_victory_bonus<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, Reward> {
+    delimited(
+        pair(any_tag(language.vocabulary().first_victory), space1),
+        parse_reward,
+        pair(row_ending, opt(line_ending)),
+    )
+}
+
+/// parse the lines of completed battle tasks and challenges
+fn parse_completed_tasks<'a>(
+    language: Language,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<CompletedTaskRef<'a>>> {
+    terminated(many1(completed_task(language)), opt(line_ending))
+}
+
+/// parse a completed battle task or challenge
+///
+/// The name ends at the last space before the rewards, so it may contain
+/// quotes and punctuation.
+///
+/// # Examples
+/// ```text
+/// Battle task completed: "Destroy 5 ground vehicles" 30000 SL
+/// ```
+/// ```text
+/// Challenge completed: "Sharpshooter, "Pro" edition!" 1000 SL, 5 GE
+/// ```
+fn completed_task<'a>(
+    language: Language,
+) -> impl FnMut(&'a str) -> IResult<'a, CompletedTaskRef<'a>> {
+    map_parser(
+        terminated(not_line_ending, line_ending),
+        all_consuming(preceded(
+            pair(any_tag(language.vocabulary().completed_task), space0),
+            map(
+                pair(
+                    recognize(many_till(anychar, peek(task_rewards))),
+                    task_rewards,
+                ),
+                |(name, amounts)| {
+                    let name = name
+                        .strip_prefix('"')
+                        .and_then(|name| name.strip_suffix('"'))
+                        .unwrap_or(name);
+                    let mut task = CompletedTaskRef {
+                        name,
+                        reward: Reward::default(),
+                        convertible_research: 0,
+                        golden_eagles: 0,
+                    };
+                    for (currency, breakdown) in amounts {
+                        match currency {
+                            Currency::Silverlions => task.reward.sl = breakdown,
+                            Currency::Research => task.reward.rp = breakdown,
+                            Currency::ConvertibleResearch => {
+                                task.convertible_research = breakdown.total
+                            }
+                            Currency::SquadronResearch => {
+                                task.reward.squadron_research = breakdown.total
+                            }
+                            Currency::GoldenEagles => task.golden_eagles = breakdown.total,
+                            Currency::WarBonds => task
+                                .reward
+                                .other_currencies
+                                .push((currency.unit().to_string(), breakdown.total)),
+                        }
+                    }
+                    task
+                },
+            ),
+        )),
+    )
 }
 
-// FIXME: too greedy :(
-fn vehicle_name(input: &str) -> IResult<String> {
+/// parse any wager lines, which may appear anywhere in the reward summary
+fn collect_wagers<'a>(
+    input: &'a str,
+    language: Language,
+    parts: &mut ReportParts<'a>,
+) -> IResult<'a, ()> {
+    let (input, wagers) = many0(wager(language))(input)?;
+    parts.wagers.get_or_insert_with(Vec::new).extend(wagers);
+    Ok((input, ()))
+}
+
+/// parse a completed wager stage or the final payout of a wager
+///
+/// Wager lines are only known from English reports, so only the leading
+/// label is looked up in the vocabulary.
+///
+/// # Examples
+/// ```text
+/// Wager "Kill streak" stage 3/5 completed: 15000 SL
+/// ```
+/// ```text
+/// Wager "Kill streak" completed: 50000 SL    120 RP
+/// ```
+fn wager<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, WagerProgressRef<'a>> {
+    let end_of_name = || pair(tag("\""), alt((tag(" stage "), tag(" completed:"))));
     map(
-        take_while(|c: char| match c {
-            'a'..='z' | 'A'..='Z' | '0'..='9' | ' ' => true,
-            '#' | '&' | '\'' | '(' | ')' | ',' | '-' | '.' | '/' | '_' => true,
-            _ => false,
-        }),
-        String::from,
+        tuple((
+            delimited(
+                pair(any_tag(language.vocabulary().wager), space1),
+                preceded(
+                    tag("\""),
+                    recognize(many_till(anychar, peek(end_of_name()))),
+                ),
+                tag("\""),
+            ),
+            opt(preceded(
+                tag(" stage "),
+                separated_pair(number, tag("/"), number),
+            )),
+            preceded(pair(tag(" completed:"), space1), parse_reward),
+            row_ending,
+        )),
+        |(name, stage, reward, ())| WagerProgressRef {
+            name,
+            stage: stage.map(|(stage, _)| stage),
+            stages_total: stage.map(|(_, total)| total),
+            reward,
+        },
+    )
+}
+
+/// parse the rewards ending a completed task line, like ` 1000 SL, 5 GE`
+fn task_rewards(input: &str) -> IResult<'_, Vec<(Currency, RewardBreakdown)>> {
+    delimited(
+        space1,
+        separated_list1(list_separator, amount),
+        pair(space0, eof),
     )(input)
 }
 
-fn parse_earned(input: &str) -> IResult<Reward> {
+/// parse a vehicle name
+///
+/// A name ends before a run of spaces that separates columns and before a
+/// comma followed by a space that separates list items. Single spaces and
+/// commas within the name are kept, a trailing space is not.
+///
+/// # Examples
+/// ```text
+/// T-34 (1940)
+/// ```
+/// ```text
+/// Pz.Kpfw. IV Ausf. F2
+/// ```
+fn vehicle_name(input: &str) -> IResult<'_, &str> {
+    fn name_char(c: char) -> bool {
+        matches!(
+            c,
+            'a'..='z'
+                | 'A'..='Z'
+                | '0'..='9'
+                | '#'
+                | '&'
+                | '\''
+                | '('
+                | ')'
+                | '-'
+                | '.'
+                | '/'
+                | '_'
+        )
+    }
+
+    recognize(many0(alt((
+        take_while1(name_char),
+        terminated(alt((tag(" "), tag(","))), peek(satisfy(name_char))),
+    ))))(input)
+}
+
+fn parse_earned<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, Reward> {
     map(
         delimited(
-            tag("Earned: "),
-            separated_pair(parse_silverlions_simple, tag(", "), parse_crp),
-            line_ending,
+            label(language.vocabulary().earned),
+            separated_pair(
+                simple_amount(Currency::Silverlions),
+                list_separator,
+                simple_amount(Currency::ConvertibleResearch),
+            ),
+            row_ending,
         ),
-        |(silverlions, research)| Reward {
-            silverlions,
-            research,
-        },
-    )(input)
+        |(silverlions, research)| Reward::new(silverlions, research),
+    )
 }
 
-fn parse_activity(input: &str) -> IResult<u8> {
-    map(
-        delimited(tag("Activity: "), terminated(u8, tag("%")), line_ending),
-        |activity| activity,
-    )(input)
+/// parse the research converted automatically for premium vehicles
+///
+/// # Examples
+/// ```text
+/// Converted RP: 1 234 CRP
+/// ```
+fn parse_converted_research<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, u32> {
+    delimited(
+        pair(any_tag(language.vocabulary().converted_research), space0),
+        simple_amount(Currency::ConvertibleResearch),
+        row_ending,
+    )
+}
+
+fn parse_activity<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, u16> {
+    delimited(label(language.vocabulary().activity), percent, row_ending)
 }
 
-fn parse_damaged_vehicles(input: &str) -> IResult<Vec<String>> {
+fn parse_damaged_vehicles<'a>(
+    language: Language,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<&'a str>> {
     delimited(
-        tag("Damaged Vehicles: "),
-        separated_list1(tag(", "), map(vehicle_name, String::from)),
-        line_ending,
-    )(input)
+        label(language.vocabulary().damaged_vehicles),
+        separated_list1(preceded(space0, list_separator), vehicle_name),
+        row_ending,
+    )
 }
 
-fn parse_automatic_repair(input: &str) -> IResult<u32> {
+fn parse_automatic_repair<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, u32> {
     delimited(
-        tag("Automatic repair of all vehicles: -"),
-        parse_silverlions_simple,
-        line_ending,
-    )(input)
+        pair(label(language.vocabulary().automatic_repair), tag("-")),
+        simple_amount(Currency::Silverlions),
+        row_ending,
+    )
 }
 
-fn parse_automatic_purchase(input: &str) -> IResult<u32> {
+fn parse_automatic_purchase<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, u32> {
     delimited(
-        tag("Automatic purchasing of ammo and \"Crew Replenishment\": -"),
-        parse_silverlions_simple,
-        line_ending,
-    )(input)
+        pair(label(language.vocabulary().automatic_purchase), tag("-")),
+        simple_amount(Currency::Silverlions),
+        row_ending,
+    )
 }
 
-fn parse_researched_units(input: &str) -> IResult<Vec<VehicleResearch>> {
+fn parse_researched_units<'a>(
+    language: Language,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<VehicleResearchRef<'a>>> {
     delimited(
-        pair(tag("Researched unit: "), line_ending),
+        pair(label(language.vocabulary().researched_unit), line_ending),
         context("researched vehicles", many1(parse_vehicle_research)),
         line_ending,
-    )(input)
+    )
 }
 
-fn parse_vehicle_research(input: &str) -> IResult<VehicleResearch> {
+/// parse a vehicle research line
+///
+/// # Examples
+/// ```text
+/// T-34 (1941): 1191 RP
+/// ```
+/// ```text
+/// T-34-85: 1 191 RP (34%)
+/// ```
+fn parse_vehicle_research(input: &str) -> IResult<'_, VehicleResearchRef<'_>> {
     map(
         terminated(
-            separated_pair(vehicle_name, tag(": "), parse_research_points_simple),
-            line_ending,
+            tuple((
+                vehicle_name,
+                tag(": "),
+                simple_amount(Currency::Research),
+                opt(preceded(space1, delimited(tag("("), u8, tag("%)")))),
+            )),
+            pair(space0, line_ending),
         ),
-        |(name, research)| VehicleResearch { name, research },
+        |(name, _, research, progress_percent)| VehicleResearchRef {
+            name,
+            research,
+            progress_percent,
+        },
     )(input)
 }
 
-fn parse_researched_modifications(input: &str) -> IResult<Vec<ModificationResearch>> {
+fn parse_researched_modifications<'a>(
+    language: Language,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<ModificationResearchRef<'a>>> {
     delimited(
-        pair(tag("Researching progress: "), line_ending),
+        pair(
+            label(language.vocabulary().researching_progress),
+            line_ending,
+        ),
         many1(parse_modification_research),
         line_ending,
-    )(input)
+    )
 }
 
-fn parse_modification_research(input: &str) -> IResult<ModificationResearch> {
-    dbg!(input);
-    map(
-        terminated(
-            tuple((
-                map_parser(take_until(" - "), vehicle_name),
-                tag(" - "),
-                context(
-                    "name",
-                    take_while(|c: char| c.is_ascii_alphanumeric() || c == ' '),
-                ),
-                tag(": "),
-                parse_research_points_simple,
-            )),
-            line_ending,
-        ),
-        |(vehicle, _, name, _, research)| ModificationResearch {
-            vehicle,
-            name: name.to_string(),
-            research,
-        },
-    )(input)
-}
+/// parse a modification research line
+///
+/// # Examples
+/// ```text
+/// YaG-10 (29-K) - Improved Parts: 220 RP
+/// ```
+/// ```text
+/// YaG-10 (29-K) - Improved Parts: 220 RP, 35 CRP
+/// ```
+fn parse_modification_research(input: &str) -> IResult<'_, ModificationResearchRef<'_>> {
+    map(
+        terminated(
+            tuple((
+                map_parser(take_until(" - "), vehicle_name),
+                tag(" - "),
+                context(
+                    "name",
+                    take_while(|c: char| c.is_ascii_alphanumeric() || c == ' '),
+                ),
+                tag(": "),
+                simple_amount(Currency::Research),
+                opt(preceded(
+                    tag(", "),
+                    simple_amount(Currency::ConvertibleResearch),
+                )),
+            )),
+            line_ending,
+        ),
+        |(vehicle, _, name, _, research, convertible_research)| ModificationResearchRef {
+            vehicle,
+            name,
+            research,
+            convertible_research: convertible_research.unwrap_or_default(),
+        },
+    )(input)
+}
+
+/// parse the points added to the research of a squadron vehicle
+///
+/// # Example
+/// ```text
+/// Squadron research: 680 points
+/// ```
+fn parse_squadron_research_line<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, u32> {
+    delimited(
+        pair(any_tag(language.vocabulary().squadron_research), space0),
+        terminated(
+            number,
+            pair(
+                space1,
+                any_tag(language.vocabulary().squadron_research_points),
+            ),
+        ),
+        pair(row_ending, line_ending),
+    )
+}
+
+/// parse the crew experience section
+///
+/// The first vehicle may follow the label on the same line.
+///
+/// # Examples
+/// ```text
+/// Crew experience: Concept 3: 320
+/// ```
+/// ```text
+/// Crew experience:
+/// Concept 3: 320
+/// Sherman Firefly: 150
+/// ```
+fn parse_crew_experience<'a>(
+    language: Language,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<CrewExperienceRef<'a>>> {
+    delimited(
+        tuple((
+            any_tag(language.vocabulary().crew_experience),
+            space0,
+            opt(line_ending),
+        )),
+        many1(crew_experience),
+        line_ending,
+    )
+}
+
+/// parse the experience of one crew
+///
+/// # Example
+/// ```text
+/// Concept 3: 320
+/// ```
+fn crew_experience(input: &str) -> IResult<'_, CrewExperienceRef<'_>> {
+    map(
+        terminated(separated_pair(vehicle_name, tag(": "), number), row_ending),
+        |(vehicle, experience)| CrewExperienceRef {
+            vehicle,
+            experience,
+        },
+    )(input)
+}
+
+/// parse the used items section, and the lines it skipped
+///
+/// The active boosters and their descriptions are skipped. Other lines that
+/// do not name an item are skipped in lenient mode and fail the section in
+/// strict mode.
+///
+/// # Example
+/// ```text
+/// Used items:
+/// Fire extinguisher: 2
+/// Repair Kit x2, Fire Extinguisher x1
+/// Active boosters RP:
+/// Common: +10%RP
+/// ```
+fn parse_used_items<'a>(
+    language: Language,
+    options: ParseOptions,
+) -> impl FnMut(&'a str) -> IResult<'a, (Vec<UsedItemRef<'a>>, Vec<&'a str>)> {
+    let words = language.vocabulary();
+    let unknown_line = move |input: &'a str| {
+        if options.strict {
+            cut(|input| error(input, "used item"))(input)
+        } else {
+            map(recognize(pair(not_line_ending, line_ending)), Err)(input)
+        }
+    };
+    map(
+        preceded(
+            pair(label(words.used_items), line_ending),
+            // never the session or total line, so a missing session line is
+            // reported there
+            many0(preceded(
+                not(alt((tag(words.session), tag(words.total), eof))),
+                alt((
+                    map(used_item, |item| Ok(vec![item])),
+                    map(
+                        terminated(
+                            separated_list1(tag(", "), counted_item),
+                            pair(space0, line_ending),
+                        ),
+                        Ok,
+                    ),
+                    value(Ok(Vec::new()), active_boosters(language)),
+                    value(Ok(Vec::new()), pair(space0, line_ending)),
+                    unknown_line,
+                )),
+            )),
+        ),
+        |lines| {
+            let mut items = Vec::new();
+            let mut skipped = Vec::new();
+            for line in lines {
+                match line {
+                    Ok(line) => items.extend(line),
+                    Err(line) => skipped.push(line),
+                }
+            }
+            (items, skipped)
+        },
+    )
+}
+
+/// parse the active boosters in the used items and the lines describing them
+///
+/// # Example
+/// ```text
+/// Active boosters RP:
+/// Common: +10%RP
+/// * Personal booster
+/// +10%RP, gives (+10%RP).
+/// ```
+fn active_boosters<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, &'a str> {
+    let words = language.vocabulary();
+    recognize(pair(
+        tuple((any_tag(words.active_boosters), not_line_ending, line_ending)),
+        many0(tuple((
+            not(alt((tag(words.session), tag(words.total)))),
+            not(pair(space0, line_ending)),
+            not_line_ending,
+            line_ending,
+        ))),
+    ))
+}
+
+/// parse a used item on its own line
+///
+/// # Examples
+/// ```text
+/// Fire extinguisher: 2
+/// ```
+fn used_item(input: &str) -> IResult<'_, UsedItemRef<'_>> {
+    map(
+        terminated(
+            separated_pair(is_not(":\r\n"), tag(": "), u32),
+            pair(space0, line_ending),
+        ),
+        |(name, count): (&str, u32)| UsedItemRef { name, count },
+    )(input)
+}
+
+/// parse a used item followed by its count
+///
+/// # Examples
+/// ```text
+/// Repair Kit x2
+/// ```
+fn counted_item(input: &str) -> IResult<'_, UsedItemRef<'_>> {
+    map(
+        pair(
+            recognize(many_till(
+                satisfy(|c| !matches!(c, ',' | '\r' | '\n')),
+                peek(item_count),
+            )),
+            item_count,
+        ),
+        |(name, count): (&str, u32)| UsedItemRef { name, count },
+    )(input)
+}
+
+/// parse the count after a used item, like ` x2`
+fn item_count(input: &str) -> IResult<'_, u32> {
+    preceded(
+        tag(" x"),
+        terminated(u32, not(satisfy(|c| c.is_alphanumeric()))),
+    )(input)
+}
+
+/// parse the session line into the session id and the time, if stated
+///
+/// # Examples
+/// ```text
+/// Session: 1712a4f0003b2c5
+/// ```
+/// ```text
+/// Session: 1712a4f0003b2c5 2023-08-03 19:42:11
+/// ```
+fn parse_session<'a>(
+    language: Language,
+) -> impl FnMut(&'a str) -> IResult<'a, (&'a str, Option<&'a str>)> {
+    delimited(
+        label(language.vocabulary().session),
+        pair(hex_digit1, opt(preceded(space1, session_time))),
+        row_ending,
+    )
+}
+
+/// parse a date and time like `2023-08-03 19:42:11`
+fn session_time(input: &str) -> IResult<'_, &str> {
+    fn digits<'a>(count: usize) -> impl FnMut(&'a str) -> IResult<'a, &'a str> {
+        take_while_m_n(count, count, |c: char| c.is_ascii_digit())
+    }
+
+    context(
+        "session time",
+        recognize(tuple((
+            separated_pair(
+                digits(4),
+                tag("-"),
+                separated_pair(digits(2), tag("-"), digits(2)),
+            ),
+            tag(" "),
+            separated_pair(
+                digits(2),
+                tag(":"),
+                separated_pair(digits(2), tag(":"), digits(2)),
+            ),
+        ))),
+    )(input)
+}
+
+fn parse_total<'a>(language: Language) -> impl FnMut(&'a str) -> IResult<'a, (Reward, u32)> {
+    map(
+        preceded(
+            label(language.vocabulary().total),
+            tuple((
+                simple_amount(Currency::Silverlions),
+                list_separator,
+                simple_amount(Currency::ConvertibleResearch),
+                list_separator,
+                simple_amount(Currency::Research),
+                opt(preceded(list_separator, parse_squadron_research)),
+            )),
+        ),
+        |(silverlions, _, crp, _, research, squadron_research)| {
+            (
+                Reward::new(silverlions, research)
+                    .with_squadron_research(squadron_research.unwrap_or_default()),
+                crp,
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use nom::{error::convert_error, Finish};
+    use rstest::*;
+
+    use super::Currency;
+    use crate::*;
+
+    fn run_parser<'a, T, P>(input: &'a str, mut parser: P) -> (&'a str, T)
+    where
+        P: FnMut(&'a str) -> super::IResult<'a, T>,
+    {
+        match parser(input).finish() {
+            Ok(result) => result,
+            Err(err) => panic!("\n{}", convert_error(input, err)),
+        }
+    }
+
+    #[test]
+    fn parse_victory_as_result_name() {
+        let input = "Victory";
+        assert_eq!(
+            super::battle_result(Language::English)(input),
+            Ok(("", BattleResult::Win))
+        )
+    }
+
+    #[test]
+    fn parse_defeat_as_result_name() {
+        let input = "Defeat";
+        assert_eq!(
+            super::battle_result(Language::English)(input),
+            Ok(("", BattleResult::Loss))
+        )
+    }
+
+    #[test]
+    fn parse_draw_as_result_name() {
+        let input = "Draw";
+        assert_eq!(
+            super::battle_result(Language::English)(input),
+            Ok(("", BattleResult::Draw))
+        )
+    }
+
+    #[test]
+    fn test_parse_result_line() {
+        let input = "Victory in the [Domination] Poland (winter) mission!\r\n\n";
+        let result = super::result_line(Language::English)(input).finish();
+        match result {
+            Ok((_, (result, map))) => {
+                assert_eq!(result, BattleResult::Win);
+                assert_eq!(map, "[Domination] Poland (winter)")
+            }
+            Err(err) => {
+                panic!("Error parsing result line:\n{}", convert_error(input, err))
+            }
+        }
+    }
+
+    #[rstest]
+    #[case("Player: Chronophylos\n", None, "Chronophylos")]
+    #[case("Player: [WTBR] Chronophylos\n", Some("WTBR"), "Chronophylos")]
+    #[case("Player: [WTBR] Chronophylos   \r\n", Some("WTBR"), "Chronophylos")]
+    fn parse_player_line(#[case] input: &str, #[case] squadron: Option<&str>, #[case] name: &str) {
+        let (input, (actual_squadron, actual_name)) =
+            run_parser(input, super::player_line(Language::English));
+        assert_eq!(input, "");
+        assert_eq!(actual_squadron, squadron);
+        assert_eq!(actual_name, name);
+    }
+
+    #[rstest]
+    #[case("./data/160409b0002a1af-player.report", Some("Chronophylos"), None)]
+    #[case(
+        "./data/1703c87000d01c0-squadron.report",
+        Some("Chronophylos"),
+        Some("WTBR")
+    )]
+    #[case("./data/1603c1c00028a36.report", None, None)]
+    fn parse_player_from_report(
+        #[case] path: &str,
+        #[case] player_name: Option<&str>,
+        #[case] squadron: Option<&str>,
+    ) {
+        let input = std::fs::read_to_string(path).unwrap();
+        let report = super::parse(&input).unwrap();
+        assert_eq!(report.player_name.as_deref(), player_name);
+        assert_eq!(report.squadron.as_deref(), squadron);
+    }
+
+    #[rstest]
+    fn test_real_data(
+        #[files("./data/*.report")]
+        #[files("./data/utf16/*.report")]
+        #[files("./data/crlf/*.report")]
+        path: PathBuf,
+    ) {
+        if let Err(err) = from_path(&path) {
+            panic!("\n{err}")
+        }
+    }
+
+    /// `data/crlf` holds copies of reports with CRLF line endings, some of
+    /// them only on every other line
+    #[rstest]
+    #[case("1603c1c00028a36.report", "1603c1c00028a36.report")]
+    #[case("1603c1c00028a36-mixed.report", "1603c1c00028a36.report")]
+    #[case("160409b0002a1af.report", "160409b0002a1af.report")]
+    #[case("1703c87000d01c0-items.report", "1703c87000d01c0-items.report")]
+    #[case("1703c87000d01c0-items-mixed.report", "1703c87000d01c0-items.report")]
+    fn line_endings_match_plain_report(#[case] crlf: &str, #[case] plain: &str) {
+        let crlf = std::fs::read_to_string(format!("./data/crlf/{crlf}")).unwrap();
+        let plain = std::fs::read_to_string(format!("./data/{plain}")).unwrap();
+        assert_eq!(super::parse(&crlf).unwrap(), super::parse(&plain).unwrap());
+
+        let (_, warnings) = super::parse_with_options(&crlf, ParseOptions::lenient()).unwrap();
+        assert_eq!(warnings, []);
+    }
+
+    #[test]
+    fn dated_session_matches_plain_report() {
+        let dated = std::fs::read_to_string("./data/dated/1712a4f0003b2c5.report").unwrap();
+        let plain = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        #[allow(unused_mut)]
+        let mut dated = super::parse(&dated).unwrap();
+
+        #[cfg(feature = "timestamps")]
+        {
+            let time = crate::datetime::naive((2023, 8, 3), (19, 42, 11));
+            assert_eq!(dated.session_time, Some(time));
+            dated.session_time = None;
+        }
+        assert_eq!(dated, super::parse(&plain).unwrap());
+    }
+
+    #[rstest]
+    #[case("Session: 1712a4f0003b2c5\n", None)]
+    #[case(
+        "Session: 1712a4f0003b2c5 2023-08-03 19:42:11\n",
+        Some("2023-08-03 19:42:11")
+    )]
+    fn parse_session_line(#[case] input: &str, #[case] expected: Option<&str>) {
+        let (input, (session_id, time)) =
+            run_parser(input, super::parse_session(Language::English));
+        assert!(input.is_empty());
+        assert_eq!(session_id, "1712a4f0003b2c5");
+        assert_eq!(time, expected);
+    }
+
+    #[test]
+    fn parse_complete_report() {
+        fn event(time: u32, kind: EventKind, enemy: &str, sl: u32, rp: u32) -> Event {
+            Event {
+                time,
+                kind,
+                vehicle: "Z-25".to_string(),
+                enemy: Some(enemy.to_string()),
+                ally: None,
+                assisted: false,
+                reward: Reward::new(sl, rp),
+            }
+        }
+
+        fn table(name: &str, count: usize, sl: u32, rp: u32) -> EventTable {
+            EventTable {
+                name: name.to_string(),
+                kind: EventKind::from_name(name),
+                count,
+                total: Reward::new(sl, rp),
+            }
+        }
+
+        let input = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        assert_eq!(
+            super::parse(&input).unwrap(),
+            BattleReport {
+                session_id: "1712a4f0003b2c5".to_string(),
+                #[cfg(feature = "timestamps")]
+                session_time: None,
+                result: BattleResult::Win,
+                mission_name: "[Domination] Sea of Japan".to_string(),
+                player_name: None,
+                squadron: None,
+                events: vec![
+                    event(
+                        4 * 60 + 12,
+                        EventKind::DestructionOfFleets,
+                        "Fletcher",
+                        1630,
+                        98
+                    ),
+                    event(
+                        9 * 60 + 48,
+                        EventKind::DestructionOfFleets,
+                        "Porter",
+                        1630,
+                        98
+                    ),
+                    event(3 * 60 + 5, EventKind::Damage, "Fletcher", 90, 6),
+                    event(3 * 60 + 40, EventKind::Damage, "Fletcher", 90, 6),
+                    event(9 * 60 + 2, EventKind::Damage, "Porter", 90, 6),
+                    event(6 * 60 + 31, EventKind::DamageToBase, "Base", 420, 30),
+                    event(6 * 60 + 31, EventKind::AttackOfBase, "Base", 210, 15),
+                    event(2 * 60 + 20, EventKind::CaptureOfZones, "41%", 800, 60),
+                ],
+                event_tables: vec![
+                    table("Destruction of enemy fleets", 2, 3260, 196),
+                    table("Damage to the enemy", 3, 270, 18),
+                    table("Damage to the base", 1, 420, 30),
+                    table("Attack of the base", 1, 210, 15),
+                    table("Capture of the zone", 1, 800, 60),
+                ],
+                awards: vec![
+                    Award {
+                        time: 9 * 60 + 49,
+                        name: "Without a miss".to_string(),
+                        reward: Reward::new(200, 0),
+                        golden_eagles: 0,
+                    },
+                    Award {
+                        time: 12 * 60 + 30,
+                        name: "The Best Squad".to_string(),
+                        reward: Reward::new(900, 100),
+                        golden_eagles: 0,
+                    },
+                ],
+                awards_header_count: 2,
+                awards_header_total: Reward::new(1100, 100),
+                reward_for_winning: Some(Reward::new(2100, 0)),
+                first_victory_bonus: None,
+                other_awards: Reward::new(5, 0),
+                completed_tasks: Vec::new(),
+                wagers: Vec::new(),
+                vehicles: vec![Vehicle {
+                    name: "Z-25".to_string(),
+                    activity: 91,
+                    time_played: 12 * 60 + 5,
+                    reward: Reward::new(650, 55 + 720),
+                }],
+                crew_experience: Vec::new(),
+                activity: 91,
+                activity_basis_points: 9100,
+                damaged_vehicles: vec!["Z-25".to_string()],
+                automatic_repair: 2650,
+                automatic_purchases: 400,
+                vehicle_research: Vec::new(),
+                modification_research: Vec::new(),
+                squadron_research: None,
+                used_items: Vec::new(),
+                earned_rewards: Reward::new(8815, 1194),
+                converted_research: None,
+                balance: Reward::new(5765, 1194),
+            }
+        );
+    }
+
+    #[test]
+    fn event_tables_keep_header_totals() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let report = super::parse(&input).unwrap();
+
+        let (scouting, events) = report
+            .events_by_table()
+            .find(|(table, _)| table.kind == EventKind::Scouting)
+            .unwrap();
+        assert_eq!(scouting.count, 5);
+        assert_eq!(scouting.total, Reward::new(255, 0));
+        assert_eq!(events.len(), 5);
+        assert!(events.iter().all(|event| event.kind == EventKind::Scouting));
+
+        let scouted = report
+            .events_by_table()
+            .skip_while(|(table, _)| table.kind != EventKind::Scouting)
+            .skip(1)
+            .map(|(table, events)| (table.kind.clone(), events.len(), table.total.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scouted,
+            [
+                (EventKind::DamageToScoutedEnemy, 1, Reward::new(101, 0)),
+                (
+                    EventKind::DestructionOfScoutedEnemy,
+                    1,
+                    Reward::new(505, 40)
+                ),
+            ]
+        );
+        assert_eq!(
+            report
+                .events_by_table()
+                .map(|(_, events)| events.len())
+                .sum::<usize>(),
+            report.events.len()
+        );
+    }
+
+    #[rstest]
+    #[case("Automatic repair of all vehicles: -2650 SL\n", 0, 400)]
+    #[case(
+        "Automatic purchasing of ammo and \"Crew Replenishment\": -400 SL\n",
+        2650,
+        0
+    )]
+    fn missing_costs_are_zero(#[case] line: &str, #[case] repair: u32, #[case] purchases: u32) {
+        let input = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        assert!(input.contains(line));
+        let (report, issues) =
+            super::parse_validated(&input.replace(line, ""), ParseOptions::strict()).unwrap();
+        assert_eq!(report.automatic_repair, repair);
+        assert_eq!(report.automatic_purchases, purchases);
+        assert_eq!(
+            report.net_silverlions(),
+            8815 - i64::from(repair) - i64::from(purchases)
+        );
+        // the stated balance still includes the removed cost
+        assert_eq!(issues.len(), 1);
+    }
+
+    #[test]
+    fn grouped_numbers_match_plain_report() {
+        let plain = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let grouped = std::fs::read_to_string("./data/1603c1c00028a36-grouped.report").unwrap();
+        let plain = super::parse(&plain).unwrap();
+        let grouped = super::parse(&grouped).unwrap();
+        assert_eq!(grouped.earned_rewards.silverlions(), 24552);
+        assert_eq!(grouped.balance.silverlions(), plain.balance.silverlions());
+        assert_eq!(grouped.balance.research(), plain.balance.research());
+        assert_eq!(grouped.automatic_repair, plain.automatic_repair);
+        assert_eq!(grouped.vehicle_research[1].research, 1594);
+    }
+
+    #[test]
+    fn console_layout_matches_plain_report() {
+        let plain = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let console = std::fs::read_to_string("./data/1603c1c00028a36-console.report").unwrap();
+        let plain = super::parse(&plain).unwrap();
+        let console = super::parse(&console).unwrap();
+        assert_eq!(console.session_id, plain.session_id);
+        assert_eq!(console.activity, plain.activity);
+        assert_eq!(console.damaged_vehicles, plain.damaged_vehicles);
+        assert_eq!(console.automatic_repair, plain.automatic_repair);
+        assert_eq!(console.automatic_purchases, plain.automatic_purchases);
+        assert_eq!(
+            console.other_awards.research(),
+            plain.other_awards.research()
+        );
+        assert_eq!(
+            console.earned_rewards.silverlions(),
+            plain.earned_rewards.silverlions()
+        );
+        assert_eq!(console.balance.research(), plain.balance.research());
+    }
+
+    #[rstest]
+    #[case("Earned: 24552 SL, 2218 CRP\n")]
+    #[case("Earned:24552 SL,2218 CRP\n")]
+    #[case("Earned:   24552 SL,   2218 CRP   \n")]
+    fn parse_earned_with_any_spacing(#[case] input: &str) {
+        let (input, reward) = run_parser(input, super::parse_earned(Language::English));
+        assert_eq!(input, "");
+        assert_eq!(reward.silverlions(), 24552);
+        assert_eq!(reward.research(), 2218);
+    }
+
+    #[rstest]
+    #[case("./data/160409b0002a1af-tabs.report")]
+    #[case("./data/160409b0002a1af-narrow.report")]
+    fn indentation_variants_match_plain_report(#[case] path: &str) {
+        let plain = std::fs::read_to_string("./data/160409b0002a1af.report").unwrap();
+        let variant = std::fs::read_to_string(path).unwrap();
+        let plain = super::parse(&plain).unwrap();
+        let variant = super::parse(&variant).unwrap();
+        assert_eq!(variant.events.len(), plain.events.len());
+        for (variant, plain) in variant.events.iter().zip(&plain.events) {
+            assert_eq!(variant.time, plain.time);
+            assert_eq!(variant.kind, plain.kind);
+            assert_eq!(variant.vehicle, plain.vehicle);
+            assert_eq!(variant.enemy, plain.enemy);
+            assert_eq!(variant.reward.silverlions(), plain.reward.silverlions());
+            assert_eq!(variant.reward.research(), plain.reward.research());
+        }
+        assert_eq!(variant.awards.len(), plain.awards.len());
+        assert_eq!(variant.vehicles.len(), plain.vehicles.len());
+        assert_eq!(variant.vehicles[0].name, plain.vehicles[0].name);
+        assert_eq!(variant.vehicles[0].activity, plain.vehicles[0].activity);
+    }
+
+    #[rstest]
+    #[case("\t2:58\tYaG-10 (29-K)\tM5A1\t930 SL\t55 RP\n")]
+    #[case("  2:58  YaG-10 (29-K)  M5A1  930 SL  55 RP\n")]
+    #[case("    2:58     YaG-10 (29-K)    M5A1            930 SL    55 RP\n")]
+    fn parse_row_with_any_indentation(#[case] input: &str) {
+        let (input, row) = run_parser(input, super::table_row);
+        assert_eq!(input, "");
+        assert_eq!(row.time, 2 * 60 + 58);
+        assert_eq!(row.vehicle, "YaG-10 (29-K)");
+        assert_eq!(row.enemy_vehicle, Some("M5A1"));
+        assert_eq!(row.reward.silverlions(), 930);
+        assert_eq!(row.reward.research(), 55);
+    }
+
+    #[test]
+    fn german_report_matches_english_report() {
+        let english = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let english = super::parse(&english).unwrap();
+        let german = std::fs::read_to_string("./data/de/1603c1c00028a36.report").unwrap();
+        let mut german = super::parse_with_language(&german, Language::German).unwrap();
+
+        assert_eq!(german.awards.len(), english.awards.len());
+        for (german, english) in german.awards.iter_mut().zip(&english.awards) {
+            assert_eq!(german.time, english.time);
+            assert_eq!(german.reward, english.reward);
+            german.name.clone_from(&english.name);
+        }
+        assert_eq!(german, english);
+    }
+
+    #[test]
+    fn german_report_is_not_english() {
+        let german = std::fs::read_to_string("./data/de/1603c1c00028a36.report").unwrap();
+        assert!(super::parse(&german).is_err());
+    }
+
+    #[rstest]
+    #[case("T-34 (1940)", "T-34 (1940)", "")]
+    #[case("Pz.Kpfw. IV Ausf. F2    92%", "Pz.Kpfw. IV Ausf. F2", "    92%")]
+    #[case("Wyvern S4 \n", "Wyvern S4", " \n")]
+    #[case("Wyvern S4, Concept 3", "Wyvern S4", ", Concept 3")]
+    #[case("IT-1,5", "IT-1,5", "")]
+    #[case("T-34 (1941): 1191 RP", "T-34 (1941)", ": 1191 RP")]
+    fn parse_vehicle_name(#[case] input: &str, #[case] name: &str, #[case] rest: &str) {
+        let (input, parsed) = run_parser(input, super::vehicle_name);
+        assert_eq!(parsed, name);
+        assert_eq!(input, rest);
+    }
+
+    #[rstest]
+    #[case("Damaged Vehicles: Z-25\n", &["Z-25"])]
+    #[case(
+        "Damaged Vehicles: Wyvern S4, Concept 3\n",
+        &["Wyvern S4", "Concept 3"]
+    )]
+    #[case(
+        "Damaged Vehicles:  T-34 (1940), KV-1 (L-11), Pz.Kpfw. IV Ausf. F2   \n",
+        &["T-34 (1940)", "KV-1 (L-11)", "Pz.Kpfw. IV Ausf. F2"]
+    )]
+    #[case("Damaged Vehicles: B18B , Ikv 103 \n", &["B18B", "Ikv 103"])]
+    fn parse_damaged_vehicles(#[case] input: &str, #[case] expected: &[&str]) {
+        let (input, vehicles) = run_parser(input, super::parse_damaged_vehicles(Language::English));
+        assert_eq!(input, "");
+        assert_eq!(vehicles, expected);
+    }
+
+    #[test]
+    fn damaged_vehicles_of_report() {
+        let input = std::fs::read_to_string("./data/160409b0002a1af.report").unwrap();
+        let report = super::parse(&input).unwrap();
+        assert_eq!(
+            report.damaged_vehicles,
+            ["T-34 (1940)", "KV-1 (L-11)", "YaG-10 (29-K)"]
+        );
+    }
+
+    #[rstest]
+    #[case("Crew experience: Concept 3: 320\n\n", &[("Concept 3", 320)])]
+    #[case(
+        "Crew experience:\nConcept 3: 320\nSherman Firefly: 150    \n\n",
+        &[("Concept 3", 320), ("Sherman Firefly", 150)]
+    )]
+    fn parse_crew_experience(#[case] input: &str, #[case] expected: &[(&str, u32)]) {
+        let (input, crews) = run_parser(input, super::parse_crew_experience(Language::English));
+        assert_eq!(input, "");
+        let crews = crews
+            .iter()
+            .map(|crew| (crew.vehicle, crew.experience))
+            .collect::<Vec<_>>();
+        assert_eq!(crews, expected);
+    }
+
+    #[test]
+    fn parse_report_with_crew_experience() {
+        let plain = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let input =
+            std::fs::read_to_string("./data/1603c1c00028a36-crew-experience.report").unwrap();
+        let mut report = super::parse(&input).unwrap();
+        assert_eq!(
+            std::mem::take(&mut report.crew_experience),
+            [
+                CrewExperience {
+                    vehicle: "Concept 3".to_string(),
+                    experience: 320,
+                },
+                CrewExperience {
+                    vehicle: "Sherman Firefly".to_string(),
+                    experience: 150,
+                },
+                CrewExperience {
+                    vehicle: "Wyvern S4".to_string(),
+                    experience: 85,
+                },
+            ]
+        );
+        assert_eq!(report, super::parse(&plain).unwrap());
+    }
+
+    #[test]
+    fn parse_used_items() {
+        let input = std::fs::read_to_string("./data/1703c87000d01c0-items.report").unwrap();
+        let report = super::parse(&input).unwrap();
+        assert_eq!(
+            report.used_items,
+            [
+                UsedItem {
+                    name: "Fire extinguisher".to_string(),
+                    count: 2
+                },
+                UsedItem {
+                    name: "Repair kit".to_string(),
+                    count: 1
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("Used items:\nRepair Kit x2\n", &[("Repair Kit", 2)])]
+    #[case(
+        "Used items: \nRepair Kit x2, Fire Extinguisher x1\n",
+        &[("Repair Kit", 2), ("Fire Extinguisher", 1)]
+    )]
+    #[case(
+        "Used items:\nFire extinguisher: 2\nScout UAV x10  \n",
+        &[("Fire extinguisher", 2), ("Scout UAV", 10)]
+    )]
+    #[case("Used items:\nActive boosters RP: \nCommon: +10%RP\n* Personal booster\n", &[])]
+    #[case(
+        "Used items:\nUniversal backup: 1\nBooster \"+50% RP\": 1\n",
+        &[("Universal backup", 1), ("Booster \"+50% RP\"", 1)]
+    )]
+    #[case("Used items:\n\n", &[])]
+    fn parse_used_item_lines(#[case] input: &str, #[case] expected: &[(&str, u32)]) {
+        let (input, (items, skipped)) = run_parser(
+            input,
+            super::parse_used_items(Language::English, ParseOptions::strict()),
+        );
+        assert_eq!(input, "");
+        assert!(skipped.is_empty(), "{skipped:?}");
+        let items = items
+            .iter()
+            .map(|item| (item.name, item.count))
+            .collect::<Vec<_>>();
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn unknown_used_item_line() {
+        let input = std::fs::read_to_string("./data/1703c87000d01c0-items.report").unwrap();
+        let input = input.replace("Repair kit: 1\n", "Repair kit: 1\nMystery crate: lots\n");
+
+        let super::Error::Syntax(err) = super::parse(&input).unwrap_err() else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!(err.section.as_deref(), Some("used items"));
+        assert_eq!(err.context, ["used items", "used item"]);
+        assert_eq!(err.line, 64);
+
+        let (report, warnings) =
+            super::parse_with_options(&input, super::ParseOptions::lenient()).unwrap();
+        assert_eq!(report.used_items.len(), 2);
+        assert_eq!(
+            warnings,
+            [Warning {
+                message: r#"skipped unknown used item "Mystery crate: lots""#.to_string(),
+                lines: Some(64..=64),
+            }]
+        );
+    }
+
+    #[test]
+    fn missing_session_after_used_items() {
+        let input = std::fs::read_to_string("./data/1703c87000d01c0-items.report").unwrap();
+        let input = input.replace("Session: 1703c87000d01c0\n", "");
+        let super::Error::Syntax(err) = super::parse(&input).unwrap_err() else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!(err.section.as_deref(), Some("session id"));
+        assert!(err.snippet.starts_with("Total: "), "{}", err.snippet);
+    }
+
+    #[rstest]
+    #[case("./data/1703c87000d01c0-repairs.report")]
+    #[case("./data/1603c1c00028a36.report")]
+    fn parse_without_used_items(#[case] path: &str) {
+        let input = std::fs::read_to_string(path).unwrap();
+        let report = super::parse(&input).unwrap();
+        assert!(report.used_items.is_empty());
+    }
+
+    #[test]
+    fn repeated_total_is_ignored() {
+        let input = std::fs::read_to_string("./data/lenient/duplicated-total.report").unwrap();
+        let (report, warnings) =
+            super::parse_with_options(&input, super::ParseOptions::lenient()).unwrap();
+        assert_eq!(report.balance.silverlions(), 19796);
+        assert_eq!(report.balance.research(), 2118);
+        assert_eq!(warnings, [Warning::new("ignored repeated total line")]);
+    }
+
+    #[test]
+    fn error_points_at_broken_line() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let input = input.replace("Activity: 95%", "Activity: lots");
+        let super::Error::Syntax(err) = super::parse(&input).unwrap_err() else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!(err.section.as_deref(), Some("activity"));
+        assert_eq!(err.context, ["activity", "percent"]);
+        assert_eq!(err.offset, input.find("lots").unwrap());
+        assert_eq!(err.line, 69);
+        assert_eq!(err.column, 11);
+        assert_eq!(err.snippet, "Activity: lots");
+        assert_eq!(
+            err.to_string(),
+            r#"Error parsing battle report: activity at line 69, column 11: "Activity: lots""#
+        );
+        assert!(err.verbose().contains("Activity:"), "{}", err.verbose());
+    }
+
+    #[test]
+    fn debriefing_is_wrong_format() {
+        let input = std::fs::read_to_string("./data/invalid/debriefing.txt").unwrap();
+        let err = super::parse(&input).unwrap_err();
+        assert!(
+            matches!(&err, super::Error::WrongReportFormat { hint } if hint.contains("message log")),
+            "{err:?}"
+        );
+        assert!(err.to_string().contains("debriefing screen"), "{err}");
+    }
 
-fn parse_used_items(input: &str) -> IResult<&str> {
-    preceded(
-        pair(tag("Used items: "), line_ending),
-        take_until("Session: "),
-    )(input)
-}
+    #[test]
+    fn broken_report_is_not_wrong_format() {
+        let err = super::parse("Victory in the mission!\nMy results\n").unwrap_err();
+        assert!(matches!(err, super::Error::Syntax(_)), "{err:?}");
+    }
 
-fn parse_session_id(input: &str) -> IResult<String> {
-    delimited(tag("Session: "), map(hex_digit1, String::from), line_ending)(input)
-}
+    #[test]
+    fn failure_is_converted() {
+        let input = "Victory";
+        let err = nom::Err::Failure(nom::error::VerboseError {
+            errors: vec![(&input[3..], nom::error::VerboseErrorKind::Context("result"))],
+        });
+        let err = super::SyntaxError::new(input, err);
+        assert!(err.to_string().contains("result"), "{err}");
+        assert_eq!((err.line, err.column), (1, 4));
+    }
 
-fn parse_total(input: &str) -> IResult<(Reward, u32)> {
-    map(
-        preceded(
-            tag("Total: "),
-            tuple((
-                parse_silverlions_simple,
-                tag(", "),
-                parse_crp,
-                tag(", "),
-                parse_research_points_simple,
-            )),
-        ),
-        |(silverlions, _, crp, _, research)| {
-            (
-                Reward {
-                    silverlions,
-                    research,
+    #[test]
+    fn lenient_parse_skips_chat_lines() {
+        let clean = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let clean = super::parse(&clean).unwrap();
+        let input = std::fs::read_to_string("./data/lenient/chat-lines.report").unwrap();
+        let (report, warnings) =
+            super::parse_with_options(&input, super::ParseOptions::lenient()).unwrap();
+
+        assert_eq!(report, clean);
+        assert_eq!(
+            warnings,
+            [
+                Warning {
+                    message: r#"skipped chat message "[Squad] Chronophylos: gg""#.to_string(),
+                    lines: Some(21..=21),
                 },
-                crp,
-            )
-        },
-    )(input)
-}
+                Warning {
+                    message: r#"skipped chat message "[All] Nitro (T-34 (1942)): o7""#.to_string(),
+                    lines: Some(71..=71),
+                },
+            ]
+        );
+        assert!(super::parse(&input).is_err());
+    }
 
-#[cfg(test)]
-mod test {
-    use std::path::PathBuf;
+    #[test]
+    fn lenient_parse_drops_duplicated_rows() {
+        let input = std::fs::read_to_string("./data/lenient/duplicated-rows.report").unwrap();
+        let (report, warnings) =
+            super::parse_with_options(&input, super::ParseOptions::lenient()).unwrap();
+        assert_eq!(report.events.len(), 1 + 6 + 1 + 8 + 5 + 1 + 1);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].message.contains("Chi-To Late"));
+        assert_eq!(warnings[0].lines, Some(10..=10));
+    }
 
-    use nom::{error::convert_error, Finish};
-    use rstest::*;
+    #[test]
+    fn lenient_parse_skips_unknown_sections() {
+        let clean = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let clean = super::parse(&clean).unwrap();
+        let input = std::fs::read_to_string("./data/lenient/unknown-sections.report").unwrap();
+        let (report, warnings) =
+            super::parse_with_options(&input, super::ParseOptions::lenient()).unwrap();
 
-    use crate::*;
+        assert_eq!(report, clean);
+        let warnings = warnings
+            .iter()
+            .map(|warning| (warning.message.as_str(), warning.lines.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            [
+                (
+                    r#"skipped chat message "[Squad] Chronophylos: gg""#,
+                    Some(21..=21)
+                ),
+                (
+                    "skipped unrecognized lines before other awards",
+                    Some(67..=68)
+                ),
+                (
+                    "skipped unrecognized lines before researched vehicles",
+                    Some(78..=79)
+                ),
+                ("skipped unrecognized trailing lines", Some(88..=88)),
+            ]
+        );
+    }
 
-    fn run_parser<T, P>(input: &str, parser: P) -> (&str, T)
-    where
-        P: Fn(&str) -> super::IResult<T>,
-    {
-        match parser(input).finish() {
-            Ok(result) => result,
-            Err(err) => panic!("\n{}", convert_error(input, err)),
-        }
+    #[test]
+    fn strict_parse_rejects_unknown_sections() {
+        let input = std::fs::read_to_string("./data/lenient/unknown-sections.report").unwrap();
+        let input = input.replace("[Squad] Chronophylos: gg\n", "");
+        let super::Error::Syntax(err) = super::parse(&input).unwrap_err() else {
+            panic!("expected a syntax error");
+        };
+        assert_eq!(err.section.as_deref(), Some("other awards"));
+        assert_eq!(err.line, 66);
     }
 
     #[test]
-    fn parse_victory_as_result_name() {
-        let input = "Victory";
-        assert_eq!(super::battle_result(input), Ok(("", BattleResult::Win)))
+    fn strict_parse_rejects_duplicated_rows() {
+        let input = std::fs::read_to_string("./data/lenient/duplicated-rows.report").unwrap();
+        let err = super::parse(&input).unwrap_err().to_string();
+        assert!(err.contains("duplicated row"), "{err}");
+        assert!(err.contains("Chi-To Late"), "{err}");
     }
 
     #[test]
-    fn parse_defeat_as_result_name() {
-        let input = "Defeat";
-        assert_eq!(super::battle_result(input), Ok(("", BattleResult::Loss)))
+    fn table_with_missing_rows_fails() {
+        let input =
+            "Scouting of the enemy    2    102 SL\n    2:05    Concept 3    M36 GMC()    51 SL\n\n";
+        assert!(super::table(input).is_err());
     }
 
     #[test]
-    fn test_parse_result_line() {
-        let input = "Victory in the [Domination] Poland (winter) mission!\r\n\n";
-        let result = super::result_line(input).finish();
-        match result {
-            Ok((_, (result, map))) => {
-                assert_eq!(result, BattleResult::Win);
-                assert_eq!(map, "[Domination] Poland (winter)")
-            }
-            Err(err) => {
-                panic!("Error parsing result line:\n{}", convert_error(input, err))
-            }
-        }
+    fn lenient_parse_without_result_line() {
+        let input = std::fs::read_to_string("./data/lenient/missing-first-line.report").unwrap();
+        let (report, warnings) =
+            super::parse_with_options(&input, super::ParseOptions::lenient()).unwrap();
+        assert_eq!(report.result, BattleResult::Unknown);
+        assert_eq!(report.mission_name, "");
+        assert_eq!(report.session_id, "1603c1c00028a36");
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn strict_parse_requires_result_line() {
+        let input = std::fs::read_to_string("./data/lenient/missing-first-line.report").unwrap();
+        assert!(super::parse(&input).is_err());
+    }
+
+    #[test]
+    fn lenient_parse_without_session_line() {
+        let input = std::fs::read_to_string("./data/lenient/missing-session.report").unwrap();
+        let (report, warnings) =
+            super::parse_with_options(&input, super::ParseOptions::lenient()).unwrap();
+        assert_eq!(report.session_id, "");
+        assert_eq!(report.balance.silverlions(), 19796);
+        assert_eq!(
+            warnings,
+            [Warning::new(
+                "report has no session line; the session ID is unknown"
+            )]
+        );
+        assert!(super::parse(&input).is_err());
     }
 
     #[rstest]
-    fn test_real_data(#[files("./data/*.report")] path: PathBuf) {
-        let input = std::fs::read_to_string(&path).unwrap();
-        let result = super::parse(&input);
-        if let Err(err) = result {
-            panic!("\n{err}")
-        }
+    #[case("7:13", 7 * 60 + 13)]
+    #[case("0:05", 5)]
+    #[case("59:59", 59 * 60 + 59)]
+    #[case("1:02:33", 3600 + 2 * 60 + 33)]
+    fn parse_timestamp(#[case] input: &str, #[case] expected: u32) {
+        let (input, value) = run_parser(input, super::timestamp);
+        assert!(input.is_empty());
+        assert_eq!(value, expected)
+    }
+
+    #[rstest]
+    #[case::minutes_out_of_range("1:99:99")]
+    #[case::seconds_out_of_range("7:60")]
+    #[case::single_digit_seconds("7:5")]
+    #[case::overflowing_hours("1193047:00:00")]
+    #[case::overflowing_minutes("71582789:00")]
+    fn reject_invalid_timestamp(#[case] input: &str) {
+        assert!(super::timestamp(input).is_err(), "{input}");
+    }
+
+    #[test]
+    fn parse_row_with_hours() {
+        let input = "    1:02:33    Concept 3    M6A1    1010 SL    77 RP\n";
+        let (input, row) = run_parser(input, super::table_row);
+        assert_eq!(input, "");
+        assert_eq!(row.time, 3600 + 2 * 60 + 33);
     }
 
     #[rstest]
-    #[case("100 RP", 100)]
-    #[case("3242 RP", 3242)]
-    fn parse_research_points_simple(#[case] input: &str, #[case] expected: u32) {
-        let (input, value) = run_parser(input, super::parse_research_points_simple);
+    #[case("12450", 12450)]
+    #[case("12 450", 12450)]
+    #[case("1\u{a0}010", 1010)]
+    #[case("1\u{202f}234\u{202f}567", 1234567)]
+    fn parse_number(#[case] input: &str, #[case] expected: u32) {
+        let (input, value) = run_parser(input, super::number);
         assert!(input.is_empty());
         assert_eq!(value, expected)
     }
 
     #[rstest]
-    #[case("10 + (PA)10 + (Booster)10 + (Talismans)10 = 40 RP", 40)]
-    #[case("96 + (Talismans)96 = 192 RP", 192)]
-    #[case("113 + (Talismans)113 = 226 RP", 226)]
-    fn parse_research_points_complex(#[case] input: &str, #[case] expected: u32) {
-        let (input, value) = run_parser(input, super::parse_research_points_complex);
+    #[case("85%", 8500)]
+    #[case("85 %", 8500)]
+    #[case("85\u{a0}%", 8500)]
+    #[case("97,5%", 9750)]
+    #[case("97,5 %", 9750)]
+    #[case("97.4%", 9740)]
+    #[case("97.45%", 9745)]
+    #[case("97.456%", 9746)]
+    #[case("99.999%", 10000)]
+    #[case("255.99%", 25599)]
+    fn parse_percent(#[case] input: &str, #[case] expected: u16) {
+        let (input, value) = run_parser(input, super::percent);
         assert!(input.is_empty());
         assert_eq!(value, expected)
     }
 
     #[rstest]
-    #[case("10 + (PA)10 + (Booster)10 + (Talismans)10 = 40 RP", 40)]
-    #[case("100 RP", 100)]
-    #[case("96 + (Talismans)96 = 192 RP", 192)]
-    #[case("113 + (Talismans)113 = 226 RP", 226)]
-    fn parse_research_points(#[case] input: &str, #[case] expected: u32) {
-        let (input, value) = run_parser(input, super::parse_research_points);
+    #[case("Activity: 85 %\n", 8500)]
+    #[case("Activity: 97,5%\n", 9750)]
+    #[case("Activity: 97,5 %\n", 9750)]
+    fn parse_activity_line(#[case] input: &str, #[case] expected: u16) {
+        let (input, value) = run_parser(input, super::parse_activity(Language::English));
         assert!(input.is_empty());
         assert_eq!(value, expected)
     }
 
+    #[rstest]
+    #[case(9700, 97)]
+    #[case(9749, 97)]
+    #[case(9750, 98)]
+    #[case(25599, 255)]
+    #[case(u16::MAX, u8::MAX)]
+    fn round_to_whole_percent(#[case] basis_points: u16, #[case] expected: u8) {
+        assert_eq!(super::whole_percent(basis_points), expected);
+    }
+
+    #[rstest]
+    #[case("12 450 SL", " SL")]
+    #[case("1 0100 SL", " 0100 SL")]
+    #[case("10 10 SL", " 10 SL")]
+    fn parse_number_stops_at_invalid_group(#[case] input: &str, #[case] leftover: &str) {
+        let (input, _) = run_parser(input, super::number);
+        assert_eq!(input, leftover);
+    }
+
+    #[rstest]
+    #[case("100 RP", Currency::Research, 100)]
+    #[case("3242 RP", Currency::Research, 3242)]
+    #[case("1010 SL", Currency::Silverlions, 1010)]
+    #[case("12 450 SL", Currency::Silverlions, 12450)]
+    #[case("2218 CRP", Currency::ConvertibleResearch, 2218)]
+    #[case("1\u{a0}035 CRP", Currency::ConvertibleResearch, 1035)]
+    #[case("15 GE", Currency::GoldenEagles, 15)]
+    fn parse_simple_amount(#[case] input: &str, #[case] currency: Currency, #[case] total: u32) {
+        let (rest, value) = run_parser(input, super::amount);
+        assert!(rest.is_empty());
+        assert_eq!(value, (currency, RewardBreakdown::from(total)));
+
+        let (rest, value) = run_parser(input, super::simple_amount(currency));
+        assert!(rest.is_empty());
+        assert_eq!(value, total);
+    }
+
+    #[rstest]
+    #[case("96 + (Talismans)96 = 192 RP", Currency::Research, 96, &[(BonusKind::Talisman, 96)], 192)]
+    #[case(
+        "10 + (PA)10 + (Booster)10 + (Talismans)10 = 40 RP",
+        Currency::Research,
+        10,
+        &[(BonusKind::PremiumAccount, 10), (BonusKind::Booster, 10), (BonusKind::Talisman, 10)],
+        40
+    )]
+    #[case(
+        "10 + (Wager)5 = 15 RP",
+        Currency::Research,
+        10,
+        &[(BonusKind::Unknown("Wager".to_string()), 5)],
+        15
+    )]
+    #[case("53 + (Booster)8 = 61 SL", Currency::Silverlions, 53, &[(BonusKind::Booster, 8)], 61)]
+    #[case(
+        "1 050 + (PA)1 050 + (Booster)150 = 2 250 SL",
+        Currency::Silverlions,
+        1050,
+        &[(BonusKind::PremiumAccount, 1050), (BonusKind::Booster, 150)],
+        2250
+    )]
+    #[case("20 + (Booster)5 = 25 CRP", Currency::ConvertibleResearch, 20, &[(BonusKind::Booster, 5)], 25)]
+    #[case("10 + (PA)5 = 15 GE", Currency::GoldenEagles, 10, &[(BonusKind::PremiumAccount, 5)], 15)]
+    fn parse_amount_with_bonuses(
+        #[case] input: &str,
+        #[case] currency: Currency,
+        #[case] base: u32,
+        #[case] bonuses: &[(BonusKind, u32)],
+        #[case] total: u32,
+    ) {
+        let breakdown = RewardBreakdown {
+            base,
+            bonuses: bonuses.to_vec(),
+            total,
+        };
+        let (rest, value) = run_parser(input, super::amount);
+        assert!(rest.is_empty());
+        assert_eq!(value, (currency, breakdown.clone()));
+
+        let (rest, value) = run_parser(input, super::amount_in(currency));
+        assert!(rest.is_empty());
+        assert_eq!(value, breakdown);
+
+        assert!(super::simple_amount(currency)(input).is_err());
+    }
+
+    #[rstest]
+    #[case("100 RP", Currency::Silverlions)]
+    #[case("100 CRP", Currency::Research)]
+    #[case("100 SL", Currency::GoldenEagles)]
+    #[case("10 + (PA)10 = 20 SL", Currency::Research)]
+    fn amount_in_other_currency(#[case] input: &str, #[case] currency: Currency) {
+        assert!(super::amount_in(currency)(input).is_err());
+    }
+
+    #[rstest]
+    #[case("100 XP")]
+    #[case("100")]
+    #[case("10 + (PA)10 = 20")]
+    #[case("10 + 10 = 20 SL")]
+    fn parse_invalid_amount(#[case] input: &str) {
+        assert!(super::amount(input).is_err());
+    }
+
     #[rstest]
     #[case("5820 SL     413 RP", 5820, 413)]
     #[case("1000 SL", 1000, 0)]
     #[case("505 SL    10 + (PA)10 + (Booster)10 + (Talismans)10 = 40 RP", 505, 40)]
     #[case("53 + (Booster)8 = 61 SL    3 + (Booster)2 = 5 RP", 61, 5)]
+    #[case("12 450 SL     1\u{a0}413 RP", 12450, 1413)]
+    #[case("1 050 + (Booster)150 = 1 200 SL", 1200, 0)]
     fn parse_reward(#[case] input: &str, #[case] silverlions: u32, #[case] research: u32) {
         let (input, reward) = run_parser(input, super::parse_reward);
         assert_eq!("", input);
-        assert_eq!(reward.silverlions, silverlions);
-        assert_eq!(reward.research, research);
+        assert_eq!(reward.silverlions(), silverlions);
+        assert_eq!(reward.research(), research);
+    }
+
+    #[rstest]
+    #[case("50 SRP", Reward::new(0, 0).with_squadron_research(50))]
+    #[case("1630 SL    98 RP    50 SRP", Reward::new(1630, 98).with_squadron_research(50))]
+    #[case("1630 SL    50 SRP", Reward::new(1630, 0).with_squadron_research(50))]
+    #[case("98 RP    1 050 SRP", Reward::new(0, 98).with_squadron_research(1050))]
+    fn parse_reward_with_squadron_research(#[case] input: &str, #[case] expected: Reward) {
+        let (input, reward) = run_parser(input, super::parse_reward);
+        assert_eq!(input, "");
+        assert_eq!(reward, expected);
+    }
+
+    #[rstest]
+    #[case("15 WB", Reward::new(0, 0).with_other_currency("WB", 15))]
+    #[case("1000 SL    15 WB", Reward::new(1000, 0).with_other_currency("WB", 15))]
+    #[case("Holiday Toys: 3", Reward::new(0, 0).with_other_currency("Holiday Toys", 3))]
+    #[case(
+        "1630 SL    98 RP    15 WB    Holiday Toys: 3",
+        Reward::new(1630, 98)
+            .with_other_currency("WB", 15)
+            .with_other_currency("Holiday Toys", 3)
+    )]
+    fn parse_reward_with_other_currencies(#[case] input: &str, #[case] expected: Reward) {
+        let (input, reward) = run_parser(input, super::parse_reward);
+        assert_eq!(input, "");
+        assert_eq!(reward, expected);
+    }
+
+    #[rstest]
+    #[case("Total: 19796 SL, 2218 CRP, 2118 RP", 0)]
+    #[case("Total: 19796 SL, 2218 CRP, 2118 RP, 50 SRP", 50)]
+    fn parse_total_with_squadron_research(#[case] input: &str, #[case] squadron_research: u32) {
+        let (input, (reward, crp)) = run_parser(input, super::parse_total(Language::English));
+        assert_eq!(input, "");
+        assert_eq!(
+            reward,
+            Reward::new(19796, 2118).with_squadron_research(squadron_research)
+        );
+        assert_eq!(crp, 2218);
     }
 
     #[test]
@@ -691,13 +3479,7 @@ mod test {
 36 GMC()     101 SL\n\nDestruction by allies of scouted enemies      1     505 SL      40 RP    \n    3:45    Concept 3    M36 GMC()     ×    505 SL    10 + (PA)10 + (Booster)10 + (Talismans)10 = 40
  RP\n";
         let (input, reward) = run_parser(input, super::parse_reward);
-        assert!(matches!(
-            reward,
-            Reward {
-                silverlions: 255,
-                research: 0
-            }
-        ));
+        assert_eq!(reward, Reward::new(255, 0));
 
         let leftover = "               \n    2:05    Concept 3    M36 GMC()       51 SL\n    3:04    Concept 3    M36 GMC()       51 SL\n    5:56    Concept 3    Chi-To Late     51 SL\n 
    6:25    Concept 3    M6A1            51 SL\n    6:51    Concept 3    ISU-122()       51 SL\n\nDamage taken by scouted enemies               1     101 SL               \n    3:45    Concept 3    M
@@ -714,7 +3496,8 @@ mod test {
         "Concept 3",
         "M6A1",
         1010,
-        77
+        77,
+        false
     )]
     #[case(
         "    8:17     Concept 3          ISU-122()       1010 SL    80 RP\n",
@@ -722,7 +3505,8 @@ mod test {
         "Concept 3",
         "ISU-122()",
         1010,
-        80
+        80,
+        false
     )]
     #[case(
         "    8:31     Concept 3          Chi-To Late     1010 SL    73 RP\n",
@@ -730,7 +3514,8 @@ mod test {
         "Concept 3",
         "Chi-To Late",
         1010,
-        73
+        73,
+        false
     )]
     #[case(
         "    10:07    Wyvern S4          Pe-8            440 SL    11 + (Talismans)11 = 22 RP\n",
@@ -738,7 +3523,8 @@ mod test {
         "Wyvern S4",
         "Pe-8",
         440,
-        22
+        22,
+        false
     )]
     #[case(
         "    13:14    Sherman Firefly    Chi-Nu II       930 SL     61 RP\n",
@@ -746,7 +3532,8 @@ mod test {
         "Sherman Firefly",
         "Chi-Nu II",
         930,
-        61
+        61,
+        false
     )]
     #[case(
         "    13:43    Sherman Firefly    KV-85           930 SL     64 RP\n",
@@ -754,9 +3541,10 @@ mod test {
         "Sherman Firefly",
         "KV-85",
         930,
-        64
+        64,
+        false
     )]
-    #[case("    3:45    Concept 3    M36 GMC()     ×    505 SL    10 + (PA)10 + (Booster)10 + (Talismans)10 = 40 RP\n", 3*60+45, "Concept 3", "M36 GMC()", 505, 40)]
+    #[case("    3:45    Concept 3    M36 GMC()     ×    505 SL    10 + (PA)10 + (Booster)10 + (Talismans)10 = 40 RP\n", 3*60+45, "Concept 3", "M36 GMC()", 505, 40, true)]
     fn parse_row(
         #[case] input: &str,
         #[case] time: u32,
@@ -764,14 +3552,82 @@ mod test {
         #[case] enemy_vehicle: &str,
         #[case] silverlions: u32,
         #[case] research: u32,
+        #[case] assisted: bool,
     ) {
         let (input, row) = super::table_row(input).unwrap();
         assert_eq!(input, "");
         assert_eq!(row.time, time);
         assert_eq!(row.vehicle, vehice);
-        assert_eq!(row.enemy_vehicle, enemy_vehicle);
-        assert_eq!(row.reward.silverlions, silverlions);
-        assert_eq!(row.reward.research, research);
+        assert_eq!(row.enemy_vehicle, Some(enemy_vehicle));
+        assert_eq!(row.reward.silverlions(), silverlions);
+        assert_eq!(row.reward.research(), research);
+        assert_eq!(row.assisted, assisted);
+    }
+
+    #[rstest]
+    #[case("    6:02     Bf 109 F-4    300 SL    20 RP\n", false)]
+    #[case("    6:02     Bf 109 F-4    300 SL    20 RP    \n", false)]
+    #[case("    6:02     Bf 109 F-4    ×    300 SL    20 RP\n", true)]
+    fn parse_row_without_enemy(#[case] input: &str, #[case] assisted: bool) {
+        let (input, row) = run_parser(input, super::table_row);
+        assert_eq!(input, "");
+        assert_eq!(row.vehicle, "Bf 109 F-4");
+        assert_eq!(row.enemy_vehicle, None);
+        assert_eq!(row.assisted, assisted);
+        assert_eq!(row.reward.silverlions(), 300);
+        assert_eq!(row.reward.research(), 20);
+    }
+
+    #[test]
+    fn parse_report_with_ally_assistance() {
+        let plain = plain_report();
+        let input = std::fs::read_to_string("./data/1703c87000d01c0-repairs.report").unwrap();
+        let mut report = super::parse(&input).unwrap();
+
+        let (helped, events) = std::mem::take(&mut report.events)
+            .into_iter()
+            .partition::<Vec<_>, _>(|event| event.kind == EventKind::AllyAssistance);
+        assert_eq!(
+            helped,
+            [
+                Event::new(8 * 60 + 12, EventKind::AllyAssistance, "Ikv 103")
+                    .with_ally("Strv m/42 EH")
+                    .with_reward(Reward::new(150, 5)),
+                Event::new(11 * 60 + 5, EventKind::AllyAssistance, "Ikv 103")
+                    .with_ally("Ikv 72")
+                    .with_reward(Reward::new(150, 5)),
+            ]
+        );
+        report.events = events;
+        report
+            .event_tables
+            .retain(|table| table.kind != EventKind::AllyAssistance);
+        assert_eq!(report, plain);
+    }
+
+    /// the real report the 1703c87000d01c0 fixtures are derived from, with the
+    /// time played row of the Ikv 103 they add
+    fn plain_report() -> BattleReport {
+        let input = std::fs::read_to_string("./data/lenient/unplayed-vehicle.report").unwrap();
+        let (mut report, _) = super::parse_with_options(&input, ParseOptions::lenient()).unwrap();
+        let ikv = &mut report.vehicles[1];
+        assert_eq!(ikv.name, "Ikv 103");
+        ikv.activity = 3;
+        ikv.time_played = 9;
+        report
+    }
+
+    #[test]
+    fn assisted_events_of_report() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let report = super::parse(&input).unwrap();
+        let assisted = report
+            .events
+            .iter()
+            .filter(|event| event.assisted)
+            .map(|event| (event.time, event.enemy.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(assisted, [(3 * 60 + 45, Some("M36 GMC()"))]);
     }
 
     #[test]
@@ -795,6 +3651,26 @@ Destruction by allies of scouted enemies      1     505 SL      40 RP
         assert_eq!(table.rows.len(), 5);
     }
 
+    #[test]
+    fn parse_table_without_rows() {
+        let input = "Damage to the enemy                           0       0 SL\n\
+            \n\
+            Capture of the zone                           1     800 SL      60 RP    \n\
+            \x20   2:20     Z-25    41%          800 SL    60 RP\n\
+            \n";
+        let (rest, table) = run_parser(input, super::table);
+        assert!(rest.starts_with("Capture of the zone"), "{rest:?}");
+        assert_eq!(table.name, "Damage to the enemy");
+        assert!(table.rows.is_empty());
+        assert_eq!(table.total, Reward::new(0, 0));
+
+        let (rest, tables) = run_parser(input, super::parse_events(Language::English));
+        assert_eq!(rest, "");
+        assert_eq!(tables.events.len(), 1);
+        assert_eq!(tables.tables[0].count, 0);
+        assert_eq!(tables.tables[1].count, 1);
+    }
+
     #[test]
     fn parse_scouting_table_header_with_leftovers() {
         let input = r#"Scouting of the enemy                         5     255 SL               
@@ -827,8 +3703,8 @@ Destruction by allies of scouted enemies      1     505 SL      40 RP
         assert_eq!(input, leftover);
         assert_eq!(name, "Scouting of the enemy");
         assert_eq!(count, 5);
-        assert_eq!(reward.silverlions, 255);
-        assert_eq!(reward.research, 0);
+        assert_eq!(reward.silverlions(), 255);
+        assert_eq!(reward.research(), 0);
     }
 
     #[test]
@@ -850,18 +3726,262 @@ Destruction by allies of scouted enemies      1     505 SL      40 RP
     13:55    The Best Squad           1000 SL    100 RP
 
 "#;
-        let (input, awards) = run_parser(input, super::award_table);
+        let (input, table) = run_parser(input, super::award_table);
+        assert_eq!(input, "");
+        assert_eq!(table.awards.len(), 14);
+        assert_eq!(table.count, 14);
+        assert_eq!(table.total, Reward::new(3450, 100));
+        assert_eq!(table.issue, None);
+    }
+
+    #[test]
+    fn parse_award_with_golden_eagles() {
+        let input = "Awards                                       3    1350 SL     100 RP    \n\
+            \x20   13:55    The Best Squad           1000 SL    100 RP    5 GE    \n\
+            \x20   13:55    Final blow!              250 SL           \n\
+            \x20   14:02    Tank Rescuer             100 SL    3 GE\n\
+            \n";
+        let (input, table) = run_parser(input, super::award_table);
         assert_eq!(input, "");
-        assert_eq!(awards.len(), 14);
+        assert_eq!(table.issue, None);
+        let awards = table
+            .awards
+            .iter()
+            .map(|award| (award.name, award.reward.clone(), award.golden_eagles))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            awards,
+            [
+                ("The Best Squad", Reward::new(1000, 100), 5),
+                ("Final blow!", Reward::new(250, 0), 0),
+                ("Tank Rescuer", Reward::new(100, 0), 3),
+            ]
+        );
     }
 
     #[test]
     fn parse_other_awards() {
         let input = "Other awards                                       5295 SL     115 RP    \n\n";
-        let (input, reward) = super::parse_other_awards(input).unwrap();
+        let (input, reward) = super::parse_other_awards(Language::English)(input).unwrap();
+        assert_eq!(input, "");
+        assert_eq!(reward.silverlions(), 5295);
+        assert_eq!(reward.research(), 115);
+    }
+
+    #[rstest]
+    #[case(
+        "Battle task completed: \"Destroy 5 ground vehicles\" 30000 SL\n",
+        "Destroy 5 ground vehicles",
+        Reward::new(30000, 0),
+        0,
+        0
+    )]
+    #[case(
+        "Battle task completed: \"Sink a ship: destroyer, cruiser, etc.\" 1000 SL, 2 GE\n",
+        "Sink a ship: destroyer, cruiser, etc.",
+        Reward::new(1000, 0),
+        0,
+        2
+    )]
+    #[case(
+        "Challenge completed: \"Win 3 battles with \"Z-25\"!\"    5 000 SL, 30 RP, 10 WB  \n",
+        "Win 3 battles with \"Z-25\"!",
+        Reward::new(5000, 30).with_other_currency("WB", 10),
+        0,
+        0
+    )]
+    #[case(
+        "Battle task completed: \"Research a vehicle\" 200 RP, 150 CRP\n",
+        "Research a vehicle",
+        Reward::new(0, 200),
+        150,
+        0
+    )]
+    #[case(
+        "Challenge completed: Earn 300 RP 300 RP\n",
+        "Earn 300 RP",
+        Reward::new(0, 300),
+        0,
+        0
+    )]
+    fn parse_completed_task(
+        #[case] input: &str,
+        #[case] name: &str,
+        #[case] reward: Reward,
+        #[case] convertible_research: u32,
+        #[case] golden_eagles: u32,
+    ) {
+        let (input, task) = run_parser(input, super::completed_task(Language::English));
+        assert_eq!(input, "");
+        assert_eq!(
+            task.to_owned(),
+            CompletedTask {
+                name: name.to_string(),
+                reward,
+                convertible_research,
+                golden_eagles,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_report_with_completed_tasks() {
+        let plain = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        let input = std::fs::read_to_string("./data/1712a4f0003b2c5-tasks.report").unwrap();
+        let mut report = super::parse(&input).unwrap();
+        let tasks = std::mem::take(&mut report.completed_tasks)
+            .into_iter()
+            .map(|task| task.name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tasks,
+            [
+                "Destroy 5 ground vehicles",
+                "Sink a ship: destroyer, cruiser, etc.",
+                "Win 3 battles with \"Z-25\"!",
+            ]
+        );
+        assert_eq!(report, super::parse(&plain).unwrap());
+    }
+
+    #[rstest]
+    #[case(
+        "Wager \"Kill streak\" stage 3/5 completed: 15000 SL\n",
+        "Kill streak",
+        Some((3, 5)),
+        Reward::new(15000, 0)
+    )]
+    #[case(
+        "Wager \"Kill streak\" completed: 50 000 SL    120 RP  \n",
+        "Kill streak",
+        None,
+        Reward::new(50000, 120)
+    )]
+    #[case(
+        "Wager \"Don't \"stage\" it\" stage 1/3 completed: 10 RP\n",
+        "Don't \"stage\" it",
+        Some((1, 3)),
+        Reward::new(0, 10)
+    )]
+    fn parse_wager(
+        #[case] input: &str,
+        #[case] name: &str,
+        #[case] stage: Option<(u32, u32)>,
+        #[case] reward: Reward,
+    ) {
+        let (input, wager) = run_parser(input, super::wager(Language::English));
+        assert_eq!(input, "");
+        assert_eq!(
+            wager.to_owned(),
+            WagerProgress {
+                name: name.to_string(),
+                stage: stage.map(|(stage, _)| stage),
+                stages_total: stage.map(|(_, total)| total),
+                reward,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_report_with_wagers() {
+        let plain = plain_report();
+        let input = std::fs::read_to_string("./data/1703c87000d01c0-wagers.report").unwrap();
+        let mut report = super::parse(&input).unwrap();
+        let wagers = std::mem::take(&mut report.wagers)
+            .into_iter()
+            .map(|wager| (wager.name, wager.stage, wager.reward.silverlions()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            wagers,
+            [
+                ("Kill streak".to_string(), Some(3), 15000),
+                ("Kill streak".to_string(), None, 50000),
+                ("Top of the team".to_string(), Some(1), 5000),
+            ]
+        );
+        assert_eq!(report, plain);
+    }
+
+    #[rstest]
+    #[case("Reward for winning                                4234 SL               \n\n")]
+    #[case("Reward for the victory    4234 SL\n\n")]
+    fn parse_reward_for_winning(#[case] input: &str) {
+        let (input, reward) = run_parser(input, super::parse_reward_for_winning(Language::English));
+        assert_eq!(input, "");
+        assert_eq!(reward, Reward::new(4234, 0));
+    }
+
+    #[rstest]
+    #[case("First victory of the day                          1000 SL    100 RP    \n\n")]
+    #[case("First victory of the day    1000 SL    100 RP\n")]
+    fn parse_first_victory_bonus(#[case] input: &str) {
+        let (input, reward) =
+            run_parser(input, super::parse_first_victory_bonus(Language::English));
+        assert_eq!(input, "");
+        assert_eq!(reward, Reward::new(1000, 100));
+    }
+
+    #[rstest]
+    #[case::after_reward_for_winning(false)]
+    #[case::before_reward_for_winning(true)]
+    fn parse_report_with_first_victory_bonus(#[case] before: bool) {
+        let plain = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        let mut input =
+            std::fs::read_to_string("./data/1712a4f0003b2c5-first-victory.report").unwrap();
+        if before {
+            let reward_for_winning =
+                "Reward for winning                                2100 SL               \n\n";
+            input = input.replacen(reward_for_winning, "", 1);
+            input = input.replacen(
+                "Other awards",
+                &format!("{reward_for_winning}Other awards"),
+                1,
+            );
+        }
+
+        let mut report = super::parse(&input).unwrap();
+        assert_eq!(
+            report.first_victory_bonus.take(),
+            Some(Reward::new(1000, 100))
+        );
+        assert_eq!(report, super::parse(&plain).unwrap());
+    }
+
+    #[rstest]
+    #[case("Squadron research: 680 points\n\n", 680)]
+    #[case("Squadron research:    1 250 points    \n\n", 1250)]
+    fn parse_squadron_research_line(#[case] input: &str, #[case] expected: u32) {
+        let (input, points) = run_parser(
+            input,
+            super::parse_squadron_research_line(Language::English),
+        );
         assert_eq!(input, "");
-        assert_eq!(reward.silverlions, 5295);
-        assert_eq!(reward.research, 115);
+        assert_eq!(points, expected);
+    }
+
+    #[test]
+    fn parse_report_with_squadron_research() {
+        let plain = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let input = plain.replacen("Session:", "Squadron research: 680 points\n\nSession:", 1);
+
+        let mut report = super::parse(&input).unwrap();
+        assert_eq!(report.squadron_research.take(), Some(680));
+        assert_eq!(report, super::parse(&plain).unwrap());
+    }
+
+    #[test]
+    fn missing_reward_for_winning_is_skipped() {
+        let input = "Other awards                                       5295 SL     115 RP    \n\n";
+        let (rest, reward) = super::optional_section(
+            input,
+            "reward for winning",
+            ParseOptions::strict(),
+            &mut Vec::new(),
+            super::parse_reward_for_winning(Language::English),
+        )
+        .unwrap();
+        assert_eq!(reward, None);
+        assert_eq!(rest, input);
     }
 
     #[test]
@@ -877,23 +3997,149 @@ Time Played                                   3               1057 RP
     Wyvern S4          67%    1:33    96 + (Talismans)96 = 192 RP
 
 "#;
-        let (input, vehicles) = run_parser(input, super::vehicle_tables);
+        let (input, (vehicles, issues, unplayed)) =
+            run_parser(input, super::vehicle_tables(ParseOptions::strict()));
         assert_eq!(input, "");
+        assert_eq!(issues, []);
+        assert_eq!(unplayed, []);
         assert_eq!(vehicles.len(), 3);
         assert_eq!(vehicles[0].name, "Concept 3");
         assert_eq!(vehicles[0].activity, 97);
         assert_eq!(vehicles[0].time_played, 8 * 60 + 21);
-        assert_eq!(vehicles[0].reward.silverlions, 730);
-        assert_eq!(vehicles[0].reward.research, 68 + 680);
+        assert_eq!(vehicles[0].reward.silverlions(), 730);
+        assert_eq!(vehicles[0].reward.research(), 68 + 680);
+    }
+
+    #[test]
+    fn vehicle_without_time_played_row() {
+        let input = "Activity Time                                 2    1252 SL     124 RP    \n\
+            \x20   13:54    Concept 3          730 SL     68 RP\n\
+            \x20   13:54    Sherman Firefly    522 SL     56 RP\n\
+            \n\
+            Time Played                                   1                185 RP    \n\
+            \x20   Sherman Firefly    84%    2:51    185 RP\n\
+            \n";
+        let (_, (vehicles, issues, unplayed)) =
+            run_parser(input, super::vehicle_tables(ParseOptions::lenient()));
+        assert_eq!(issues, []);
+        assert_eq!(
+            unplayed,
+            [(
+                "Concept 3",
+                "    13:54    Concept 3          730 SL     68 RP\n"
+            )]
+        );
+        let vehicles = vehicles
+            .iter()
+            .map(|vehicle| {
+                (
+                    vehicle.name,
+                    vehicle.activity,
+                    vehicle.time_played,
+                    vehicle.reward.research(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vehicles,
+            [
+                ("Concept 3", 0, 0, 68),
+                ("Sherman Firefly", 84, 2 * 60 + 51, 56 + 185)
+            ]
+        );
+
+        // the Ikv 103 was barely played and has no time played row
+        let input = std::fs::read_to_string("./data/lenient/unplayed-vehicle.report").unwrap();
+        assert!(super::parse(&input).is_err());
+        let (report, warnings) =
+            super::parse_with_options(&input, ParseOptions::lenient()).unwrap();
+        let warnings = warnings
+            .iter()
+            .filter(|warning| warning.message.contains("time played"))
+            .map(|warning| (warning.message.as_str(), warning.lines.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            [(
+                "vehicle \"Ikv 103\" has no time played row; its activity and time played are zero",
+                Some(39..=39)
+            )]
+        );
+        let vehicles = report
+            .vehicles
+            .iter()
+            .map(|vehicle| (vehicle.name.as_str(), vehicle.activity))
+            .collect::<Vec<_>>();
+        assert_eq!(vehicles, [("B18B", 68), ("Ikv 103", 0), ("Pvkv II", 92)]);
+    }
+
+    #[test]
+    fn time_played_row_without_activity_time_row() {
+        let input = "Activity Time                                 1    730 SL     68 RP    \n\
+            \x20   13:54    Concept 3          730 SL     68 RP\n\
+            \n\
+            Time Played                                   2                865 RP    \n\
+            \x20   Concept 3          97%    8:21    680 RP\n\
+            \x20   Sherman Firefly    84%    2:51    185 RP\n\
+            \n";
+        let Err(nom::Err::Error(err)) = super::vehicle_tables(ParseOptions::strict())(input) else {
+            panic!("expected an error");
+        };
+        let (rest, kind) = &err.errors[0];
+        assert!(rest.starts_with("Time Played"), "{rest:?}");
+        assert_eq!(
+            *kind,
+            nom::error::VerboseErrorKind::Context("time played row without an activity time row")
+        );
+    }
+
+    #[test]
+    fn activity_time_row_without_time_played_row() {
+        let input = "Activity Time                                 2    915 SL     80 RP    \n\
+            \x20   13:54    Concept 3          730 SL     68 RP\n\
+            \x20   2:51     Sherman Firefly    185 SL     12 RP\n\
+            \n\
+            Time Played                                   1                680 RP    \n\
+            \x20   Concept 3          97%    8:21    680 RP\n\
+            \n";
+        let Err(nom::Err::Error(err)) = super::vehicle_tables(ParseOptions::strict())(input) else {
+            panic!("expected an error");
+        };
+        let (rest, kind) = &err.errors[0];
+        assert!(rest.starts_with("    2:51     Sherman Firefly"), "{rest:?}");
+        assert_eq!(
+            *kind,
+            nom::error::VerboseErrorKind::Context("activity time row without a time played row")
+        );
     }
 
     #[test]
-    fn test_parse_vehicle_research() {
-        let input = "T-34 (1941): 1191 RP\n";
-        let (input, research) = run_parser(input, super::parse_vehicle_research);
+    fn vehicle_activity_is_not_report_activity() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let report = super::parse(&input).unwrap();
+        assert_eq!(report.activity, 95);
+        let concept = &report.vehicles[0];
+        assert_eq!(concept.name, "Concept 3");
+        assert_eq!(concept.activity, 97);
+        assert_ne!(concept.activity, report.activity);
+    }
+
+    #[rstest]
+    #[case("T-34 (1941): 1191 RP\n", "T-34 (1941)", 1191, None)]
+    #[case("T-34 (1941): 1 191 RP\n", "T-34 (1941)", 1191, None)]
+    #[case("T-34-85: 1 191 RP (34%)\n", "T-34-85", 1191, Some(34))]
+    #[case("T-34-85: 524 RP (100%)  \n", "T-34-85", 524, Some(100))]
+    fn test_parse_vehicle_research(
+        #[case] input: &str,
+        #[case] name: &str,
+        #[case] research: u32,
+        #[case] progress_percent: Option<u8>,
+    ) {
+        let (input, parsed) = run_parser(input, super::parse_vehicle_research);
         assert_eq!(input, "");
-        assert_eq!(research.name, "T-34 (1941)");
-        assert_eq!(research.research, 1191);
+        assert_eq!(parsed.name, name);
+        assert_eq!(parsed.research, research);
+        assert_eq!(parsed.progress_percent, progress_percent);
     }
 
     #[test]
@@ -902,7 +4148,7 @@ Time Played                                   3               1057 RP
 T-34 (1941): 1191 RP
 
 "#;
-        let (input, research) = run_parser(input, super::parse_researched_units);
+        let (input, research) = run_parser(input, super::parse_researched_units(Language::English));
         assert_eq!(input, "");
         assert_eq!(research.len(), 1);
         assert_eq!(research[0].name, "T-34 (1941)");
@@ -917,5 +4163,17 @@ T-34 (1941): 1191 RP
         assert_eq!(research.vehicle, "YaG-10 (29-K)");
         assert_eq!(research.name, "Improved Parts");
         assert_eq!(research.research, 220);
+        assert_eq!(research.convertible_research, 0);
+    }
+
+    #[test]
+    fn test_parse_modification_research_with_crp() {
+        let input = "YaG-10 (29-K) - Improved Parts: 220 RP, 35 CRP\n";
+        let (input, research) = run_parser(input, super::parse_modification_research);
+        assert_eq!(input, "");
+        assert_eq!(research.vehicle, "YaG-10 (29-K)");
+        assert_eq!(research.name, "Improved Parts");
+        assert_eq!(research.research, 220);
+        assert_eq!(research.convertible_research, 35);
     }
 }
@@ -0,0 +1,28 @@
+//! Common Imports
+//!
+//! Glob import this module to get the report types, the parse options and
+//! errors, and the entry points for parsing in one line.
+//!
+//! ```
+//! use wt_battle_report::prelude::*;
+//!
+//! # fn main() -> Result<(), Error> {
+//! # let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+//! let report: BattleReport = from_str(&input)?;
+//! println!("{} kills", report.score_inputs().kills);
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::from_reader;
+#[cfg(feature = "sqlite")]
+pub use crate::BattleStore;
+pub use crate::{
+    from_path, from_slice, from_str, from_str_with_options, parse_many, AggregateStats, Award,
+    BattleReport, BattleReportBuilder, BattleResult, BattleSummary, BonusKind, CompletedTask,
+    CrewExperience, Error, Event, EventFilter, EventKind, EventTable, Language,
+    ModificationResearch, ParseOptions, PathError, ReadError, Reward, RewardBreakdown,
+    ScoreFormula, ScoreInputs, SessionStats, UsedItem, ValidationIssue, Vehicle, VehicleResearch,
+    WagerProgress, Warning,
+};
@@ -0,0 +1,656 @@
+//! Battle Report Text
+//!
+//! Renders a [`BattleReport`] in the layout of an English report. Parsing the
+//! text gives back an equal report, though the column widths differ from the
+//! ones the game uses.
+
+use std::fmt;
+
+use crate::{language::Language, BattleReport, BattleResult, Event, Reward, RewardBreakdown};
+
+/// whitespace between two columns
+const SEPARATOR: &str = "    ";
+
+impl fmt::Display for BattleReport {
+    /// Render the report as text
+    ///
+    /// The parser adds the research points of the activity time and time
+    /// played tables up per vehicle, so the activity time table is written
+    /// with silver lions only and the time played table with all research
+    /// points.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let words = Language::English.vocabulary();
+
+        let result = match self.result {
+            BattleResult::Win => Some(words.victory),
+            BattleResult::Loss => Some(words.defeat),
+            BattleResult::Draw => Some(words.draw),
+            BattleResult::Unknown => None,
+        };
+        if let Some(result) = result {
+            writeln!(
+                f,
+                "{result}{}{}{}",
+                words.mission_prefix, self.mission_name, words.mission_suffix
+            )?;
+            if let Some(name) = &self.player_name {
+                write!(f, "{} ", words.player)?;
+                if let Some(squadron) = &self.squadron {
+                    write!(f, "[{squadron}] ")?;
+                }
+                writeln!(f, "{name}")?;
+            }
+            writeln!(f)?;
+        }
+
+        for (name, total, events) in self.tables_to_render() {
+            let rows = events
+                .iter()
+                .map(|event| {
+                    let mut row = vec![timestamp(event.time), event.vehicle.clone()];
+                    row.extend(event.enemy.clone().or_else(|| event.ally.clone()));
+                    if event.assisted {
+                        row.push("\u{d7}".to_string());
+                    }
+                    row.extend(reward(&event.reward));
+                    row
+                })
+                .collect::<Vec<_>>();
+            write_table(f, header(name, rows.len(), &total), &rows)?;
+        }
+
+        if !self.awards.is_empty() {
+            let rows = self
+                .awards
+                .iter()
+                .map(|award| {
+                    let mut row = vec![timestamp(award.time), award.name.clone()];
+                    row.extend(reward(&award.reward));
+                    if award.golden_eagles > 0 {
+                        row.push(format!("{} GE", award.golden_eagles));
+                    }
+                    row
+                })
+                .collect::<Vec<_>>();
+            let total = match self.awards_header_count == self.awards.len() {
+                true => self.awards_header_total.clone(),
+                false => self.awards.iter().map(|award| award.reward.clone()).sum(),
+            };
+            write_table(f, header(words.awards, rows.len(), &total), &rows)?;
+        }
+
+        if !self.vehicles.is_empty() {
+            let silverlions = self
+                .vehicles
+                .iter()
+                .map(|vehicle| vehicle.reward.silverlions())
+                .sum();
+            let research: u32 = self
+                .vehicles
+                .iter()
+                .map(|vehicle| vehicle.reward.research())
+                .sum();
+
+            let rows = self
+                .vehicles
+                .iter()
+                .map(|vehicle| {
+                    vec![
+                        timestamp(vehicle.time_played),
+                        vehicle.name.clone(),
+                        amount(&vehicle.reward.sl, "SL"),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            let total = Reward::new(silverlions, 0);
+            write_table(f, header(words.activity_time, rows.len(), &total), &rows)?;
+
+            let rows = self
+                .vehicles
+                .iter()
+                .map(|vehicle| {
+                    vec![
+                        vehicle.name.clone(),
+                        format!("{}%", vehicle.activity),
+                        timestamp(vehicle.time_played),
+                        amount(&vehicle.reward.rp, "RP"),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            let header = vec![
+                words.time_played.to_string(),
+                rows.len().to_string(),
+                format!("{research} RP"),
+            ];
+            write_table(f, header, &rows)?;
+        }
+
+        if !self.crew_experience.is_empty() {
+            writeln!(f, "{}", words.crew_experience[0])?;
+            for crew in &self.crew_experience {
+                writeln!(f, "{}: {}", crew.vehicle, crew.experience)?;
+            }
+            writeln!(f)?;
+        }
+
+        if let Some(reward_for_winning) = &self.reward_for_winning {
+            write_line(f, words.reward_for_winning[0], reward(reward_for_winning))?;
+        }
+        if let Some(bonus) = &self.first_victory_bonus {
+            write_line(f, words.first_victory[0], reward(bonus))?;
+        }
+        write_line(f, words.other_awards, reward(&self.other_awards))?;
+
+        for wager in &self.wagers {
+            write!(f, "{} \"{}\"", words.wager[0], wager.name)?;
+            if let (Some(stage), Some(stages_total)) = (wager.stage, wager.stages_total) {
+                write!(f, " stage {stage}/{stages_total}")?;
+            }
+            writeln!(f, " completed: {}", reward(&wager.reward).join(SEPARATOR))?;
+        }
+
+        if !self.completed_tasks.is_empty() {
+            for task in &self.completed_tasks {
+                let mut amounts = reward(&task.reward);
+                if task.convertible_research > 0 {
+                    amounts.push(format!("{} CRP", task.convertible_research));
+                }
+                if task.golden_eagles > 0 {
+                    amounts.push(format!("{} GE", task.golden_eagles));
+                }
+                writeln!(
+                    f,
+                    "{} \"{}\" {}",
+                    words.completed_task[0],
+                    task.name,
+                    amounts.join(", ")
+                )?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(
+            f,
+            "{} {} SL, {} CRP",
+            words.earned,
+            self.earned_rewards.silverlions(),
+            self.earned_rewards.research()
+        )?;
+        if let Some(converted) = self.converted_research {
+            writeln!(f, "{} {converted} CRP", words.converted_research[0])?;
+        }
+        writeln!(
+            f,
+            "{} {}",
+            words.activity,
+            percent(self.activity_basis_points)
+        )?;
+        writeln!(
+            f,
+            "{} {}",
+            words.damaged_vehicles,
+            self.damaged_vehicles.join(", ")
+        )?;
+        writeln!(
+            f,
+            "{} -{} SL",
+            words.automatic_repair, self.automatic_repair
+        )?;
+        writeln!(
+            f,
+            "{} -{} SL",
+            words.automatic_purchase, self.automatic_purchases
+        )?;
+        writeln!(f)?;
+
+        if !self.vehicle_research.is_empty() {
+            writeln!(f, "{}", words.researched_unit)?;
+            for research in &self.vehicle_research {
+                write!(f, "{}: {} RP", research.name, research.research)?;
+                if let Some(progress) = research.progress_percent {
+                    write!(f, " ({progress}%)")?;
+                }
+                writeln!(f)?;
+            }
+            writeln!(f)?;
+        }
+
+        if !self.modification_research.is_empty() {
+            writeln!(f, "{}", words.researching_progress)?;
+            for research in &self.modification_research {
+                write!(
+                    f,
+                    "{} - {}: {} RP",
+                    research.vehicle, research.name, research.research
+                )?;
+                if research.convertible_research > 0 {
+                    write!(f, ", {} CRP", research.convertible_research)?;
+                }
+                writeln!(f)?;
+            }
+            writeln!(f)?;
+        }
+
+        if let Some(points) = self.squadron_research {
+            writeln!(
+                f,
+                "{} {points} {}",
+                words.squadron_research[0], words.squadron_research_points[0]
+            )?;
+            writeln!(f)?;
+        }
+
+        if !self.used_items.is_empty() {
+            writeln!(f, "{}", words.used_items)?;
+            for item in &self.used_items {
+                writeln!(f, "{}: {}", item.name, item.count)?;
+            }
+            writeln!(f)?;
+        }
+
+        write!(f, "{} {}", words.session, self.session_id)?;
+        #[cfg(feature = "timestamps")]
+        if let Some(time) = self.session_time {
+            write!(f, " {}", crate::datetime::format_session_time(&time))?;
+        }
+        writeln!(f)?;
+        write!(
+            f,
+            "{} {} SL, {} CRP, {} RP",
+            words.total,
+            self.balance.silverlions(),
+            self.earned_rewards.research(),
+            self.balance.research()
+        )?;
+        if self.balance.squadron_research > 0 {
+            write!(f, ", {} SRP", self.balance.squadron_research)?;
+        }
+        Ok(())
+    }
+}
+
+impl BattleReport {
+    /// the event tables to render with their totals
+    ///
+    /// These are the parsed tables, including empty ones, unless the events
+    /// were changed since. Then there is one table per run of events of the
+    /// same kind.
+    fn tables_to_render(&self) -> Vec<(&str, Reward, &[Event])> {
+        let tables = self.events_by_table().collect::<Vec<_>>();
+        let parsed = tables.iter().map(|(_, events)| events.len()).sum::<usize>()
+            == self.events.len()
+            && tables.iter().all(|(table, events)| {
+                table.count == events.len() && events.iter().all(|event| event.kind == table.kind)
+            });
+        if parsed {
+            return tables
+                .into_iter()
+                .map(|(table, events)| (table.name.as_str(), table.total.clone(), events))
+                .collect();
+        }
+
+        self.events
+            .chunk_by(|a, b| a.kind == b.kind)
+            .map(|events| {
+                let total = events.iter().map(|event| event.reward.clone()).sum();
+                (events[0].kind.name(), total, events)
+            })
+            .collect()
+    }
+}
+
+/// the header of a table with `count` rows summing up to `total`
+///
+/// Like in the game, the total is written without bonuses.
+fn header(name: &str, count: usize, total: &Reward) -> Vec<String> {
+    let mut header = vec![name.to_string(), count.to_string()];
+    header.extend(reward(&Reward::new(total.silverlions(), total.research())));
+    header
+}
+
+/// write a table with aligned columns, followed by an empty line
+fn write_table(
+    f: &mut fmt::Formatter<'_>,
+    header: Vec<String>,
+    rows: &[Vec<String>],
+) -> fmt::Result {
+    writeln!(f, "{}", header.join(SEPARATOR))?;
+
+    let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+    let widths = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    for row in rows {
+        f.write_str(SEPARATOR)?;
+        for (column, cell) in row.iter().enumerate() {
+            if column + 1 == row.len() {
+                writeln!(f, "{cell}")?;
+            } else {
+                write!(f, "{cell:<width$}{SEPARATOR}", width = widths[column])?;
+            }
+        }
+    }
+
+    writeln!(f)
+}
+
+/// write a labelled reward, followed by an empty line
+fn write_line(f: &mut fmt::Formatter<'_>, label: &str, reward: Vec<String>) -> fmt::Result {
+    writeln!(f, "{label}{SEPARATOR}{}", reward.join(SEPARATOR))?;
+    writeln!(f)
+}
+
+/// the columns of a reward, leaving out research points, squadron research
+/// points and other currencies if there are none
+fn reward(reward: &Reward) -> Vec<String> {
+    let none = RewardBreakdown::default();
+    let mut columns = match (reward.sl != none, reward.rp != none) {
+        (false, false) if reward.squadron_research > 0 || !reward.other_currencies.is_empty() => {
+            Vec::new()
+        }
+        (_, false) => vec![amount(&reward.sl, "SL")],
+        (false, true) => vec![amount(&reward.rp, "RP")],
+        (true, true) => vec![amount(&reward.sl, "SL"), amount(&reward.rp, "RP")],
+    };
+    if reward.squadron_research > 0 {
+        columns.push(format!("{} SRP", reward.squadron_research));
+    }
+    for (currency, amount) in &reward.other_currencies {
+        // units like `WB` follow the amount, names like `Holiday Toys` lead
+        if currency.chars().all(|c| c.is_ascii_uppercase()) {
+            columns.push(format!("{amount} {currency}"));
+        } else {
+            columns.push(format!("{currency}: {amount}"));
+        }
+    }
+    columns
+}
+
+/// an amount with its bonuses, like `10 + (PA)10 = 20 RP`
+fn amount(breakdown: &RewardBreakdown, unit: &str) -> String {
+    let mut amount = String::new();
+    if !breakdown.bonuses.is_empty() {
+        amount.push_str(&breakdown.base.to_string());
+        for (kind, value) in &breakdown.bonuses {
+            amount.push_str(&format!(" + ({}){value}", kind.name()));
+        }
+        amount.push_str(" = ");
+    }
+    format!("{amount}{} {unit}", breakdown.total)
+}
+
+/// a percentage in basis points like `91%` or `97.5%`
+fn percent(basis_points: u16) -> String {
+    match basis_points % 100 {
+        0 => format!("{}%", basis_points / 100),
+        hundredths if hundredths % 10 == 0 => {
+            format!("{}.{}%", basis_points / 100, hundredths / 10)
+        }
+        hundredths => format!("{}.{hundredths:02}%", basis_points / 100),
+    }
+}
+
+/// a timestamp like `7:13` or `1:02:33`
+fn timestamp(seconds: u32) -> String {
+    match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{hours}:{:02}:{:02}", seconds / 60 % 60, seconds % 60),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use rstest::*;
+
+    use crate::*;
+
+    #[rstest]
+    #[case(0, "0:00")]
+    #[case(7 * 60 + 13, "7:13")]
+    #[case(3600 + 2 * 60 + 33, "1:02:33")]
+    fn render_timestamp(#[case] seconds: u32, #[case] expected: &str) {
+        assert_eq!(super::timestamp(seconds), expected);
+    }
+
+    #[rstest]
+    #[case(9100, "91%")]
+    #[case(9750, "97.5%")]
+    #[case(9705, "97.05%")]
+    fn render_percent(#[case] basis_points: u16, #[case] expected: &str) {
+        assert_eq!(super::percent(basis_points), expected);
+    }
+
+    #[test]
+    fn render_report_header() {
+        let report = from_path("./data/160409b0002a1af-player.report").unwrap();
+        let text = report.to_string();
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next(),
+            Some("Defeat in the [Domination] Seversk-13 mission!")
+        );
+        assert_eq!(lines.next(), Some("Player: Chronophylos"));
+        assert_eq!(lines.next(), Some(""));
+    }
+
+    /// Parse, render and parse again every report in `dir`
+    fn round_trip(dir: &str, options: Option<ParseOptions>) {
+        for entry in std::fs::read_dir(Path::new("./data").join(dir)).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                continue;
+            }
+            let (report, rendered) = match options {
+                Some(options) => {
+                    let input = std::fs::read_to_string(&path).unwrap();
+                    let (report, _) = from_str_with_options(&input, options).unwrap();
+                    let (rendered, _) =
+                        from_str_with_options(&report.to_string(), options).unwrap();
+                    (report, rendered)
+                }
+                None => {
+                    let report = from_path(&path).unwrap();
+                    (report.clone(), from_str(&report.to_string()).unwrap())
+                }
+            };
+            assert_eq!(rendered, report, "{}", path.display());
+        }
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("crlf")]
+    #[case("de")]
+    #[case("utf16")]
+    #[case("dated")]
+    #[case("premium")]
+    fn round_trip_reports(#[case] dir: &str) {
+        round_trip(dir, None);
+    }
+
+    #[test]
+    fn round_trip_lenient_reports() {
+        round_trip("lenient", Some(ParseOptions::lenient()));
+    }
+
+    #[rstest]
+    #[case("./data/1714b3e0001c7a2.report", "Severe damage to aircraft")]
+    #[case("./data/1714b3e0001c7a2.report", "Critical damage")]
+    #[case("./data/1712a4f0003b2c5.report", "Capture of the zone")]
+    #[case("./data/1703c87000d01c0-repairs.report", "Help with repairs")]
+    fn round_trip_table_names(#[case] path: &str, #[case] name: &str) {
+        let input = std::fs::read_to_string(path).unwrap();
+        let report = from_str(&input).unwrap();
+        assert!(report.event_tables.iter().any(|table| table.name == name));
+        let text = report.to_string();
+        assert!(
+            text.lines()
+                .any(|line| line.starts_with(&format!("{name}{}", super::SEPARATOR))),
+            "{text}"
+        );
+        assert_eq!(from_str(&text).unwrap(), report);
+    }
+
+    #[test]
+    fn round_trip_empty_event_table() {
+        let report = BattleReport::builder()
+            .with_session_id("1712a4f0003b2c5")
+            .with_result(BattleResult::Win)
+            .add_event(
+                Event::new(140, EventKind::CaptureOfZones, "Z-25")
+                    .with_enemy("41%")
+                    .with_reward(Reward::new(800, 60)),
+            )
+            .with_event_tables(vec![
+                EventTable {
+                    name: "Damage to the enemy".to_string(),
+                    kind: EventKind::Damage,
+                    count: 0,
+                    total: Reward::default(),
+                },
+                EventTable {
+                    name: "Capture of the zone".to_string(),
+                    kind: EventKind::CaptureOfZones,
+                    count: 1,
+                    total: Reward::new(800, 60),
+                },
+            ])
+            .add_award(Award {
+                time: 140,
+                name: "Without a miss".to_string(),
+                reward: Reward::new(200, 0),
+                golden_eagles: 0,
+            })
+            .add_vehicle(
+                Vehicle::new("Z-25")
+                    .with_activity(91)
+                    .with_time_played(300)
+                    .with_reward(Reward::new(650, 55)),
+            )
+            .with_damaged_vehicles(vec!["Z-25".to_string()])
+            .build()
+            .unwrap();
+        let text = report.to_string();
+        assert!(
+            text.contains("Damage to the enemy    0    0 SL\n\n"),
+            "{text}"
+        );
+        assert_eq!(from_str(&text).unwrap(), report);
+    }
+
+    #[test]
+    fn round_trip_award_golden_eagles() {
+        let input = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        let mut report = from_str(&input).unwrap();
+        report.awards[1].golden_eagles = 5;
+        let text = report.to_string();
+        assert_eq!(from_str(&text).unwrap(), report);
+    }
+
+    #[test]
+    fn round_trip_task_convertible_research() {
+        let input = std::fs::read_to_string("./data/1712a4f0003b2c5-tasks.report").unwrap();
+        let mut report = from_str(&input).unwrap();
+        report.completed_tasks[0].convertible_research = 150;
+        let text = report.to_string();
+        assert!(
+            text.contains("\"Destroy 5 ground vehicles\" 30000 SL, 150 CRP\n"),
+            "{text}"
+        );
+        assert_eq!(from_str(&text).unwrap(), report);
+    }
+
+    #[test]
+    fn round_trip_fractional_activity() {
+        let input = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        let report = from_str(&input.replace("Activity: 91%", "Activity: 90,5 %")).unwrap();
+        assert_eq!((report.activity, report.activity_basis_points), (91, 9050));
+        let text = report.to_string();
+        assert!(text.contains("Activity: 90.5%\n"), "{text}");
+        assert_eq!(from_str(&text).unwrap(), report);
+    }
+
+    #[test]
+    fn round_trip_wagers() {
+        let input = std::fs::read_to_string("./data/1703c87000d01c0-wagers.report").unwrap();
+        let report = from_str(&input).unwrap();
+        let text = report.to_string();
+        assert!(
+            text.contains("Wager \"Kill streak\" completed: 50000 SL    120 RP\n"),
+            "{text}"
+        );
+        assert_eq!(from_str(&text).unwrap(), report);
+    }
+
+    #[test]
+    fn round_trip_squadron_research() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let mut report = from_str(&input).unwrap();
+        report.squadron_research = Some(680);
+        let text = report.to_string();
+        assert!(text.contains("\nSquadron research: 680 points\n"), "{text}");
+        assert_eq!(from_str(&text).unwrap(), report);
+    }
+
+    #[test]
+    fn round_trip_squadron_research_points() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let input = input
+            .replace(
+                "M36 GMC()     455 SL    36 RP",
+                "M36 GMC()     455 SL    36 RP    50 SRP",
+            )
+            .replace("2118 RP", "2118 RP, 50 SRP");
+        let report = from_str(&input).unwrap();
+        let squadron_research = |report: &BattleReport| {
+            report
+                .events
+                .iter()
+                .map(|event| event.reward.squadron_research)
+                .sum::<u32>()
+        };
+        assert_eq!(squadron_research(&report), 50);
+        assert_eq!(report.balance.squadron_research, 50);
+
+        let text = report.to_string();
+        assert!(text.ends_with(", 2118 RP, 50 SRP"), "{text}");
+        assert_eq!(from_str(&text).unwrap(), report);
+    }
+
+    #[test]
+    fn round_trip_other_currencies() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let input = input.replace(
+            "5295 SL     115 RP",
+            "5295 SL     115 RP    15 WB    Holiday Toys: 3",
+        );
+        let report = from_str(&input).unwrap();
+        assert_eq!(
+            report.other_awards,
+            Reward::new(5295, 115)
+                .with_other_currency("WB", 15)
+                .with_other_currency("Holiday Toys", 3)
+        );
+
+        let text = report.to_string();
+        assert!(text.contains("15 WB    Holiday Toys: 3"), "{text}");
+        assert_eq!(from_str(&text).unwrap(), report);
+    }
+
+    #[test]
+    fn rendered_reports_validate() {
+        let input = std::fs::read_to_string("./data/1703c87000d01c0-repairs.report").unwrap();
+        let report = from_str(&input).unwrap();
+        let (_, issues) = from_str_validated(&report.to_string()).unwrap();
+        assert_eq!(issues, []);
+    }
+}
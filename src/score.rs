@@ -0,0 +1,180 @@
+//! Battle Scores
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{BattleReport, EventKind};
+
+/// Counts from a battle report that battle scores are computed from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScoreInputs {
+    /// Enemies destroyed by the player
+    pub kills: u32,
+    pub assists: u32,
+    /// Enemies damaged without being destroyed by the player
+    pub damaged: u32,
+    pub captures: u32,
+    /// Activity in percent
+    pub activity: u8,
+    /// Duration of the battle in seconds, see [`BattleReport::battle_duration`]
+    pub duration: u32,
+}
+
+impl BattleReport {
+    pub fn score_inputs(&self) -> ScoreInputs {
+        let count = |f: fn(&EventKind) -> bool| {
+            self.events.iter().filter(|event| f(&event.kind)).count() as u32
+        };
+
+        ScoreInputs {
+            kills: count(EventKind::is_destruction),
+            assists: count(|kind| matches!(kind, EventKind::Assistance)),
+            damaged: count(EventKind::is_damage),
+            captures: count(|kind| matches!(kind, EventKind::CaptureOfZones)),
+            activity: self.activity,
+            duration: self.battle_duration().unwrap_or_default(),
+        }
+    }
+}
+
+impl ScoreInputs {
+    /// Weighted sum of the counts of these inputs
+    pub fn score(&self, formula: &ScoreFormula) -> f64 {
+        let activity_minutes = f64::from(self.activity) / 100.0 * f64::from(self.duration) / 60.0;
+
+        f64::from(self.kills) * formula.kill
+            + f64::from(self.assists) * formula.assist
+            + f64::from(self.damaged) * formula.damage
+            + f64::from(self.captures) * formula.capture
+            + activity_minutes * formula.activity_per_minute
+    }
+}
+
+/// Weights for computing a battle score from a [`ScoreInputs`]
+///
+/// Formulas serialize so that stored scores can be recomputed later.
+///
+/// ```
+/// use wt_battle_report::ScoreFormula;
+///
+/// let formula = ScoreFormula::new().with_kill(1.0).with_capture(0.5);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ScoreFormula {
+    pub kill: f64,
+    pub assist: f64,
+    pub damage: f64,
+    pub capture: f64,
+    /// Weight of a minute spent at full activity
+    pub activity_per_minute: f64,
+}
+
+impl ScoreFormula {
+    /// A formula with every weight set to zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Weights common in community tools
+    pub fn standard() -> Self {
+        Self::new()
+            .with_kill(1.0)
+            .with_assist(0.4)
+            .with_capture(0.6)
+            .with_activity_per_minute(0.05)
+    }
+
+    /// Count nothing but kills
+    pub fn kills_only() -> Self {
+        Self::new().with_kill(1.0)
+    }
+
+    pub fn with_kill(self, kill: f64) -> Self {
+        Self { kill, ..self }
+    }
+
+    pub fn with_assist(self, assist: f64) -> Self {
+        Self { assist, ..self }
+    }
+
+    pub fn with_damage(self, damage: f64) -> Self {
+        Self { damage, ..self }
+    }
+
+    pub fn with_capture(self, capture: f64) -> Self {
+        Self { capture, ..self }
+    }
+
+    pub fn with_activity_per_minute(self, activity_per_minute: f64) -> Self {
+        Self {
+            activity_per_minute,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn score_inputs(path: &str) -> ScoreInputs {
+        let input = std::fs::read_to_string(path).unwrap();
+        from_str(&input).unwrap().score_inputs()
+    }
+
+    #[test]
+    fn score_inputs_of_real_report() {
+        assert_eq!(
+            score_inputs("./data/1603c1c00028a36.report"),
+            ScoreInputs {
+                kills: 7,
+                assists: 1,
+                damaged: 8,
+                captures: 0,
+                activity: 95,
+                duration: 13 * 60 + 55,
+            }
+        );
+    }
+
+    #[test]
+    fn standard_score() {
+        let score = score_inputs("./data/1603c1c00028a36.report").score(&ScoreFormula::standard());
+        // 7 kills + 1 assist * 0.4 + 95% of 835 s * 0.05 per minute
+        let expected = 7.0 + 0.4 + 0.95 * 835.0 / 60.0 * 0.05;
+        assert!((score - expected).abs() < 1e-9, "{score}");
+    }
+
+    #[test]
+    fn kills_only_score() {
+        let score =
+            score_inputs("./data/1712a4f0003b2c5.report").score(&ScoreFormula::kills_only());
+        assert_eq!(score, 2.0);
+    }
+
+    #[test]
+    fn capture_score() {
+        let formula = ScoreFormula::new().with_capture(0.6).with_damage(0.1);
+        let score = score_inputs("./data/1712a4f0003b2c5.report").score(&formula);
+        // 1 capture * 0.6 + 3 damage * 0.1
+        assert!((score - 0.9).abs() < 1e-9, "{score}");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn formula_json_round_trip() {
+        let formula = ScoreFormula::standard();
+        let json = serde_json::to_string(&formula).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ScoreFormula>(&json).unwrap(),
+            formula
+        );
+        assert_eq!(
+            serde_json::from_str::<ScoreFormula>(r#"{"kill": 2.0}"#).unwrap(),
+            ScoreFormula::new().with_kill(2.0)
+        );
+    }
+}
@@ -0,0 +1,333 @@
+//! Statistics across multiple battle reports
+
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{battle_report::normalize_vehicle_name, BattleReport, BattleResult};
+
+/// Statistics accumulated over a session of battles
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SessionStats {
+    pub battles: usize,
+    /// Encounters with enemy vehicles keyed by their normalized name
+    pub enemy_encounters: BTreeMap<String, EnemyStats>,
+    /// Enemies destroyed by each of the player's vehicles, both keyed by their
+    /// normalized name
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kills: BTreeMap<String, BTreeMap<String, u32>>,
+}
+
+/// How often the player interacted with an enemy vehicle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EnemyStats {
+    pub destroyed: u32,
+    pub damaged: u32,
+    pub scouted: u32,
+}
+
+/// How often one of the player's vehicles destroyed an enemy vehicle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Matchup<'a> {
+    pub vehicle: &'a str,
+    pub enemy: &'a str,
+    pub kills: u32,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_reports<'a, I>(reports: I) -> Self
+    where
+        I: IntoIterator<Item = &'a BattleReport>,
+    {
+        let mut stats = Self::new();
+        for report in reports {
+            stats.add(report);
+        }
+        stats
+    }
+
+    /// Add a single battle to the statistics
+    pub fn add(&mut self, report: &BattleReport) {
+        self.battles += 1;
+
+        for event in &report.events {
+            let Some(enemy) = &event.enemy else {
+                continue;
+            };
+
+            let kind = &event.kind;
+            if !(kind.is_destruction() || kind.is_damage() || kind.is_scouting()) {
+                continue;
+            }
+
+            let enemy = normalize_vehicle_name(enemy);
+            let stats = self.enemy_encounters.entry(enemy.to_string()).or_default();
+            if kind.is_destruction() {
+                stats.destroyed += 1;
+                *self
+                    .kills
+                    .entry(normalize_vehicle_name(&event.vehicle).to_string())
+                    .or_default()
+                    .entry(enemy.to_string())
+                    .or_default() += 1;
+            } else if kind.is_damage() {
+                stats.damaged += 1;
+            } else {
+                stats.scouted += 1;
+            }
+        }
+    }
+
+    /// The `n` enemies destroyed most often
+    ///
+    /// Ties are broken by damage, then scouting, then name.
+    pub fn top_enemies(&self, n: usize) -> Vec<(&str, &EnemyStats)> {
+        let mut enemies = self
+            .enemy_encounters
+            .iter()
+            .map(|(name, stats)| (name.as_str(), stats))
+            .collect::<Vec<_>>();
+        enemies.sort_by(|(a_name, a), (b_name, b)| {
+            (b.destroyed, b.damaged, b.scouted)
+                .cmp(&(a.destroyed, a.damaged, a.scouted))
+                .then_with(|| a_name.cmp(b_name))
+        });
+        enemies.truncate(n);
+        enemies
+    }
+
+    /// Kills in which `name` is either the player's vehicle or the enemy
+    ///
+    /// Names are compared after [`normalize_vehicle_name`]. The matchups are
+    /// sorted by kills, then by vehicle and enemy name.
+    pub fn matchups(&self, name: &str) -> Vec<Matchup<'_>> {
+        let name = normalize_vehicle_name(name);
+        let mut matchups = self
+            .kills
+            .iter()
+            .flat_map(|(vehicle, enemies)| {
+                enemies.iter().map(|(enemy, kills)| Matchup {
+                    vehicle,
+                    enemy,
+                    kills: *kills,
+                })
+            })
+            .filter(|matchup| matchup.vehicle == name || matchup.enemy == name)
+            .collect::<Vec<_>>();
+        matchups.sort_by(|a, b| {
+            b.kills
+                .cmp(&a.kills)
+                .then_with(|| (a.vehicle, a.enemy).cmp(&(b.vehicle, b.enemy)))
+        });
+        matchups
+    }
+}
+
+/// Results and rewards accumulated over many battles
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AggregateStats {
+    pub total_battles: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+    /// Silver lions earned before costs
+    pub total_sl: u64,
+    /// Research points on the total lines
+    pub total_rp: u64,
+    /// Battles each vehicle was played in
+    pub battles_per_vehicle: HashMap<String, usize>,
+    /// Enemies each vehicle destroyed
+    pub kills_per_vehicle: HashMap<String, usize>,
+}
+
+impl AggregateStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_reports<'a, I>(reports: I) -> Self
+    where
+        I: IntoIterator<Item = &'a BattleReport>,
+    {
+        let mut stats = Self::new();
+        for report in reports {
+            stats.add(report);
+        }
+        stats
+    }
+
+    /// Add a single battle to the statistics
+    pub fn add(&mut self, report: &BattleReport) {
+        self.total_battles += 1;
+        match report.result {
+            BattleResult::Win => self.wins += 1,
+            BattleResult::Loss => self.losses += 1,
+            BattleResult::Draw => self.draws += 1,
+            BattleResult::Unknown => {}
+        }
+        self.total_sl += u64::from(report.earned_rewards.silverlions());
+        self.total_rp += u64::from(report.balance.research());
+
+        for vehicle in &report.vehicles {
+            *self
+                .battles_per_vehicle
+                .entry(vehicle.name.clone())
+                .or_default() += 1;
+        }
+        for event in &report.events {
+            if event.kind.is_destruction() {
+                *self
+                    .kills_per_vehicle
+                    .entry(event.vehicle.clone())
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    /// Share of battles won, zero if there are none
+    pub fn win_rate(&self) -> f64 {
+        if self.total_battles == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.total_battles as f64
+    }
+
+    /// Silver lions earned per battle, zero if there are none
+    pub fn avg_sl_per_battle(&self) -> f64 {
+        if self.total_battles == 0 {
+            return 0.0;
+        }
+        self.total_sl as f64 / self.total_battles as f64
+    }
+
+    /// The vehicle with the most kills
+    ///
+    /// Ties are broken by name.
+    pub fn top_vehicle_by_kills(&self) -> Option<&str> {
+        self.kills_per_vehicle
+            .iter()
+            .max_by(|(a_name, a), (b_name, b)| a.cmp(b).then_with(|| b_name.cmp(a_name)))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    use super::*;
+
+    fn corpus() -> Vec<BattleReport> {
+        ["1603c1c00028a36", "160409b0002a1af", "161878b0007a4c8"]
+            .into_iter()
+            .map(|session| {
+                let input = std::fs::read_to_string(format!("./data/{session}.report")).unwrap();
+                from_str(&input).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn enemy_encounters_over_corpus() {
+        let stats = SessionStats::from_reports(&corpus());
+        assert_eq!(stats.battles, 3);
+        assert_eq!(
+            stats.enemy_encounters["M36 GMC"],
+            EnemyStats {
+                destroyed: 0,
+                damaged: 1,
+                scouted: 2,
+            }
+        );
+        assert_eq!(
+            stats.enemy_encounters["M3A1 Stuart"],
+            EnemyStats {
+                destroyed: 2,
+                damaged: 1,
+                scouted: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn enemy_names_are_normalized() {
+        let stats = SessionStats::from_reports(&corpus());
+        assert!(stats.enemy_encounters.contains_key("ISU-122"));
+        assert!(!stats.enemy_encounters.contains_key("ISU-122()"));
+    }
+
+    #[test]
+    fn aggregate_stats_over_corpus() {
+        let stats = AggregateStats::from_reports(&corpus());
+        assert_eq!(stats.total_battles, 3);
+        assert_eq!((stats.wins, stats.losses, stats.draws), (1, 2, 0));
+        assert_eq!(stats.total_sl, 72213);
+        assert_eq!(stats.total_rp, 7007);
+        assert_eq!(stats.battles_per_vehicle["Concept 3"], 2);
+        assert_eq!(stats.kills_per_vehicle["Sherman Firefly"], 3);
+        assert!((stats.win_rate() - 1.0 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(stats.avg_sl_per_battle(), 24071.0);
+        // Concept 3 and Wyvern S4 both have four kills
+        assert_eq!(stats.top_vehicle_by_kills(), Some("Concept 3"));
+    }
+
+    #[test]
+    fn aggregate_stats_without_battles() {
+        let stats = AggregateStats::new();
+        assert_eq!(stats.win_rate(), 0.0);
+        assert_eq!(stats.avg_sl_per_battle(), 0.0);
+        assert_eq!(stats.top_vehicle_by_kills(), None);
+    }
+
+    #[test]
+    fn matchups_over_corpus() {
+        let stats = SessionStats::from_reports(&corpus());
+        let matchup = |vehicle, enemy, kills| Matchup {
+            vehicle,
+            enemy,
+            kills,
+        };
+        assert_eq!(
+            stats.matchups("Sherman Firefly"),
+            [
+                matchup("Sherman Firefly", "Chi-Nu II", 1),
+                matchup("Sherman Firefly", "KV-85", 1),
+                matchup("Sherman Firefly", "T-34 (1942)", 1),
+            ]
+        );
+        // the Wyvern S4 only assisted in destroying the other KV-85
+        assert_eq!(
+            stats.matchups("KV-85"),
+            [matchup("Sherman Firefly", "KV-85", 1)]
+        );
+        assert_eq!(
+            stats.matchups("ISU-122()"),
+            [matchup("Concept 3", "ISU-122", 1)]
+        );
+        assert_eq!(stats.matchups("Maus"), []);
+    }
+
+    #[test]
+    fn top_enemies_are_sorted_by_kills() {
+        let stats = SessionStats::from_reports(&corpus());
+        let top = stats.top_enemies(2);
+        assert_eq!(top.len(), 2);
+        assert!(top[0].1.destroyed >= top[1].1.destroyed);
+    }
+
+    #[test]
+    fn helped_allies_are_not_enemies() {
+        let input = std::fs::read_to_string("./data/1703c87000d01c0-repairs.report").unwrap();
+        let stats = SessionStats::from_reports(&[from_str(&input).unwrap()]);
+        assert!(!stats.enemy_encounters.contains_key("Strv m/42 EH"));
+        assert!(!stats.enemy_encounters.contains_key("Ikv 72"));
+    }
+}
@@ -0,0 +1,1017 @@
+//! SQLite Storage
+
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::{
+    Award, BattleReport, BattleResult, BonusKind, CompletedTask, CrewExperience, Event, EventKind,
+    EventTable, ModificationResearch, Reward, UsedItem, Vehicle, VehicleResearch, WagerProgress,
+};
+
+// every reward is stored as silverlions, research and squadron research
+// columns, see `reward`
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS reports (
+    session_id TEXT PRIMARY KEY,
+    -- 1 for reports without a session line, stored under a derived ID
+    derived_session_id INTEGER NOT NULL,
+    session_time TEXT,
+    result TEXT NOT NULL,
+    mission_name TEXT NOT NULL,
+    player_name TEXT,
+    squadron TEXT,
+    awards_header_count INTEGER NOT NULL,
+    awards_header_silverlions INTEGER NOT NULL,
+    awards_header_research INTEGER NOT NULL,
+    awards_header_squadron_research INTEGER NOT NULL,
+    reward_for_winning_silverlions INTEGER,
+    reward_for_winning_research INTEGER,
+    reward_for_winning_squadron_research INTEGER,
+    first_victory_bonus_silverlions INTEGER,
+    first_victory_bonus_research INTEGER,
+    first_victory_bonus_squadron_research INTEGER,
+    other_awards_silverlions INTEGER NOT NULL,
+    other_awards_research INTEGER NOT NULL,
+    other_awards_squadron_research INTEGER NOT NULL,
+    activity INTEGER NOT NULL,
+    activity_basis_points INTEGER NOT NULL,
+    damaged_vehicles TEXT NOT NULL,
+    automatic_repair INTEGER NOT NULL,
+    automatic_purchases INTEGER NOT NULL,
+    squadron_research INTEGER,
+    earned_silverlions INTEGER NOT NULL,
+    earned_research INTEGER NOT NULL,
+    earned_squadron_research INTEGER NOT NULL,
+    converted_research INTEGER,
+    balance_silverlions INTEGER NOT NULL,
+    balance_research INTEGER NOT NULL,
+    balance_squadron_research INTEGER NOT NULL
+);
+-- `assisted` is 1 for events marked with `×` in their table
+CREATE TABLE IF NOT EXISTS events (
+    session_id TEXT NOT NULL REFERENCES reports(session_id),
+    position INTEGER NOT NULL,
+    time INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    vehicle TEXT NOT NULL,
+    enemy TEXT,
+    ally TEXT,
+    assisted INTEGER NOT NULL,
+    silverlions INTEGER NOT NULL,
+    research INTEGER NOT NULL,
+    squadron_research INTEGER NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+CREATE TABLE IF NOT EXISTS event_tables (
+    session_id TEXT NOT NULL REFERENCES reports(session_id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    count INTEGER NOT NULL,
+    silverlions INTEGER NOT NULL,
+    research INTEGER NOT NULL,
+    squadron_research INTEGER NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+CREATE TABLE IF NOT EXISTS vehicles (
+    session_id TEXT NOT NULL REFERENCES reports(session_id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    activity INTEGER NOT NULL,
+    time_played INTEGER NOT NULL,
+    silverlions INTEGER NOT NULL,
+    research INTEGER NOT NULL,
+    squadron_research INTEGER NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+CREATE TABLE IF NOT EXISTS awards (
+    session_id TEXT NOT NULL REFERENCES reports(session_id),
+    position INTEGER NOT NULL,
+    time INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    silverlions INTEGER NOT NULL,
+    research INTEGER NOT NULL,
+    squadron_research INTEGER NOT NULL,
+    golden_eagles INTEGER NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+CREATE TABLE IF NOT EXISTS completed_tasks (
+    session_id TEXT NOT NULL REFERENCES reports(session_id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    silverlions INTEGER NOT NULL,
+    research INTEGER NOT NULL,
+    squadron_research INTEGER NOT NULL,
+    convertible_research INTEGER NOT NULL,
+    golden_eagles INTEGER NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+-- the final payout of a wager has no stage
+CREATE TABLE IF NOT EXISTS wagers (
+    session_id TEXT NOT NULL REFERENCES reports(session_id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    stage INTEGER,
+    stages_total INTEGER,
+    silverlions INTEGER NOT NULL,
+    research INTEGER NOT NULL,
+    squadron_research INTEGER NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+-- vehicle research has no vehicle and no convertible research, modification
+-- research has no progress
+CREATE TABLE IF NOT EXISTS research (
+    session_id TEXT NOT NULL REFERENCES reports(session_id),
+    position INTEGER NOT NULL,
+    vehicle TEXT,
+    name TEXT NOT NULL,
+    research INTEGER NOT NULL,
+    convertible_research INTEGER,
+    progress_percent INTEGER,
+    PRIMARY KEY (session_id, position)
+);
+CREATE TABLE IF NOT EXISTS crew_experience (
+    session_id TEXT NOT NULL REFERENCES reports(session_id),
+    position INTEGER NOT NULL,
+    vehicle TEXT NOT NULL,
+    experience INTEGER NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+CREATE TABLE IF NOT EXISTS used_items (
+    session_id TEXT NOT NULL REFERENCES reports(session_id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    count INTEGER NOT NULL,
+    PRIMARY KEY (session_id, position)
+);
+-- bonuses of the reward in row `position` of table `source`, the base is the
+-- stored total minus the bonuses
+CREATE TABLE IF NOT EXISTS bonuses (
+    session_id TEXT NOT NULL REFERENCES reports(session_id),
+    source TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    currency TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    value INTEGER NOT NULL
+);
+-- other currencies of the reward in row `position` of table `source`, in the
+-- order of the report
+CREATE TABLE IF NOT EXISTS reward_other_currencies (
+    session_id TEXT NOT NULL REFERENCES reports(session_id),
+    source TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    currency TEXT NOT NULL,
+    amount INTEGER NOT NULL
+);
+";
+
+/// Battle reports stored in an SQLite database
+pub struct BattleStore {
+    connection: Connection,
+}
+
+impl BattleStore {
+    /// Open or create the database at `path`
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Open a database that only lives in memory
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(connection: Connection) -> rusqlite::Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Store a report unless a report with the same session ID is stored
+    ///
+    /// Reports without a session ID are stored under their
+    /// [effective session ID](BattleReport::effective_session_id).
+    pub fn insert(&mut self, report: &BattleReport) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        let session_id = report.effective_session_id();
+        let session_id = session_id.as_ref();
+        #[cfg(feature = "timestamps")]
+        let session_time = report
+            .session_time
+            .as_ref()
+            .map(crate::datetime::format_session_time);
+        #[cfg(not(feature = "timestamps"))]
+        let session_time = None::<String>;
+        let reward_for_winning = report.reward_for_winning.as_ref();
+        let first_victory_bonus = report.first_victory_bonus.as_ref();
+
+        let inserted = transaction.execute(
+            "INSERT OR IGNORE INTO reports VALUES
+                (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+                ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33)",
+            params![
+                session_id,
+                report.session_id.is_empty(),
+                session_time,
+                result_name(report.result),
+                report.mission_name,
+                report.player_name,
+                report.squadron,
+                report.awards_header_count,
+                report.awards_header_total.silverlions(),
+                report.awards_header_total.research(),
+                report.awards_header_total.squadron_research,
+                reward_for_winning.map(Reward::silverlions),
+                reward_for_winning.map(Reward::research),
+                reward_for_winning.map(|reward| reward.squadron_research),
+                first_victory_bonus.map(Reward::silverlions),
+                first_victory_bonus.map(Reward::research),
+                first_victory_bonus.map(|reward| reward.squadron_research),
+                report.other_awards.silverlions(),
+                report.other_awards.research(),
+                report.other_awards.squadron_research,
+                report.activity,
+                report.activity_basis_points,
+                report.damaged_vehicles.join("\n"),
+                report.automatic_repair,
+                report.automatic_purchases,
+                report.squadron_research,
+                report.earned_rewards.silverlions(),
+                report.earned_rewards.research(),
+                report.earned_rewards.squadron_research,
+                report.converted_research,
+                report.balance.silverlions(),
+                report.balance.research(),
+                report.balance.squadron_research,
+            ],
+        )?;
+        if inserted == 0 {
+            return Ok(());
+        }
+
+        for (position, event) in report.events.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO events VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    session_id,
+                    position,
+                    event.time,
+                    event.kind.name(),
+                    event.vehicle,
+                    event.enemy,
+                    event.ally,
+                    event.assisted,
+                    event.reward.silverlions(),
+                    event.reward.research(),
+                    event.reward.squadron_research,
+                ],
+            )?;
+        }
+        for (position, table) in report.event_tables.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO event_tables VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    session_id,
+                    position,
+                    table.name,
+                    table.kind.name(),
+                    table.count,
+                    table.total.silverlions(),
+                    table.total.research(),
+                    table.total.squadron_research,
+                ],
+            )?;
+        }
+        for (position, vehicle) in report.vehicles.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO vehicles VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    session_id,
+                    position,
+                    vehicle.name,
+                    vehicle.activity,
+                    vehicle.time_played,
+                    vehicle.reward.silverlions(),
+                    vehicle.reward.research(),
+                    vehicle.reward.squadron_research,
+                ],
+            )?;
+        }
+        for (position, award) in report.awards.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO awards VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    session_id,
+                    position,
+                    award.time,
+                    award.name,
+                    award.reward.silverlions(),
+                    award.reward.research(),
+                    award.reward.squadron_research,
+                    award.golden_eagles,
+                ],
+            )?;
+        }
+        for (position, task) in report.completed_tasks.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO completed_tasks VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    session_id,
+                    position,
+                    task.name,
+                    task.reward.silverlions(),
+                    task.reward.research(),
+                    task.reward.squadron_research,
+                    task.convertible_research,
+                    task.golden_eagles,
+                ],
+            )?;
+        }
+        for (position, wager) in report.wagers.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO wagers VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    session_id,
+                    position,
+                    wager.name,
+                    wager.stage,
+                    wager.stages_total,
+                    wager.reward.silverlions(),
+                    wager.reward.research(),
+                    wager.reward.squadron_research,
+                ],
+            )?;
+        }
+        let research = report
+            .vehicle_research
+            .iter()
+            .map(|research| {
+                (
+                    None,
+                    &research.name,
+                    research.research,
+                    None,
+                    research.progress_percent,
+                )
+            })
+            .chain(report.modification_research.iter().map(|research| {
+                (
+                    Some(&research.vehicle),
+                    &research.name,
+                    research.research,
+                    Some(research.convertible_research),
+                    None,
+                )
+            }));
+        for (position, (vehicle, name, research, convertible_research, progress_percent)) in
+            research.enumerate()
+        {
+            transaction.execute(
+                "INSERT INTO research VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    session_id,
+                    position,
+                    vehicle,
+                    name,
+                    research,
+                    convertible_research,
+                    progress_percent
+                ],
+            )?;
+        }
+
+        for (position, crew) in report.crew_experience.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO crew_experience VALUES (?1, ?2, ?3, ?4)",
+                params![session_id, position, crew.vehicle, crew.experience],
+            )?;
+        }
+
+        for (position, item) in report.used_items.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO used_items VALUES (?1, ?2, ?3, ?4)",
+                params![session_id, position, item.name, item.count],
+            )?;
+        }
+
+        let rewards = report
+            .events
+            .iter()
+            .enumerate()
+            .map(|(position, event)| ("events", position, &event.reward))
+            .chain(
+                report
+                    .vehicles
+                    .iter()
+                    .enumerate()
+                    .map(|(position, vehicle)| ("vehicles", position, &vehicle.reward)),
+            )
+            .chain(
+                report
+                    .awards
+                    .iter()
+                    .enumerate()
+                    .map(|(position, award)| ("awards", position, &award.reward)),
+            )
+            .chain(
+                report
+                    .reward_for_winning
+                    .iter()
+                    .map(|reward| ("reward_for_winning", 0, reward)),
+            )
+            .chain(
+                report
+                    .first_victory_bonus
+                    .iter()
+                    .map(|reward| ("first_victory_bonus", 0, reward)),
+            )
+            .chain(
+                report
+                    .event_tables
+                    .iter()
+                    .enumerate()
+                    .map(|(position, table)| ("event_tables", position, &table.total)),
+            )
+            .chain(
+                report
+                    .completed_tasks
+                    .iter()
+                    .enumerate()
+                    .map(|(position, task)| ("completed_tasks", position, &task.reward)),
+            )
+            .chain(
+                report
+                    .wagers
+                    .iter()
+                    .enumerate()
+                    .map(|(position, wager)| ("wagers", position, &wager.reward)),
+            )
+            .chain([
+                ("awards_header_total", 0, &report.awards_header_total),
+                ("other_awards", 0, &report.other_awards),
+                ("earned_rewards", 0, &report.earned_rewards),
+                ("balance", 0, &report.balance),
+            ]);
+        for (source, position, reward) in rewards {
+            for (currency, amount) in &reward.other_currencies {
+                transaction.execute(
+                    "INSERT INTO reward_other_currencies VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![session_id, source, position, currency, amount],
+                )?;
+            }
+            for (currency, breakdown) in [("sl", &reward.sl), ("rp", &reward.rp)] {
+                for (kind, value) in &breakdown.bonuses {
+                    transaction.execute(
+                        "INSERT INTO bonuses VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        params![session_id, source, position, currency, kind.name(), value],
+                    )?;
+                }
+            }
+        }
+
+        transaction.commit()
+    }
+
+    /// Load the report of a session
+    pub fn get(&self, session_id: &str) -> rusqlite::Result<Option<BattleReport>> {
+        self.connection
+            .query_row(
+                "SELECT * FROM reports WHERE session_id = ?1",
+                [session_id],
+                report_from_row,
+            )
+            .optional()?
+            .map(|(session_id, report)| self.load_details(&session_id, report))
+            .transpose()
+    }
+
+    /// Load every report with the given result, ordered by session ID
+    pub fn query_by_result(&self, result: BattleResult) -> rusqlite::Result<Vec<BattleReport>> {
+        self.query(
+            "SELECT * FROM reports WHERE result = ?1 ORDER BY session_id",
+            [result_name(result)],
+        )
+    }
+
+    /// Load every report, ordered by session ID
+    pub fn all(&self) -> rusqlite::Result<Vec<BattleReport>> {
+        self.query("SELECT * FROM reports ORDER BY session_id", [])
+    }
+
+    fn query<P: rusqlite::Params>(
+        &self,
+        sql: &str,
+        params: P,
+    ) -> rusqlite::Result<Vec<BattleReport>> {
+        let mut statement = self.connection.prepare(sql)?;
+        let reports = statement
+            .query_map(params, report_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        reports
+            .into_iter()
+            .map(|(session_id, report)| self.load_details(&session_id, report))
+            .collect()
+    }
+
+    /// Fill in the rows of the other tables for the report stored under
+    /// `session_id`
+    fn load_details(
+        &self,
+        session_id: &str,
+        mut report: BattleReport,
+    ) -> rusqlite::Result<BattleReport> {
+        let session_id = [session_id];
+
+        report.events = self
+            .connection
+            .prepare(
+                "SELECT time, kind, vehicle, enemy, ally, assisted,
+                    silverlions, research, squadron_research
+                FROM events WHERE session_id = ?1 ORDER BY position",
+            )?
+            .query_map(session_id, |row| {
+                Ok(Event {
+                    time: row.get(0)?,
+                    kind: EventKind::from_name(&row.get::<_, String>(1)?),
+                    vehicle: row.get(2)?,
+                    enemy: row.get(3)?,
+                    ally: row.get(4)?,
+                    assisted: row.get(5)?,
+                    reward: reward(row, 6)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        report.event_tables = self
+            .connection
+            .prepare(
+                "SELECT name, kind, count, silverlions, research, squadron_research
+                FROM event_tables WHERE session_id = ?1 ORDER BY position",
+            )?
+            .query_map(session_id, |row| {
+                Ok(EventTable {
+                    name: row.get(0)?,
+                    kind: EventKind::from_name(&row.get::<_, String>(1)?),
+                    count: row.get(2)?,
+                    total: reward(row, 3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        report.vehicles = self
+            .connection
+            .prepare(
+                "SELECT name, activity, time_played, silverlions, research, squadron_research
+                FROM vehicles WHERE session_id = ?1 ORDER BY position",
+            )?
+            .query_map(session_id, |row| {
+                Ok(Vehicle {
+                    name: row.get(0)?,
+                    activity: row.get(1)?,
+                    time_played: row.get(2)?,
+                    reward: reward(row, 3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        report.awards = self
+            .connection
+            .prepare(
+                "SELECT time, name, silverlions, research, squadron_research, golden_eagles
+                FROM awards WHERE session_id = ?1 ORDER BY position",
+            )?
+            .query_map(session_id, |row| {
+                Ok(Award {
+                    time: row.get(0)?,
+                    name: row.get(1)?,
+                    reward: reward(row, 2)?,
+                    golden_eagles: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        report.completed_tasks = self
+            .connection
+            .prepare(
+                "SELECT name, silverlions, research, squadron_research, convertible_research,
+                    golden_eagles
+                FROM completed_tasks WHERE session_id = ?1 ORDER BY position",
+            )?
+            .query_map(session_id, |row| {
+                Ok(CompletedTask {
+                    name: row.get(0)?,
+                    reward: reward(row, 1)?,
+                    convertible_research: row.get(4)?,
+                    golden_eagles: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        report.wagers = self
+            .connection
+            .prepare(
+                "SELECT name, stage, stages_total, silverlions, research, squadron_research
+                FROM wagers WHERE session_id = ?1 ORDER BY position",
+            )?
+            .query_map(session_id, |row| {
+                Ok(WagerProgress {
+                    name: row.get(0)?,
+                    stage: row.get(1)?,
+                    stages_total: row.get(2)?,
+                    reward: reward(row, 3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        let mut statement = self.connection.prepare(
+            "SELECT vehicle, name, research, convertible_research, progress_percent
+            FROM research WHERE session_id = ?1 ORDER BY position",
+        )?;
+        let mut rows = statement.query(session_id)?;
+        while let Some(row) = rows.next()? {
+            match row.get::<_, Option<String>>(0)? {
+                Some(vehicle) => report.modification_research.push(ModificationResearch {
+                    vehicle,
+                    name: row.get(1)?,
+                    research: row.get(2)?,
+                    convertible_research: row.get(3)?,
+                }),
+                None => report.vehicle_research.push(VehicleResearch {
+                    name: row.get(1)?,
+                    research: row.get(2)?,
+                    progress_percent: row.get(4)?,
+                }),
+            }
+        }
+
+        report.crew_experience = self
+            .connection
+            .prepare(
+                "SELECT vehicle, experience
+                FROM crew_experience WHERE session_id = ?1 ORDER BY position",
+            )?
+            .query_map(session_id, |row| {
+                Ok(CrewExperience {
+                    vehicle: row.get(0)?,
+                    experience: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        report.used_items = self
+            .connection
+            .prepare(
+                "SELECT name, count
+                FROM used_items WHERE session_id = ?1 ORDER BY position",
+            )?
+            .query_map(session_id, |row| {
+                Ok(UsedItem {
+                    name: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let mut statement = self.connection.prepare(
+            "SELECT source, position, currency, kind, value
+            FROM bonuses WHERE session_id = ?1 ORDER BY rowid",
+        )?;
+        let mut rows = statement.query(session_id)?;
+        while let Some(row) = rows.next()? {
+            let source = row.get::<_, String>(0)?;
+            let Some(reward) = reward_of(&mut report, &source, row.get(1)?) else {
+                continue;
+            };
+            let breakdown = match row.get::<_, String>(2)?.as_str() {
+                "sl" => &mut reward.sl,
+                _ => &mut reward.rp,
+            };
+            let value = row.get::<_, u32>(4)?;
+            breakdown.base = breakdown.base.saturating_sub(value);
+            breakdown
+                .bonuses
+                .push((BonusKind::from_name(&row.get::<_, String>(3)?), value));
+        }
+
+        let mut statement = self.connection.prepare(
+            "SELECT source, position, currency, amount
+            FROM reward_other_currencies WHERE session_id = ?1 ORDER BY rowid",
+        )?;
+        let mut rows = statement.query(session_id)?;
+        while let Some(row) = rows.next()? {
+            let source = row.get::<_, String>(0)?;
+            if let Some(reward) = reward_of(&mut report, &source, row.get(1)?) {
+                reward.other_currencies.push((row.get(2)?, row.get(3)?));
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// the reward in row `position` of table `source`, see the `bonuses` table
+fn reward_of<'a>(
+    report: &'a mut BattleReport,
+    source: &str,
+    position: usize,
+) -> Option<&'a mut Reward> {
+    match source {
+        "events" => report
+            .events
+            .get_mut(position)
+            .map(|event| &mut event.reward),
+        "vehicles" => report
+            .vehicles
+            .get_mut(position)
+            .map(|vehicle| &mut vehicle.reward),
+        "awards" => report
+            .awards
+            .get_mut(position)
+            .map(|award| &mut award.reward),
+        "event_tables" => report
+            .event_tables
+            .get_mut(position)
+            .map(|table| &mut table.total),
+        "completed_tasks" => report
+            .completed_tasks
+            .get_mut(position)
+            .map(|task| &mut task.reward),
+        "wagers" => report
+            .wagers
+            .get_mut(position)
+            .map(|wager| &mut wager.reward),
+        "reward_for_winning" => report.reward_for_winning.as_mut(),
+        "first_victory_bonus" => report.first_victory_bonus.as_mut(),
+        "awards_header_total" => Some(&mut report.awards_header_total),
+        "other_awards" => Some(&mut report.other_awards),
+        "earned_rewards" => Some(&mut report.earned_rewards),
+        "balance" => Some(&mut report.balance),
+        _ => None,
+    }
+}
+
+fn result_name(result: BattleResult) -> &'static str {
+    match result {
+        BattleResult::Win => "win",
+        BattleResult::Loss => "loss",
+        BattleResult::Draw => "draw",
+        BattleResult::Unknown => "unknown",
+    }
+}
+
+/// Read a reward from its silverlions, research and squadron research
+/// columns, without bonuses, see [`BattleStore::load_details`]
+fn reward(row: &Row<'_>, index: usize) -> rusqlite::Result<Reward> {
+    Ok(Reward::new(row.get(index)?, row.get(index + 1)?)
+        .with_squadron_research(row.get(index + 2)?))
+}
+
+/// Read a reward whose columns are `NULL` if the report has none
+fn optional_reward(row: &Row<'_>, index: usize) -> rusqlite::Result<Option<Reward>> {
+    match row.get::<_, Option<u32>>(index)? {
+        Some(_) => reward(row, index).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Read a row of the reports table, leaving the other tables empty
+///
+/// Returns the session ID the report is stored under along with the report,
+/// which has no session ID if it was derived.
+fn report_from_row(row: &Row<'_>) -> rusqlite::Result<(String, BattleReport)> {
+    let stored_session_id = row.get::<_, String>(0)?;
+    let session_id = match row.get(1)? {
+        true => String::new(),
+        false => stored_session_id.clone(),
+    };
+    #[cfg(feature = "timestamps")]
+    let session_time = row
+        .get::<_, Option<String>>(2)?
+        .and_then(|time| crate::datetime::parse_session_time(&time));
+    let result = row.get::<_, String>(3)?;
+    let result = result.parse().map_err(|err| {
+        rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(err))
+    })?;
+    let damaged_vehicles = row.get::<_, String>(22)?;
+
+    let report = BattleReport {
+        session_id,
+        #[cfg(feature = "timestamps")]
+        session_time,
+        result,
+        mission_name: row.get(4)?,
+        player_name: row.get(5)?,
+        squadron: row.get(6)?,
+        events: Vec::new(),
+        event_tables: Vec::new(),
+        awards: Vec::new(),
+        awards_header_count: row.get(7)?,
+        awards_header_total: reward(row, 8)?,
+        reward_for_winning: optional_reward(row, 11)?,
+        first_victory_bonus: optional_reward(row, 14)?,
+        other_awards: reward(row, 17)?,
+        completed_tasks: Vec::new(),
+        wagers: Vec::new(),
+        vehicles: Vec::new(),
+        crew_experience: Vec::new(),
+        activity: row.get(20)?,
+        activity_basis_points: row.get(21)?,
+        damaged_vehicles: damaged_vehicles
+            .split('\n')
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect(),
+        automatic_repair: row.get(23)?,
+        automatic_purchases: row.get(24)?,
+        vehicle_research: Vec::new(),
+        modification_research: Vec::new(),
+        squadron_research: row.get(25)?,
+        used_items: Vec::new(),
+        earned_rewards: reward(row, 26)?,
+        converted_research: row.get(29)?,
+        balance: reward(row, 30)?,
+    };
+    Ok((stored_session_id, report))
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use crate::*;
+
+    fn reports_in(dir: &str) -> Vec<BattleReport> {
+        from_directory(dir)
+            .unwrap()
+            .into_iter()
+            .map(|(_, report)| report.unwrap())
+            .collect()
+    }
+
+    fn reports() -> Vec<BattleReport> {
+        reports_in("./data")
+    }
+
+    #[rstest]
+    #[case("./data")]
+    #[case("./data/premium")]
+    fn round_trip(#[case] dir: &str) {
+        let reports = reports_in(dir);
+        assert!(!reports.is_empty());
+        for report in reports {
+            let mut store = BattleStore::open_in_memory().unwrap();
+            store.insert(&report).unwrap();
+            let stored = store.get(&report.session_id).unwrap();
+            assert_eq!(stored, Some(report));
+        }
+    }
+
+    #[test]
+    fn round_trip_award_golden_eagles() {
+        let input = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        let mut report = from_str(&input).unwrap();
+        report.awards[1].golden_eagles = 5;
+
+        let mut store = BattleStore::open_in_memory().unwrap();
+        store.insert(&report).unwrap();
+        assert_eq!(store.get(&report.session_id).unwrap(), Some(report));
+    }
+
+    #[test]
+    fn round_trip_squadron_research() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let mut report = from_str(&input).unwrap();
+        report.squadron_research = Some(680);
+
+        let mut store = BattleStore::open_in_memory().unwrap();
+        store.insert(&report).unwrap();
+        assert_eq!(store.get(&report.session_id).unwrap(), Some(report));
+    }
+
+    #[test]
+    fn round_trip_fractional_activity() {
+        let input = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+        let report = from_str(&input.replace("Activity: 91%", "Activity: 90,5 %")).unwrap();
+
+        let mut store = BattleStore::open_in_memory().unwrap();
+        store.insert(&report).unwrap();
+        assert_eq!(store.get(&report.session_id).unwrap(), Some(report));
+    }
+
+    #[cfg(feature = "timestamps")]
+    #[test]
+    fn round_trip_session_time() {
+        let report = from_path("./data/dated/1712a4f0003b2c5.report").unwrap();
+        assert!(report.session_time.is_some());
+
+        let mut store = BattleStore::open_in_memory().unwrap();
+        store.insert(&report).unwrap();
+        assert_eq!(store.get(&report.session_id).unwrap(), Some(report));
+    }
+
+    #[test]
+    fn round_trip_derived_session_id() {
+        let input = std::fs::read_to_string("./data/lenient/missing-session.report").unwrap();
+        let (report, _) = from_str_with_options(&input, ParseOptions::lenient()).unwrap();
+        let session_id = report.effective_session_id().into_owned();
+
+        let mut store = BattleStore::open_in_memory().unwrap();
+        store.insert(&report).unwrap();
+        store.insert(&report).unwrap();
+        assert_eq!(store.get(&session_id).unwrap(), Some(report.clone()));
+        assert_eq!(store.all().unwrap(), [report]);
+    }
+
+    #[test]
+    fn round_trip_squadron_research_points() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let mut report = from_str(&input).unwrap();
+        report.events[0].reward.squadron_research = 50;
+        report.event_tables[0].total.squadron_research = 50;
+        report.balance.squadron_research = 50;
+
+        let mut store = BattleStore::open_in_memory().unwrap();
+        store.insert(&report).unwrap();
+        assert_eq!(store.get(&report.session_id).unwrap(), Some(report));
+    }
+
+    #[test]
+    fn round_trip_other_currencies() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let mut report = from_str(&input).unwrap();
+        report.other_awards = report
+            .other_awards
+            .with_other_currency("WB", 15)
+            .with_other_currency("Holiday Toys", 3);
+        report.vehicles[0].reward = report.vehicles[0]
+            .reward
+            .clone()
+            .with_other_currency("WB", 5);
+
+        let mut store = BattleStore::open_in_memory().unwrap();
+        store.insert(&report).unwrap();
+        assert_eq!(store.get(&report.session_id).unwrap(), Some(report));
+    }
+
+    #[test]
+    fn round_trip_task_and_wager_rewards() {
+        let input = std::fs::read_to_string("./data/1712a4f0003b2c5-tasks.report").unwrap();
+        let mut report = from_str(&input).unwrap();
+        let wagers = std::fs::read_to_string("./data/1703c87000d01c0-wagers.report").unwrap();
+        report.wagers = from_str(&wagers).unwrap().wagers;
+        let task = &mut report.completed_tasks[0].reward;
+        *task = task
+            .clone()
+            .with_squadron_research(40)
+            .with_other_currency("WB", 10);
+        report.completed_tasks[0].convertible_research = 150;
+        let task = &mut report.completed_tasks[0].reward;
+        task.sl.base -= 100;
+        task.sl.bonuses.push((BonusKind::Booster, 100));
+        let wager = &mut report.wagers[0].reward;
+        *wager = wager.clone().with_other_currency("Holiday Toys", 3);
+        wager.sl.base -= 20;
+        wager.sl.bonuses.push((BonusKind::PremiumAccount, 20));
+
+        let mut store = BattleStore::open_in_memory().unwrap();
+        store.insert(&report).unwrap();
+        assert_eq!(store.get(&report.session_id).unwrap(), Some(report));
+    }
+
+    #[test]
+    fn insert_is_idempotent() {
+        let mut store = BattleStore::open_in_memory().unwrap();
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let report = from_str(&input).unwrap();
+        store.insert(&report).unwrap();
+        store.insert(&report).unwrap();
+        assert_eq!(store.all().unwrap(), [report]);
+    }
+
+    #[test]
+    fn query_by_result() {
+        let mut store = BattleStore::open_in_memory().unwrap();
+        for report in reports() {
+            store.insert(&report).unwrap();
+        }
+
+        let wins = store.query_by_result(BattleResult::Win).unwrap();
+        assert!(!wins.is_empty());
+        assert!(wins.iter().all(|report| report.result == BattleResult::Win));
+        let losses = store.query_by_result(BattleResult::Loss).unwrap();
+        assert!(losses.iter().any(|r| r.session_id == "1603c1c00028a36"));
+    }
+
+    #[test]
+    fn open_file() {
+        let path = std::env::temp_dir().join("wt-battle-report-store-test.sqlite");
+        let _ = std::fs::remove_file(&path);
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let report = from_str(&input).unwrap();
+
+        BattleStore::open(&path).unwrap().insert(&report).unwrap();
+        let stored = BattleStore::open(&path).unwrap().all().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(stored, [report]);
+    }
+}
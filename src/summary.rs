@@ -0,0 +1,117 @@
+//! Battle Summaries
+
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::BattleReport;
+
+/// Key figures of a battle, derived from its report
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BattleSummary {
+    /// Enemies destroyed by the player
+    pub total_kills: usize,
+    /// Silver lions on the earned line, before costs
+    pub total_sl_earned: u32,
+    /// Research points on the earned line
+    pub total_rp_earned: u32,
+    /// Silver lions earned minus repair and purchase costs
+    pub net_sl: i64,
+    /// The vehicle played for the longest time
+    pub most_used_vehicle: Option<String>,
+    /// See [`BattleReport::battle_duration`]
+    pub battle_duration_seconds: Option<u32>,
+    /// Kills per damaged vehicle, `None` if no vehicle was damaged
+    ///
+    /// Reports do not list deaths, so every vehicle that needed repairs
+    /// counts as one.
+    pub kill_death_ratio: Option<f32>,
+}
+
+impl BattleReport {
+    /// Compute the [`BattleSummary`] of this report
+    ///
+    /// The counts battle scores are computed from are in
+    /// [`BattleReport::score_inputs`].
+    pub fn summary(&self) -> BattleSummary {
+        let total_kills = self.total_kills();
+        let deaths = self.damaged_vehicles.len();
+
+        BattleSummary {
+            total_kills,
+            total_sl_earned: self.earned_rewards.silverlions(),
+            total_rp_earned: self.earned_rewards.research(),
+            net_sl: self.net_silverlions(),
+            most_used_vehicle: self
+                .vehicles
+                .iter()
+                .max_by_key(|vehicle| vehicle.time_played)
+                .map(|vehicle| vehicle.name.clone()),
+            battle_duration_seconds: self.battle_duration(),
+            kill_death_ratio: (deaths > 0).then(|| total_kills as f32 / deaths as f32),
+        }
+    }
+}
+
+impl fmt::Display for BattleSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Kills: {}", self.total_kills)?;
+        writeln!(
+            f,
+            "Earned: {} SL, {} RP",
+            self.total_sl_earned, self.total_rp_earned
+        )?;
+        write!(f, "Net: {} SL", self.net_sl)?;
+        if let Some(vehicle) = &self.most_used_vehicle {
+            write!(f, "\nMost used vehicle: {vehicle}")?;
+        }
+        if let Some(duration) = self.battle_duration_seconds {
+            write!(f, "\nDuration: {}:{:02}", duration / 60, duration % 60)?;
+        }
+        if let Some(ratio) = self.kill_death_ratio {
+            write!(f, "\nK/D: {ratio:.2}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn summary(path: &str) -> BattleSummary {
+        let input = std::fs::read_to_string(path).unwrap();
+        from_str(&input).unwrap().summary()
+    }
+
+    #[test]
+    fn summary_of_real_report() {
+        assert_eq!(
+            summary("./data/1712a4f0003b2c5.report"),
+            BattleSummary {
+                total_kills: 2,
+                total_sl_earned: 8815,
+                total_rp_earned: 1194,
+                net_sl: 5765,
+                most_used_vehicle: Some("Z-25".to_string()),
+                battle_duration_seconds: Some(12 * 60 + 30),
+                kill_death_ratio: Some(2.0),
+            }
+        );
+    }
+
+    #[test]
+    fn display_summary() {
+        assert_eq!(
+            summary("./data/1712a4f0003b2c5.report").to_string(),
+            "Kills: 2\n\
+            Earned: 8815 SL, 1194 RP\n\
+            Net: 5765 SL\n\
+            Most used vehicle: Z-25\n\
+            Duration: 12:30\n\
+            K/D: 2.00"
+        );
+    }
+}
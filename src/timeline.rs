@@ -0,0 +1,125 @@
+//! Battle Timeline
+//!
+//! Reports do not say when the player spawned, so the phases of a battle are
+//! inferred from its events. A phase starts with the first event in a vehicle
+//! other than the one of the event before it.
+
+use crate::{Award, BattleReport, Event};
+
+/// A stretch of the battle spent in one vehicle, see [`BattleReport::phases`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Phase<'a> {
+    pub vehicle: &'a str,
+    /// Seconds since the start of the battle of the first event in the phase
+    pub start: u32,
+}
+
+/// An event or award on the timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineItem<'a> {
+    Event(&'a Event),
+    Award(&'a Award),
+}
+
+/// An entry of [`BattleReport::timeline`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimelineEntry<'a> {
+    /// Seconds since the start of the battle
+    pub time: u32,
+    /// Whole minutes since the start of the battle
+    pub minute: u32,
+    /// Index of the phase in [`BattleReport::phases`], `None` if the report
+    /// has no events to infer the phases from
+    pub phase_index: Option<usize>,
+    pub item: TimelineItem<'a>,
+}
+
+impl BattleReport {
+    /// The phases of the battle in time order
+    ///
+    /// Empty if the report has no events.
+    pub fn phases(&self) -> Vec<Phase<'_>> {
+        let mut events = self.events.iter().collect::<Vec<_>>();
+        events.sort_by_key(|event| event.time);
+        events
+            .chunk_by(|a, b| a.vehicle == b.vehicle)
+            .map(|events| Phase {
+                vehicle: &events[0].vehicle,
+                start: events[0].time,
+            })
+            .collect()
+    }
+
+    /// Every event and award in time order with the minute and the phase it
+    /// falls in
+    ///
+    /// Anything before the first event belongs to the first phase.
+    pub fn timeline(&self) -> Vec<TimelineEntry<'_>> {
+        let phases = self.phases();
+        let mut entries = self
+            .events
+            .iter()
+            .map(|event| (event.time, TimelineItem::Event(event)))
+            .chain(
+                self.awards
+                    .iter()
+                    .map(|award| (award.time, TimelineItem::Award(award))),
+            )
+            .map(|(time, item)| TimelineEntry {
+                time,
+                minute: time / 60,
+                phase_index: phase_index(&phases, time),
+                item,
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.time);
+        entries
+    }
+}
+
+/// the index of the phase at `time`, `None` without phases
+pub(crate) fn phase_index(phases: &[Phase], time: u32) -> Option<usize> {
+    if phases.is_empty() {
+        return None;
+    }
+    Some(
+        phases
+            .partition_point(|phase| phase.start <= time)
+            .saturating_sub(1),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn phases_of_real_report() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let report = from_str(&input).unwrap();
+        let phases = report
+            .phases()
+            .iter()
+            .map(|phase| (phase.vehicle, phase.start))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            phases,
+            [
+                ("Concept 3", 2 * 60 + 5),
+                ("Wyvern S4", 10 * 60 + 7),
+                ("Sherman Firefly", 11 * 60 + 47)
+            ]
+        );
+    }
+
+    #[test]
+    fn timeline_without_events() {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        let mut report = from_str(&input).unwrap();
+        report.events.clear();
+        assert_eq!(report.phases(), []);
+        let timeline = report.timeline();
+        assert_eq!(timeline.len(), report.awards.len());
+        assert!(timeline.iter().all(|entry| entry.phase_index.is_none()));
+    }
+}
@@ -0,0 +1,141 @@
+//! Absolute Event Times
+//!
+//! Event and award times in reports count seconds since the start of the
+//! battle. Given the wall-clock time the battle started, a
+//! [`TimestampedReport`] pairs every event and award with its absolute time,
+//! for example to join them with recorded video or telemetry.
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{datetime, Award, BattleReport, Event, UtcDateTime};
+
+/// A time in the battle does not fit into a [`UtcDateTime`] after the start
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{seconds} seconds after {start} are out of range")]
+pub struct TimeOffsetError {
+    pub start: UtcDateTime,
+    pub seconds: u32,
+}
+
+/// The events and awards of a report with absolute timestamps, see
+/// [`BattleReport::with_time_offset`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TimestampedReport<'a> {
+    pub session_id: &'a str,
+    /// Wall-clock time the battle started
+    #[cfg_attr(feature = "serde", serde(serialize_with = "datetime::serialize_utc"))]
+    pub start: UtcDateTime,
+    pub events: Vec<TimestampedEvent<'a>>,
+    pub awards: Vec<TimestampedAward<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TimestampedEvent<'a> {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "datetime::serialize_utc"))]
+    pub timestamp: UtcDateTime,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub event: &'a Event,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TimestampedAward<'a> {
+    #[cfg_attr(feature = "serde", serde(serialize_with = "datetime::serialize_utc"))]
+    pub timestamp: UtcDateTime,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub award: &'a Award,
+}
+
+impl BattleReport {
+    /// Pair every event and award with its time after `start`
+    ///
+    /// Times in reports are never negative, so this only fails if a time
+    /// after `start` cannot be represented.
+    pub fn with_time_offset(
+        &self,
+        start: UtcDateTime,
+    ) -> Result<TimestampedReport<'_>, TimeOffsetError> {
+        let timestamp = |seconds: u32| {
+            datetime::add_seconds(start, seconds).ok_or(TimeOffsetError { start, seconds })
+        };
+        Ok(TimestampedReport {
+            session_id: &self.session_id,
+            start,
+            events: self
+                .events
+                .iter()
+                .map(|event| {
+                    Ok(TimestampedEvent {
+                        timestamp: timestamp(event.time)?,
+                        event,
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+            awards: self
+                .awards
+                .iter()
+                .map(|award| {
+                    Ok(TimestampedAward {
+                        timestamp: timestamp(award.time)?,
+                        award,
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{datetime::utc, datetime::UTC_MAX, *};
+
+    fn report() -> BattleReport {
+        let input = std::fs::read_to_string("./data/1603c1c00028a36.report").unwrap();
+        from_str(&input).unwrap()
+    }
+
+    #[test]
+    fn events_at_absolute_times() {
+        let report = report();
+        let start = utc((2023, 8, 3), (19, 30, 0));
+        let timestamped = report.with_time_offset(start).unwrap();
+        assert_eq!(timestamped.events.len(), report.events.len());
+        assert_eq!(timestamped.awards.len(), report.awards.len());
+
+        let timestamps = timestamped
+            .events
+            .iter()
+            .map(|event| event.timestamp)
+            .chain(timestamped.awards.iter().map(|award| award.timestamp));
+        // the Concept 3 scouted an M36 GMC at 2:05, the last awards were
+        // handed out at 13:55
+        assert_eq!(
+            timestamps.clone().min(),
+            Some(utc((2023, 8, 3), (19, 32, 5)))
+        );
+        assert_eq!(timestamps.max(), Some(utc((2023, 8, 3), (19, 43, 55))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn timestamps_are_serialized_next_to_the_event() {
+        let report = report();
+        let start = utc((2023, 8, 3), (19, 30, 0));
+        let value = serde_json::to_value(report.with_time_offset(start).unwrap()).unwrap();
+        assert_eq!(value["start"], "2023-08-03T19:30:00Z");
+        assert_eq!(value["events"][0]["timestamp"], "2023-08-03T19:40:34Z");
+        assert_eq!(value["events"][0]["time"], 10 * 60 + 34);
+        assert_eq!(value["events"][0]["vehicle"], "Wyvern S4");
+    }
+
+    #[test]
+    fn overflowing_offset_is_an_error() {
+        let report = report();
+        let err = report.with_time_offset(UTC_MAX).unwrap_err();
+        assert_eq!(err.start, UTC_MAX);
+        assert_eq!(err.seconds, report.events[0].time);
+    }
+}
@@ -0,0 +1,21 @@
+//! Browser Interface
+//!
+//! Parses reports in the browser with the `wasm` feature. Build the package
+//! with `wasm-pack build --target web -- --features wasm` and import
+//! `parse_report` from the generated module.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::from_str;
+
+/// Parse a report into the object its JSON describes
+///
+/// The object has the same shape as the output of `wt-battle-report --json`.
+/// Throws an `Error` with the message of the parse error if the report is
+/// invalid.
+#[wasm_bindgen]
+pub fn parse_report(input: &str) -> Result<JsValue, JsError> {
+    let report = from_str(input).map_err(|err| JsError::new(&err.to_string()))?;
+    Ok(report.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
@@ -0,0 +1,61 @@
+//! Counts the allocations of owned and borrowed parsing
+//!
+//! This lives in its own test binary because it replaces the global allocator.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Number of allocations made by `f`
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+#[test]
+fn borrowed_parsing_allocates_less() {
+    let reports = [
+        "./data/1603c1c00028a36.report",
+        "./data/160409b0002a1af.report",
+        "./data/161878b0007a4c8.report",
+        "./data/16dae2c0000e753.report",
+        "./data/1703c87000d01c0-repairs.report",
+        "./data/1712a4f0003b2c5.report",
+        "./data/1714b3e0001c7a2.report",
+    ]
+    .map(|path| std::fs::read_to_string(path).unwrap());
+    let reports = (0..100).flat_map(|_| &reports).collect::<Vec<_>>();
+
+    let owned = allocations(|| {
+        for report in &reports {
+            wt_battle_report::from_str(report).unwrap();
+        }
+    });
+    let borrowed = allocations(|| {
+        for report in &reports {
+            wt_battle_report::from_str_borrowed(report).unwrap();
+        }
+    });
+
+    assert!(borrowed < owned, "owned: {owned}, borrowed: {borrowed}");
+}
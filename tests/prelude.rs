@@ -0,0 +1,59 @@
+//! Typical uses of the crate that only import the prelude
+
+use wt_battle_report::prelude::*;
+
+fn read(name: &str) -> String {
+    std::fs::read_to_string(format!("./data/{name}")).unwrap()
+}
+
+#[test]
+fn parse_and_summarize() {
+    let report: BattleReport = from_str(&read("1712a4f0003b2c5.report")).unwrap();
+    assert_eq!(report.result, BattleResult::Win);
+
+    let inputs: ScoreInputs = report.score_inputs();
+    assert!(inputs.kills > 0);
+    let _: f64 = inputs.score(&ScoreFormula::default());
+    let _: BattleSummary = report.summary();
+    let kills = report
+        .events
+        .iter()
+        .filter(|event: &&Event| event.kind == EventKind::DestructionOfFleets)
+        .count();
+    assert_eq!(kills, inputs.kills as usize);
+}
+
+#[test]
+fn parse_with_options_and_handle_errors() {
+    let options = ParseOptions::lenient().with_language(Language::English);
+    let (report, _warnings) =
+        from_str_with_options(&read("1603c1c00028a36.report"), options).unwrap();
+    let total: &Reward = &report.earned_rewards;
+    assert!(total.silverlions() > 0);
+
+    let err: Error = from_str("not a report").unwrap_err();
+    assert!(!err.to_string().is_empty());
+    let err: PathError = from_path("./data/missing.report").unwrap_err();
+    assert!(matches!(err.source, ReadError::Io(_)));
+}
+
+#[test]
+fn aggregate_reports() {
+    let reports = ["1603c1c00028a36.report", "1712a4f0003b2c5.report"]
+        .map(|name| from_slice(read(name).as_bytes()).unwrap());
+    let stats = AggregateStats::from_reports(&reports);
+    assert_eq!(stats.total_battles, 2);
+    let session = SessionStats::from_reports(&reports);
+    assert_eq!(session.battles, 2);
+}
+
+#[test]
+fn build_a_report() {
+    let report = BattleReportBuilder::new()
+        .with_session_id("1712a4f0003b2c5")
+        .with_result(BattleResult::Draw)
+        .add_vehicle(Vehicle::new("Z-25").with_reward(Reward::new(100, 10)))
+        .build()
+        .unwrap();
+    assert_eq!(report.vehicles.len(), 1);
+}
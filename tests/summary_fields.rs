@@ -0,0 +1,30 @@
+//! Reads back the summary fields of a real report through the public API
+
+use wt_battle_report::{from_str, BattleResult};
+
+#[test]
+fn summary_fields_of_a_won_battle() {
+    let input = std::fs::read_to_string("./data/1712a4f0003b2c5.report").unwrap();
+    let report = from_str(&input).unwrap();
+
+    assert_eq!(report.result, BattleResult::Win);
+    assert_eq!(
+        report
+            .reward_for_winning
+            .as_ref()
+            .map(|reward| reward.silverlions()),
+        Some(2100)
+    );
+    assert_eq!(report.earned_rewards.silverlions(), 8815);
+    assert_eq!(report.earned_rewards.research(), 1194);
+    assert_eq!(report.activity, 91);
+    assert_eq!(report.automatic_repair, 2650);
+    assert_eq!(report.automatic_purchases, 400);
+    assert_eq!(report.repair_cost(), -2650);
+    assert_eq!(report.purchase_cost(), -400);
+    assert_eq!(report.net_silverlions(), 5765);
+    assert_eq!(
+        i64::from(report.balance.silverlions()),
+        report.net_silverlions()
+    );
+}